@@ -0,0 +1,102 @@
+//! 无界面命令行工具，用于CI等环境批量生成报告
+//!
+//! 用法：`report-forge-cli <config.json>`，配置文件即 `ReportConfig` 的JSON序列化结果
+//! （与 `save_config_preset` 保存的预设格式相同）。
+//! 中断后可用 `report-forge-cli --resume <checkpoint.json>` 从断点文件继续生成。
+
+use report_forge_lib::models::ReportConfig;
+use report_forge_lib::processors::{self, ExcelProcessor};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().collect();
+    let outcome = match args.as_slice() {
+        [_, config_path] => run(config_path),
+        [_, flag, checkpoint_path] if flag == "--resume" => resume(checkpoint_path),
+        _ => {
+            eprintln!("用法: {} <config.json>", args[0]);
+            eprintln!("      {} --resume <checkpoint.json>", args[0]);
+            return ExitCode::from(2);
+        }
+    };
+
+    match outcome {
+        Ok(output_files) => {
+            for output_file in output_files {
+                println!("报告生成成功！文件: {}", output_file);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("报告生成失败: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(config_path: &str) -> anyhow::Result<Vec<String>> {
+    let config = ReportConfig::load_preset(config_path)?;
+    config.validate()?;
+
+    let checkpoint = if let Some(checkpoint_file) = &config.checkpoint_file {
+        println!(
+            "[1/3] 正在流式读取 {} 个Excel文件（断点文件: {}）...",
+            config.excel_files.len(),
+            checkpoint_file
+        );
+        let checkpoint = processors::load_checkpoint(checkpoint_file);
+        match &checkpoint {
+            Some(checkpoint) if checkpoint.matches_inputs(&config) => println!(
+                "      从断点继续，已处理 {} 个文件",
+                checkpoint.progress.files_done
+            ),
+            Some(_) => eprintln!("警告: 断点保存后输入文件或处理选项已变化，忽略断点并重新处理"),
+            None => {}
+        }
+        checkpoint
+    } else if config.streaming_read {
+        println!(
+            "[1/3] 正在流式读取 {} 个Excel文件...",
+            config.excel_files.len()
+        );
+        None
+    } else {
+        println!("[1/3] 正在合并 {} 个Excel文件...", config.excel_files.len());
+        None
+    };
+    let processed_result =
+        processors::read_and_process(&config, checkpoint, &mut |done, total| {
+            println!("      已读取 {}/{} 个文件", done, total);
+            if done == total {
+                println!("[2/3] 正在处理数据（去重、分组）...");
+            }
+        })?;
+    for skipped in &processed_result.skipped_files {
+        eprintln!(
+            "警告: 已跳过读取失败的文件 {}: {}",
+            skipped.file, skipped.reason
+        );
+    }
+    if let Some(warning) = ExcelProcessor::check_result_severity(&processed_result, &config.process)
+    {
+        eprintln!("警告: {}", warning);
+    }
+    println!(
+        "      处理完成，共 {} 条记录，{} 个分组",
+        processed_result.total_records, processed_result.total_groups
+    );
+
+    println!("[3/3] 正在生成{}文档...", config.output_format.label());
+    let output_files = processors::generate_reports(&config, &processed_result, |_, _| {})?;
+    if let Some(checkpoint_file) = &config.checkpoint_file {
+        processors::remove_checkpoint(checkpoint_file);
+    }
+    Ok(output_files)
+}
+
+fn resume(checkpoint_path: &str) -> anyhow::Result<Vec<String>> {
+    println!("正在从断点文件继续生成: {}", checkpoint_path);
+    processors::resume_generation(checkpoint_path, |_, _| {})
+}
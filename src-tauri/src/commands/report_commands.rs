@@ -1,23 +1,155 @@
-use crate::models::{ExcelProcessResult, LogLevel, LogMessage, ProgressInfo, ReportConfig};
-use crate::processors::{ExcelProcessor, WordGenerator};
-use anyhow::Result;
+use crate::error::ReportError;
+use crate::models::{
+    ExcelProcessResult, ExcelValidationReport, GenerationCheckpoint, HeaderMatchMode, LogLevel,
+    LogMessage, MergedDataPreview, ProcessOptions, ProgressInfo, ReportConfig, ReportEstimate,
+    ReportMetrics, SectionPreview, SkippedFile, WorkbookInfo,
+};
+use crate::processors::excel_processor::RawExcelData;
+use crate::processors::{self, ExcelProcessor, TextGenerator, WordGenerator};
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use tauri::ipc::Response;
 use tauri::State;
 
+/// 内存中保留的日志条数上限（默认值）
+const DEFAULT_LOG_CAPACITY: usize = 5000;
+
+/// 日志文件轮转大小（字节）
+const MAX_LOG_FILE_BYTES: u64 = 1024 * 1024;
+
+/// 轮转时保留的历史日志文件数量（app.log.1 ~ app.log.3）
+const MAX_LOG_BACKUPS: usize = 3;
+
+/// 合并预览返回的数据行数上限
+const MERGE_PREVIEW_ROWS: usize = 100;
+
 /// 应用状态
 pub struct AppState {
-    pub logs: Mutex<Vec<LogMessage>>,
+    pub logs: Mutex<VecDeque<LogMessage>>,
+    /// 超出上限被丢弃的最早日志数量
+    pub dropped_logs: Mutex<usize>,
+    log_capacity: usize,
     pub progress: Mutex<Option<ProgressInfo>>,
+    pub log_file: Mutex<Option<LogFile>>,
+    /// 是否有报告正在生成，防止重复点击时并发生成
+    generating: AtomicBool,
+}
+
+/// 报告生成占用标记，离开作用域时（完成或出错）自动释放
+pub struct GenerationGuard<'a> {
+    generating: &'a AtomicBool,
+}
+
+impl Drop for GenerationGuard<'_> {
+    fn drop(&mut self) {
+        self.generating.store(false, Ordering::Release);
+    }
+}
+
+/// 日志文件：每条日志追加写入并立即刷新，超过大小后轮转
+pub struct LogFile {
+    path: PathBuf,
+    max_bytes: u64,
+}
+
+impl LogFile {
+    /// 打开（必要时创建）日志文件，确认可写入
+    pub fn open(path: PathBuf, max_bytes: u64) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("无法创建日志目录: {:?}", parent))?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("无法打开日志文件: {:?}", path))?;
+
+        Ok(Self { path, max_bytes })
+    }
+
+    /// 追加一条日志，写入前检查是否需要轮转
+    fn append(&self, log: &LogMessage) -> Result<()> {
+        self.rotate_if_needed()?;
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("无法打开日志文件: {:?}", self.path))?;
+        writeln!(
+            file,
+            "[{}] [{}] {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            log.level.label(),
+            log.message
+        )?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// 文件超过大小上限时依次重命名为 .1、.2……，最旧的被覆盖
+    fn rotate_if_needed(&self) -> Result<()> {
+        let size = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return Ok(()),
+        };
+        if size < self.max_bytes {
+            return Ok(());
+        }
+
+        for index in (1..MAX_LOG_BACKUPS).rev() {
+            let from = self.backup_path(index);
+            if from.exists() {
+                std::fs::rename(&from, self.backup_path(index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.backup_path(1))
+            .with_context(|| format!("无法轮转日志文件: {:?}", self.path))?;
+
+        Ok(())
+    }
+
+    fn backup_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
 }
 
 impl AppState {
     pub fn new() -> Self {
+        Self::with_log_capacity(DEFAULT_LOG_CAPACITY)
+    }
+
+    /// 指定内存日志条数上限，超出时丢弃最早的日志
+    pub fn with_log_capacity(log_capacity: usize) -> Self {
         Self {
-            logs: Mutex::new(Vec::new()),
+            logs: Mutex::new(VecDeque::new()),
+            dropped_logs: Mutex::new(0),
+            log_capacity,
             progress: Mutex::new(None),
+            log_file: Mutex::new(None),
+            generating: AtomicBool::new(false),
         }
     }
 
+    /// 开始生成报告，已有报告正在生成时返回 Busy
+    pub fn begin_generation(&self) -> Result<GenerationGuard<'_>, ReportError> {
+        self.generating
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .map_err(|_| ReportError::Busy)?;
+
+        Ok(GenerationGuard {
+            generating: &self.generating,
+        })
+    }
+
     pub fn add_log(&self, level: LogLevel, message: String) {
         let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
         let log = LogMessage {
@@ -26,24 +158,59 @@ impl AppState {
             timestamp,
         };
 
+        // 写入日志文件失败不影响命令执行
+        if let Ok(log_file) = self.log_file.lock() {
+            if let Some(file) = log_file.as_ref() {
+                if let Err(e) = file.append(&log) {
+                    log::warn!("写入日志文件失败: {:#}", e);
+                }
+            }
+        }
+
         if let Ok(mut logs) = self.logs.lock() {
-            logs.push(log);
+            logs.push_back(log);
+
+            let overflow = logs.len().saturating_sub(self.log_capacity);
+            if overflow > 0 {
+                logs.drain(..overflow);
+                if let Ok(mut dropped) = self.dropped_logs.lock() {
+                    *dropped += overflow;
+                }
+            }
         }
     }
 
-    pub fn update_progress(&self, current: usize, total: usize, message: String) {
-        let percentage = if total > 0 {
-            (current as f32 / total as f32) * 100.0
-        } else {
-            0.0
-        };
+    /// 返回严重程度不低于 min_level 的日志
+    pub fn logs_at_least(&self, min_level: &LogLevel) -> Vec<LogMessage> {
+        self.logs
+            .lock()
+            .map(|logs| {
+                logs.iter()
+                    .filter(|log| log.level.severity() >= min_level.severity())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
-        let progress = ProgressInfo {
-            current,
-            total,
-            message,
-            percentage,
-        };
+    /// 设置日志文件，传入 None 时停止写入文件
+    pub fn set_log_file(&self, log_file: Option<LogFile>) {
+        if let Ok(mut current) = self.log_file.lock() {
+            *current = log_file;
+        }
+    }
+
+    /// 更新进度（百分比由调用方计算）；百分比超出 0~100 说明步数计算有误，记录警告后限制在该范围内
+    pub fn update_progress(&self, mut progress: ProgressInfo) {
+        if !(0.0..=100.0).contains(&progress.percentage) {
+            log::warn!(
+                "进度百分比超出范围: {}（{}/{}）",
+                progress.percentage,
+                progress.current,
+                progress.total
+            );
+            progress.percentage = progress.percentage.clamp(0.0, 100.0);
+        }
 
         if let Ok(mut prog) = self.progress.lock() {
             *prog = Some(progress);
@@ -54,6 +221,9 @@ impl AppState {
         if let Ok(mut logs) = self.logs.lock() {
             logs.clear();
         }
+        if let Ok(mut dropped) = self.dropped_logs.lock() {
+            *dropped = 0;
+        }
     }
 
     pub fn clear_progress(&self) {
@@ -69,15 +239,23 @@ impl Default for AppState {
     }
 }
 
-/// 处理Excel文件
+/// 记录错误日志并转换为返回给前端的结构化错误
+fn command_error(state: &AppState, context: &str, error: anyhow::Error) -> ReportError {
+    state.add_log(LogLevel::Error, format!("{}: {:#}", context, error));
+    ReportError::from_anyhow(context, error)
+}
+
+/// 处理Excel文件，按 options 去重、分组（未传入时使用默认选项）
 #[tauri::command]
 pub async fn process_excel_file(
     file_path: String,
+    options: Option<ProcessOptions>,
     state: State<'_, AppState>,
-) -> Result<ExcelProcessResult, String> {
+) -> Result<ExcelProcessResult, ReportError> {
     state.add_log(LogLevel::Info, format!("开始处理Excel文件: {}", file_path));
 
-    match ExcelProcessor::process_excel_to_json(&file_path) {
+    let options = options.unwrap_or_default();
+    match ExcelProcessor::process_excel_to_json(&file_path, &options) {
         Ok(result) => {
             state.add_log(
                 LogLevel::Success,
@@ -86,13 +264,118 @@ pub async fn process_excel_file(
                     result.total_records, result.total_groups
                 ),
             );
+            warn_unrecognized_severity(&state, &options, &result);
             Ok(result)
         }
-        Err(e) => {
-            let error_msg = format!("Excel处理失败: {}", e);
-            state.add_log(LogLevel::Error, error_msg.clone());
-            Err(error_msg)
+        Err(e) => Err(command_error(&state, "Excel处理失败", e)),
+    }
+}
+
+/// 处理前端传回的原始数据（如在预览中编辑后的表格），按配置去重、分组
+#[tauri::command]
+pub async fn process_raw_dto(
+    data: RawExcelData,
+    config: ReportConfig,
+    state: State<'_, AppState>,
+) -> Result<ExcelProcessResult, ReportError> {
+    state.add_log(
+        LogLevel::Info,
+        format!("开始处理编辑后的数据：共 {} 行", data.rows.len()),
+    );
+
+    match ExcelProcessor::process_raw_data(data, &config.process) {
+        Ok(result) => {
+            state.add_log(
+                LogLevel::Success,
+                format!(
+                    "数据处理成功！共 {} 条记录，{} 个分组",
+                    result.total_records, result.total_groups
+                ),
+            );
+            warn_unrecognized_severity(&state, &config.process, &result);
+            Ok(result)
+        }
+        Err(e) => Err(command_error(&state, "数据处理失败", e)),
+    }
+}
+
+/// 校验Excel文件（试运行，不生成报告），严重性级别按 options 识别（未传入时使用默认选项）
+#[tauri::command]
+pub async fn validate_excel_file(
+    file_path: String,
+    options: Option<ProcessOptions>,
+    state: State<'_, AppState>,
+) -> Result<ExcelValidationReport, ReportError> {
+    state.add_log(LogLevel::Info, format!("开始校验Excel文件: {}", file_path));
+
+    let report = ExcelProcessor::validate_excel_file(&file_path, &options.unwrap_or_default());
+    if report.valid {
+        state.add_log(
+            LogLevel::Success,
+            format!(
+                "Excel校验通过！共 {} 列，{} 行数据",
+                report.column_count, report.data_row_count
+            ),
+        );
+    } else {
+        state.add_log(
+            LogLevel::Warning,
+            format!("Excel校验未通过: {}", report.errors.join("；")),
+        );
+    }
+    for warning in &report.warnings {
+        state.add_log(LogLevel::Warning, warning.clone());
+    }
+
+    Ok(report)
+}
+
+/// 列出工作簿中各工作表的名称、表头及行数（不运行处理流程），供列映射选择
+#[tauri::command]
+pub async fn inspect_workbook(
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<WorkbookInfo, ReportError> {
+    match ExcelProcessor::inspect_workbook(&file_path) {
+        Ok(info) => {
+            state.add_log(
+                LogLevel::Info,
+                format!(
+                    "读取工作簿 {}：共 {} 个工作表",
+                    file_path,
+                    info.sheets.len()
+                ),
+            );
+            Ok(info)
+        }
+        Err(e) => Err(command_error(&state, "读取工作簿失败", e)),
+    }
+}
+
+/// 合并Excel文件并返回表头、前100行及总行数（不去重、不分组），供生成前确认合并结果
+#[tauri::command]
+pub async fn merge_and_preview(
+    files: Vec<String>,
+    header_match_mode: Option<HeaderMatchMode>,
+    state: State<'_, AppState>,
+) -> Result<MergedDataPreview, ReportError> {
+    let options = ProcessOptions {
+        header_match_mode: header_match_mode.unwrap_or_default(),
+        ..ProcessOptions::default()
+    };
+    match ExcelProcessor::merge_excel_files(&files, &options) {
+        Ok(merged) => {
+            state.add_log(
+                LogLevel::Info,
+                format!(
+                    "合并预览：{} 个文件，共 {} 行数据",
+                    files.len(),
+                    merged.rows.len()
+                ),
+            );
+            Ok(merged.preview(MERGE_PREVIEW_ROWS))
         }
+        Err(e) => Err(command_error(&state, "合并Excel文件失败", e)),
     }
 }
 
@@ -101,123 +384,745 @@ pub async fn process_excel_file(
 pub async fn generate_report(
     config: ReportConfig,
     state: State<'_, AppState>,
-) -> Result<String, String> {
+) -> Result<String, ReportError> {
+    let _generation = state.begin_generation()?;
     state.add_log(LogLevel::Info, "开始生成报告...".to_string());
     state.clear_progress();
 
-    // 先合并所有Excel文件
-    state.update_progress(
-        1,
-        3,
-        format!("正在合并 {} 个Excel文件...", config.excel_files.len()),
-    );
+    reject_split_by_severity(&config, &state)?;
+    let (processed_result, mut progress) = load_for_generation(&config, &state, true, None)?;
+    write_report(&config, &processed_result, &state, &mut progress)
+}
+
+/// 生成报告的命令共用：校验配置（check_output 为 false 时不检查输出目录及文件名模板），
+/// 再读取并处理Excel文件（设置 checkpoint_file 时断点续读，设置 streaming_read 时流式读取）
+///
+/// checkpoint 为调用方已读取的断点（如 resume_generation），为 None 时从 checkpoint_file 读取
+fn load_for_generation<'a>(
+    config: &ReportConfig,
+    state: &'a AppState,
+    check_output: bool,
+    checkpoint: Option<GenerationCheckpoint>,
+) -> Result<(ExcelProcessResult, GenerationProgress<'a>), ReportError> {
+    let validation = if check_output {
+        config.validate()
+    } else {
+        config.validate_without_output()
+    };
+    if let Err(e) = validation {
+        let error = ReportError::InvalidConfig {
+            message: e.to_string(),
+        };
+        state.add_log(LogLevel::Error, error.to_string());
+        return Err(error);
+    }
 
+    let mut progress = GenerationProgress::new(state, config.excel_files.len());
+    let checkpoint = checkpoint.or_else(|| {
+        config
+            .checkpoint_file
+            .as_deref()
+            .and_then(processors::load_checkpoint)
+    });
+    let processed_result = read_excel_files(config, checkpoint, state, &mut progress)?;
+    Ok((processed_result, progress))
+}
+
+/// 从断点文件继续生成报告（使用断点中保存的配置），返回输出文件路径
+#[tauri::command]
+pub async fn resume_generation(
+    checkpoint_path: String,
+    state: State<'_, AppState>,
+) -> Result<String, ReportError> {
+    let _generation = state.begin_generation()?;
     state.add_log(
         LogLevel::Info,
-        format!("准备合并 {} 个Excel文件", config.excel_files.len()),
+        format!("从断点继续生成报告: {}", checkpoint_path),
     );
+    state.clear_progress();
+
+    let checkpoint = match GenerationCheckpoint::load(&checkpoint_path) {
+        Ok(checkpoint) => checkpoint,
+        Err(e) => return Err(command_error(&state, "读取断点文件失败", e)),
+    };
+    let config = ReportConfig {
+        checkpoint_file: Some(checkpoint_path.clone()),
+        ..checkpoint.config.clone()
+    };
+
+    reject_split_by_severity(&config, &state)?;
+    let (processed_result, mut progress) =
+        load_for_generation(&config, &state, true, Some(checkpoint))?;
+    write_report(&config, &processed_result, &state, &mut progress)
+}
+
+/// 只返回一个文件路径的命令不支持 split_by_severity（应调用 generate_reports_by_severity）
+fn reject_split_by_severity(config: &ReportConfig, state: &AppState) -> Result<(), ReportError> {
+    if !config.split_by_severity {
+        return Ok(());
+    }
+    let error = ReportError::InvalidConfig {
+        message: "按风险等级分别生成报告请使用 generate_reports_by_severity".to_string(),
+    };
+    state.add_log(LogLevel::Error, error.to_string());
+    Err(error)
+}
+
+/// 按输出格式生成文档，成功后删除断点文件（设置了 checkpoint_file 时）
+fn write_report(
+    config: &ReportConfig,
+    processed_result: &ExcelProcessResult,
+    state: &AppState,
+    progress: &mut GenerationProgress,
+) -> Result<String, ReportError> {
+    warn_unreadable_appendix(state, config);
+    let label = config.output_format.label();
+    let on_progress = |done, total| progress.section_done(label, done, total);
 
-    // 合并所有Excel文件，验证表头一致性
-    let merged_data = match ExcelProcessor::merge_excel_files(&config.excel_files) {
-        Ok(data) => {
+    match processors::generate_report(config, processed_result, on_progress) {
+        Ok(output_file) => {
             state.add_log(
                 LogLevel::Success,
-                format!("Excel文件合并成功！共 {} 行数据", data.rows.len()),
+                format!("报告生成成功！文件: {}", output_file),
             );
-            data
+            if let Some(checkpoint_file) = &config.checkpoint_file {
+                processors::remove_checkpoint(checkpoint_file);
+            }
+            progress.finished();
+            Ok(output_file)
         }
         Err(e) => {
-            let error_msg = format!("合并Excel文件失败: {}", e);
-            state.add_log(LogLevel::Error, error_msg.clone());
-            return Err(error_msg);
+            let context = format!("生成{}文档失败", config.output_format.label());
+            Err(command_error(state, &context, e))
         }
-    };
+    }
+}
 
-    // 处理合并后的数据（去重、分组）
-    state.update_progress(
-        2,
-        3,
-        "正在处理数据（去重、分组）...".to_string(),
+/// 生成Word报告并以二进制响应返回docx文件内容，不写入输出目录（不需要 output_dir）
+#[tauri::command]
+pub async fn generate_report_bytes(
+    config: ReportConfig,
+    state: State<'_, AppState>,
+) -> Result<Response, ReportError> {
+    let _generation = state.begin_generation()?;
+    state.add_log(
+        LogLevel::Info,
+        "开始生成报告（返回文件内容）...".to_string(),
     );
+    state.clear_progress();
 
-    state.add_log(LogLevel::Info, "开始处理合并后的数据...".to_string());
+    reject_split_by_severity(&config, &state)?;
+    let (processed_result, mut progress) = load_for_generation(&config, &state, false, None)?;
 
-    let processed_result = match ExcelProcessor::process_raw_data(merged_data) {
-        Ok(result) => {
+    warn_unreadable_appendix(&state, &config);
+    let on_progress = |done, total| progress.section_done("Word", done, total);
+
+    match processors::generate_report_bytes(&config, &processed_result, on_progress) {
+        Ok(bytes) => {
+            state.add_log(
+                LogLevel::Success,
+                format!("报告生成成功！大小: {} 字节", bytes.len()),
+            );
+            if let Some(checkpoint_file) = &config.checkpoint_file {
+                processors::remove_checkpoint(checkpoint_file);
+            }
+            progress.finished();
+            Ok(Response::new(bytes))
+        }
+        Err(e) => Err(command_error(&state, "生成Word文档失败", e)),
+    }
+}
+
+/// 按风险等级分别生成报告，返回各输出文件路径
+#[tauri::command]
+pub async fn generate_reports_by_severity(
+    config: ReportConfig,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, ReportError> {
+    let _generation = state.begin_generation()?;
+    state.add_log(LogLevel::Info, "开始按风险等级生成报告...".to_string());
+    state.clear_progress();
+
+    let (processed_result, mut progress) = load_for_generation(&config, &state, true, None)?;
+
+    warn_unreadable_appendix(&state, &config);
+    let label = config.output_format.label();
+    let on_progress = |done, total| progress.section_done(label, done, total);
+
+    match processors::generate_reports_by_severity(&config, &processed_result, on_progress) {
+        Ok(output_files) => {
+            for output_file in &output_files {
+                state.add_log(
+                    LogLevel::Success,
+                    format!("报告生成成功！文件: {}", output_file),
+                );
+            }
+            if let Some(checkpoint_file) = &config.checkpoint_file {
+                processors::remove_checkpoint(checkpoint_file);
+            }
+            progress.finished();
+            Ok(output_files)
+        }
+        Err(e) => {
+            let context = format!("按风险等级生成{}文档失败", config.output_format.label());
+            Err(command_error(&state, &context, e))
+        }
+    }
+}
+
+/// 统计处理指标（读取、合并、去重、分组，不生成报告）
+#[tauri::command]
+pub async fn compute_metrics(
+    config: ReportConfig,
+    state: State<'_, AppState>,
+) -> Result<ReportMetrics, ReportError> {
+    state.add_log(LogLevel::Info, "开始统计处理指标...".to_string());
+
+    match processors::compute_metrics(&config) {
+        Ok(metrics) => {
             state.add_log(
                 LogLevel::Success,
                 format!(
-                    "数据处理成功！共 {} 条记录，{} 个分组",
-                    result.total_records, result.total_groups
+                    "处理指标统计完成！{} 个文件，{} 行数据，{} 个分组",
+                    metrics.total_files, metrics.total_raw_rows, metrics.total_groups
                 ),
             );
-            result
+            Ok(metrics)
         }
-        Err(e) => {
-            let error_msg = format!("数据处理失败: {}", e);
-            state.add_log(LogLevel::Error, error_msg.clone());
-            return Err(error_msg);
+        Err(e) => Err(command_error(&state, "统计处理指标失败", e)),
+    }
+}
+
+/// 预估报告规模（章节数、代码及路径字符数、文件大小、生成耗时），不生成报告
+#[tauri::command]
+pub async fn estimate_report(
+    config: ReportConfig,
+    state: State<'_, AppState>,
+) -> Result<ReportEstimate, ReportError> {
+    state.add_log(LogLevel::Info, "开始预估报告规模...".to_string());
+
+    match processors::estimate_report(&config) {
+        Ok(estimate) => {
+            state.add_log(
+                LogLevel::Success,
+                format!(
+                    "报告规模预估完成！{} 个章节，预计约 {} KB",
+                    estimate.section_count,
+                    estimate.estimated_bytes / 1024
+                ),
+            );
+            if let Some(warning) = &estimate.warning {
+                state.add_log(LogLevel::Warning, warning.clone());
+            }
+            Ok(estimate)
         }
-    };
+        Err(e) => Err(command_error(&state, "预估报告规模失败", e)),
+    }
+}
 
-    // 生成Word文档
-    state.update_progress(
-        3,
-        3,
-        "正在生成Word文档...".to_string(),
-    );
+/// 预览各章节内容（不生成文件）
+#[tauri::command]
+pub async fn preview_sections(
+    config: ReportConfig,
+    result: ExcelProcessResult,
+    state: State<'_, AppState>,
+) -> Result<Vec<SectionPreview>, ReportError> {
+    match WordGenerator::preview_sections(&config, &result) {
+        Ok(sections) => {
+            state.add_log(
+                LogLevel::Info,
+                format!("已生成 {} 个章节的预览", sections.len()),
+            );
+            Ok(sections)
+        }
+        Err(e) => Err(command_error(&state, "生成章节预览失败", e)),
+    }
+}
 
-    match WordGenerator::generate_report(&config, &processed_result) {
-        Ok(output_file) => {
+/// 预览报告的纯文本内容（不生成文件）
+#[tauri::command]
+pub async fn preview_report_text(
+    config: ReportConfig,
+    result: ExcelProcessResult,
+    state: State<'_, AppState>,
+) -> Result<String, ReportError> {
+    warn_unreadable_appendix(&state, &config);
+    match TextGenerator::report_to_text(&config, &result) {
+        Ok(text) => {
+            state.add_log(LogLevel::Info, "已生成报告文本预览".to_string());
+            Ok(text)
+        }
+        Err(e) => Err(command_error(&state, "生成文本预览失败", e)),
+    }
+}
+
+/// 导出统计信息为CSV文件（config 决定表头语言及报告编号，未提供时使用默认配置）
+#[tauri::command]
+pub async fn export_statistics_csv(
+    result: ExcelProcessResult,
+    output_path: String,
+    config: Option<ReportConfig>,
+    state: State<'_, AppState>,
+) -> Result<String, ReportError> {
+    let config = config.unwrap_or_default();
+    match WordGenerator::export_statistics_csv(&result, &config, &output_path) {
+        Ok(path) => {
             state.add_log(
                 LogLevel::Success,
-                format!("报告生成成功！文件: {}", output_file),
+                format!("统计CSV导出成功！文件: {}", path),
             );
-            state.update_progress(
-                3,
-                3,
-                "完成！".to_string(),
+            Ok(path)
+        }
+        Err(e) => Err(command_error(&state, "统计CSV导出失败", e)),
+    }
+}
+
+/// 报告生成的总体进度：读取文件（每个文件一步）、处理数据、生成章节（每个章节一步）、完成，
+/// 共用一个递增的计数器
+///
+/// 章节数为生成器追加基线、过滤分组后的章节数，在第一个章节完成时才确定，之前的总步数不含章节。
+/// 章节数确定后总步数变大，剩余步数从当时的百分比开始分配剩余进度，因此百分比只增不减。
+struct GenerationProgress<'a> {
+    state: &'a AppState,
+    files: usize,
+    sections: Option<usize>,
+    done: usize,
+    /// 总步数最近一次变化时的（已完成步数，百分比）
+    rebase: (usize, f32),
+}
+
+impl<'a> GenerationProgress<'a> {
+    fn new(state: &'a AppState, files: usize) -> Self {
+        Self {
+            state,
+            files,
+            sections: None,
+            done: 0,
+            rebase: (0, 0.0),
+        }
+    }
+
+    /// 总步数：文件数 + 处理数据 + 章节数 + 完成
+    fn total(&self) -> usize {
+        self.files + self.sections.unwrap_or(0) + 2
+    }
+
+    fn percentage(&self) -> f32 {
+        let (base_step, base) = self.rebase;
+        let steps = (self.done - base_step) as f32 / (self.total() - base_step) as f32;
+        base + (100.0 - base) * steps
+    }
+
+    /// 前进到第 done 步并更新进度
+    fn advance(&mut self, done: usize, message: String) {
+        self.done = done;
+        self.state.update_progress(ProgressInfo {
+            current: self.done,
+            total: self.total(),
+            message,
+            percentage: self.percentage(),
+        });
+    }
+
+    /// 开始读取文件
+    fn reading(&mut self, message: String) {
+        self.advance(0, message);
+    }
+
+    /// 读取完一个文件（从断点继续时 files_done 可以一次前进多步）
+    fn file_done(&mut self, files_done: usize) {
+        self.advance(
+            files_done,
+            format!("正在读取Excel文件（{}/{}）...", files_done, self.files),
+        );
+    }
+
+    /// 文件读取完成，开始处理数据
+    fn processing(&mut self, message: String) {
+        self.advance(self.files, message);
+    }
+
+    /// 数据处理完成
+    fn processed(&mut self, message: String) {
+        self.advance(self.files + 1, message);
+    }
+
+    /// 生成器完成一个章节，total 为过滤分组后的章节总数
+    fn section_done(&mut self, label: &str, done: usize, total: usize) {
+        if self.sections.is_none() {
+            self.rebase = (self.done, self.percentage());
+            self.sections = Some(total);
+        }
+        self.advance(
+            self.files + 1 + done,
+            format!("正在生成{}文档（第 {}/{} 个章节）...", label, done, total),
+        );
+    }
+
+    fn finished(&mut self) {
+        self.advance(self.total(), "完成！".to_string());
+    }
+}
+
+/// 读取并处理所有Excel文件（去重、分组），读取方式由 processors::read_and_process 按配置选择：
+/// 设置 checkpoint_file 时断点续读（checkpoint 为已读取的断点），设置 streaming_read 时流式读取，
+/// 否则合并全部数据后处理
+fn read_excel_files(
+    config: &ReportConfig,
+    checkpoint: Option<GenerationCheckpoint>,
+    state: &AppState,
+    progress: &mut GenerationProgress,
+) -> Result<ExcelProcessResult, ReportError> {
+    let file_count = config.excel_files.len();
+    let streaming = config.checkpoint_file.is_some() || config.streaming_read;
+    progress.reading(if streaming {
+        format!("正在流式读取 {} 个Excel文件...", file_count)
+    } else {
+        format!("正在合并 {} 个Excel文件...", file_count)
+    });
+
+    let (level, message) = match (&config.checkpoint_file, &checkpoint) {
+        (Some(_), Some(checkpoint)) if checkpoint.matches_inputs(config) => (
+            LogLevel::Info,
+            format!(
+                "从断点继续：已处理 {}/{} 个文件",
+                checkpoint.progress.files_done, file_count
+            ),
+        ),
+        (Some(checkpoint_file), Some(_)) => (
+            LogLevel::Warning,
+            format!(
+                "断点 {} 保存后输入文件或处理选项已变化，忽略断点并重新处理",
+                checkpoint_file
+            ),
+        ),
+        (Some(checkpoint_file), None) => (
+            LogLevel::Info,
+            format!(
+                "准备流式读取 {} 个Excel文件，断点保存至 {}",
+                file_count, checkpoint_file
+            ),
+        ),
+        (None, _) if streaming => (
+            LogLevel::Info,
+            format!("准备流式读取 {} 个Excel文件", file_count),
+        ),
+        (None, _) => (
+            LogLevel::Info,
+            format!("准备合并 {} 个Excel文件", file_count),
+        ),
+    };
+    state.add_log(level, message);
+
+    let processed = processors::read_and_process(config, checkpoint, &mut |files_done, total| {
+        progress.file_done(files_done);
+        if files_done == total {
+            progress.processing("正在处理数据（去重、分组）...".to_string());
+        }
+    });
+    match processed {
+        Ok(result) => {
+            log_skipped_files(state, &result.skipped_files);
+            state.add_log(
+                LogLevel::Success,
+                format!(
+                    "数据处理成功！共 {} 条记录，{} 个分组",
+                    result.total_records, result.total_groups
+                ),
             );
-            Ok(output_file)
+            warn_unrecognized_severity(state, &config.process, &result);
+            progress.processed("数据处理完成".to_string());
+            Ok(result)
         }
-        Err(e) => {
-            let error_msg = format!("生成Word文档失败: {}", e);
-            state.add_log(LogLevel::Error, error_msg.clone());
-            Err(error_msg)
+        Err(e) => Err(command_error(state, "读取并处理Excel文件失败", e)),
+    }
+}
+
+/// 每个被跳过的文件记录一条警告日志
+fn log_skipped_files(state: &AppState, skipped_files: &[SkippedFile]) {
+    for skipped in skipped_files {
+        state.add_log(
+            LogLevel::Warning,
+            format!("已跳过读取失败的文件 {}: {}", skipped.file, skipped.reason),
+        );
+    }
+}
+
+/// 配置的附录文件无法读取时记录警告（生成时跳过附录）
+fn warn_unreadable_appendix(state: &AppState, config: &ReportConfig) {
+    if let Some(path) = &config.appendix_file {
+        if let Err(e) = std::fs::File::open(path) {
+            state.add_log(
+                LogLevel::Warning,
+                format!("无法读取附录文件 {}，已跳过: {}", path, e),
+            );
+        }
+    }
+}
+
+/// 严重性级别列大多无法按 options 识别为风险等级时记录警告（通常是列映射有误）
+fn warn_unrecognized_severity(
+    state: &AppState,
+    options: &ProcessOptions,
+    result: &ExcelProcessResult,
+) {
+    if let Some(warning) = ExcelProcessor::check_result_severity(result, options) {
+        state.add_log(LogLevel::Warning, warning);
+    }
+}
+
+/// 保存配置预设
+#[tauri::command]
+pub async fn save_config_preset(
+    config: ReportConfig,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), ReportError> {
+    match config.save_preset(&path) {
+        Ok(()) => {
+            state.add_log(LogLevel::Success, format!("配置预设已保存: {}", path));
+            Ok(())
+        }
+        Err(e) => Err(command_error(&state, "保存配置预设失败", e)),
+    }
+}
+
+/// 加载配置预设
+#[tauri::command]
+pub async fn load_config_preset(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<ReportConfig, ReportError> {
+    match ReportConfig::load_preset(&path) {
+        Ok(config) => {
+            state.add_log(LogLevel::Success, format!("配置预设已加载: {}", path));
+            Ok(config)
         }
+        Err(e) => Err(command_error(&state, "加载配置预设失败", e)),
     }
 }
 
 /// 获取日志
 #[tauri::command]
-pub async fn get_logs(state: State<'_, AppState>) -> Result<Vec<LogMessage>, String> {
+pub async fn get_logs(state: State<'_, AppState>) -> Result<Vec<LogMessage>, ReportError> {
     state
         .logs
         .lock()
-        .map(|logs| logs.clone())
-        .map_err(|e| format!("获取日志失败: {}", e))
+        .map(|logs| logs.iter().cloned().collect())
+        .map_err(|e| ReportError::Other {
+            message: format!("获取日志失败: {}", e),
+        })
+}
+
+/// 获取因超出上限而被丢弃的日志数量
+#[tauri::command]
+pub async fn get_dropped_log_count(state: State<'_, AppState>) -> Result<usize, ReportError> {
+    state
+        .dropped_logs
+        .lock()
+        .map(|dropped| *dropped)
+        .map_err(|e| ReportError::Other {
+            message: format!("获取日志失败: {}", e),
+        })
+}
+
+/// 获取不低于指定级别的日志
+#[tauri::command]
+pub async fn get_logs_filtered(
+    min_level: LogLevel,
+    state: State<'_, AppState>,
+) -> Result<Vec<LogMessage>, ReportError> {
+    Ok(state.logs_at_least(&min_level))
 }
 
 /// 获取进度
 #[tauri::command]
-pub async fn get_progress(state: State<'_, AppState>) -> Result<Option<ProgressInfo>, String> {
+pub async fn get_progress(state: State<'_, AppState>) -> Result<Option<ProgressInfo>, ReportError> {
     state
         .progress
         .lock()
         .map(|prog| prog.clone())
-        .map_err(|e| format!("获取进度失败: {}", e))
+        .map_err(|e| ReportError::Other {
+            message: format!("获取进度失败: {}", e),
+        })
 }
 
 /// 清空日志
 #[tauri::command]
-pub async fn clear_logs(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn clear_logs(state: State<'_, AppState>) -> Result<(), ReportError> {
     state.clear_logs();
     Ok(())
 }
 
+/// 设置日志文件（路径为空时关闭文件日志）
+#[tauri::command]
+pub async fn set_log_file(path: String, state: State<'_, AppState>) -> Result<(), ReportError> {
+    if path.trim().is_empty() {
+        state.set_log_file(None);
+        state.add_log(LogLevel::Info, "已关闭文件日志".to_string());
+        return Ok(());
+    }
+
+    match LogFile::open(PathBuf::from(&path), MAX_LOG_FILE_BYTES) {
+        Ok(log_file) => {
+            state.set_log_file(Some(log_file));
+            state.add_log(LogLevel::Success, format!("日志将同时写入文件: {}", path));
+            Ok(())
+        }
+        Err(e) => Err(command_error(&state, "设置日志文件失败", e)),
+    }
+}
+
 /// 清空进度
 #[tauri::command]
-pub async fn clear_progress(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn clear_progress(state: State<'_, AppState>) -> Result<(), ReportError> {
     state.clear_progress();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logs_at_least_filters_by_severity() {
+        let state = AppState::new();
+        state.add_log(LogLevel::Info, "info".to_string());
+        state.add_log(LogLevel::Success, "success".to_string());
+        state.add_log(LogLevel::Warning, "warning".to_string());
+        state.add_log(LogLevel::Error, "error".to_string());
+
+        let messages = |level: LogLevel| -> Vec<String> {
+            state
+                .logs_at_least(&level)
+                .into_iter()
+                .map(|log| log.message)
+                .collect()
+        };
+
+        assert_eq!(messages(LogLevel::Info).len(), 4);
+        assert_eq!(messages(LogLevel::Success).len(), 4);
+        assert_eq!(messages(LogLevel::Warning), vec!["warning", "error"]);
+        assert_eq!(messages(LogLevel::Error), vec!["error"]);
+    }
+
+    #[test]
+    fn test_concurrent_generation_is_rejected() {
+        let state = AppState::new();
+        let started = std::sync::Barrier::new(2);
+        let checked = std::sync::Barrier::new(2);
+
+        std::thread::scope(|scope| {
+            let first = scope.spawn(|| {
+                let _generation = state.begin_generation().unwrap();
+                started.wait();
+                // 第二个调用检查完成前一直占用
+                checked.wait();
+            });
+            let second = scope.spawn(|| {
+                started.wait();
+                let result = state.begin_generation().map(|_| ());
+                checked.wait();
+                result
+            });
+
+            first.join().unwrap();
+            assert!(matches!(second.join().unwrap(), Err(ReportError::Busy)));
+        });
+
+        // 完成后释放，可以再次生成
+        assert!(state.begin_generation().is_ok());
+    }
+
+    fn percentage(state: &AppState) -> f32 {
+        state.progress.lock().unwrap().as_ref().unwrap().percentage
+    }
+
+    #[test]
+    fn test_generation_progress_is_monotonic() {
+        let state = AppState::new();
+
+        let mut progress = GenerationProgress::new(&state, 2);
+        let mut seen = Vec::new();
+        progress.reading("读取".to_string());
+        seen.push(percentage(&state));
+        for files_done in 1..=2 {
+            progress.file_done(files_done);
+            seen.push(percentage(&state));
+        }
+        progress.processed("处理完成".to_string());
+        seen.push(percentage(&state));
+        // 第一个章节完成时才确定章节数，总步数变大，进度不回退
+        for done in 1..=10 {
+            progress.section_done("Word", done, 10);
+            seen.push(percentage(&state));
+        }
+        progress.finished();
+        seen.push(percentage(&state));
+
+        assert!(seen.windows(2).all(|w| w[0] < w[1]), "{:?}", seen);
+        assert_eq!(seen[0], 0.0);
+        assert_eq!(*seen.last().unwrap(), 100.0);
+        let info = state.progress.lock().unwrap().clone().unwrap();
+        assert_eq!((info.current, info.total), (14, 14));
+
+        // 没有章节时直接完成
+        let mut progress = GenerationProgress::new(&state, 1);
+        progress.processed("处理完成".to_string());
+        assert!(percentage(&state) < 100.0);
+        progress.finished();
+        assert_eq!(percentage(&state), 100.0);
+    }
+
+    #[test]
+    fn test_update_progress_clamps_percentage() {
+        let progress = |percentage| ProgressInfo {
+            current: 0,
+            total: 0,
+            message: String::new(),
+            percentage,
+        };
+        let state = AppState::new();
+        state.update_progress(progress(120.0));
+        assert_eq!(percentage(&state), 100.0);
+
+        // 单调性由调用方保证，这里不比较上一次的值
+        state.update_progress(progress(25.0));
+        assert_eq!(percentage(&state), 25.0);
+    }
+
+    #[test]
+    fn test_log_buffer_is_bounded() {
+        let state = AppState::with_log_capacity(100);
+        for i in 0..250 {
+            state.add_log(LogLevel::Info, format!("日志 {}", i));
+        }
+
+        let logs = state.logs.lock().unwrap();
+        assert_eq!(logs.len(), 100);
+        assert_eq!(logs.front().unwrap().message, "日志 150");
+        assert_eq!(*state.dropped_logs.lock().unwrap(), 150);
+    }
+
+    #[test]
+    fn test_log_file_appends_and_rotates() {
+        let dir = std::env::temp_dir().join("report_forge_log_file_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("app.log");
+
+        let state = AppState::new();
+        state.set_log_file(Some(LogFile::open(path.clone(), 64).unwrap()));
+        for i in 0..10 {
+            state.add_log(LogLevel::Info, format!("第 {} 条日志", i));
+        }
+
+        let current = std::fs::read_to_string(&path).unwrap();
+        assert!(current.contains("[INFO] 第 9 条日志"));
+        assert!(dir.join("app.log.1").is_file());
+        assert!(!dir.join("app.log.4").exists());
+        assert_eq!(state.logs.lock().unwrap().len(), 10);
+
+        drop(state);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
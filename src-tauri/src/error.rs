@@ -0,0 +1,292 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+/// 返回给前端的结构化错误，序列化为 { kind, message, ...字段 }
+#[derive(Debug, Error)]
+pub enum ReportError {
+    /// 文件不存在
+    #[error("文件不存在: {path}")]
+    FileNotFound { path: String },
+    /// Excel文件没有任何行
+    #[error("Excel文件为空: {file}")]
+    EmptyWorkbook { file: String },
+    /// Excel文件只有表头
+    #[error("Excel文件只有表头，没有数据行: {file}")]
+    NoDataRows { file: String },
+    /// Excel文件设置了打开密码
+    #[error("文件已加密，无法读取，请先解除保护: {file}")]
+    Encrypted { file: String },
+    /// 表头与第一个文件不一致
+    #[error("文件 {file} 的{}", header_mismatch_detail(.expected, .got))]
+    HeaderMismatch {
+        file: String,
+        expected: Vec<String>,
+        got: Vec<String>,
+    },
+    /// 追加合并的处理结果与基线结果的列不一致（来自表头不同的Excel文件）；
+    /// 两个结果都保存了表头时为表头名称，否则为记录中的列字母
+    #[error("{}", column_mismatch_detail(.expected, .got))]
+    ColumnMismatch {
+        expected: Vec<String>,
+        got: Vec<String>,
+    },
+    /// 配置校验未通过
+    #[error("{message}")]
+    InvalidConfig { message: String },
+    /// 输出文件写入失败
+    #[error("无法写入文件 {path}: {reason}")]
+    WriteFailed { path: String, reason: String },
+    /// 过滤、去重后没有任何问题，按配置不生成报告
+    #[error("过滤和去重后没有任何问题，未生成报告")]
+    NoFindings,
+    /// 操作被取消
+    #[error("操作已取消")]
+    Cancelled,
+    /// 已有报告正在生成
+    #[error("报告正在生成中，请等待当前任务完成")]
+    Busy,
+    /// 其他错误
+    #[error("{message}")]
+    Other { message: String },
+}
+
+impl ReportError {
+    /// 错误类型名称，供前端区分处理
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ReportError::FileNotFound { .. } => "FileNotFound",
+            ReportError::EmptyWorkbook { .. } => "EmptyWorkbook",
+            ReportError::NoDataRows { .. } => "NoDataRows",
+            ReportError::Encrypted { .. } => "Encrypted",
+            ReportError::HeaderMismatch { .. } => "HeaderMismatch",
+            ReportError::ColumnMismatch { .. } => "ColumnMismatch",
+            ReportError::InvalidConfig { .. } => "InvalidConfig",
+            ReportError::WriteFailed { .. } => "WriteFailed",
+            ReportError::NoFindings => "NoFindings",
+            ReportError::Cancelled => "Cancelled",
+            ReportError::Busy => "Busy",
+            ReportError::Other { .. } => "Other",
+        }
+    }
+
+    /// 写入失败
+    pub fn write_failed(path: impl std::fmt::Display, reason: impl std::fmt::Display) -> Self {
+        ReportError::WriteFailed {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// 从 anyhow 错误还原结构化错误，无法还原时作为 Other 并加上上下文前缀
+    pub fn from_anyhow(context: &str, error: anyhow::Error) -> Self {
+        let message = format!("{}: {:#}", context, error);
+        error
+            .downcast::<ReportError>()
+            .unwrap_or(ReportError::Other { message })
+    }
+}
+
+impl Serialize for ReportError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ReportError", 5)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            ReportError::FileNotFound { path } => state.serialize_field("path", path)?,
+            ReportError::EmptyWorkbook { file }
+            | ReportError::NoDataRows { file }
+            | ReportError::Encrypted { file } => state.serialize_field("file", file)?,
+            ReportError::HeaderMismatch {
+                file,
+                expected,
+                got,
+            } => {
+                state.serialize_field("file", file)?;
+                state.serialize_field("expected", expected)?;
+                state.serialize_field("got", got)?;
+            }
+            ReportError::ColumnMismatch { expected, got } => {
+                state.serialize_field("expected", expected)?;
+                state.serialize_field("got", got)?;
+            }
+            ReportError::WriteFailed { path, reason } => {
+                state.serialize_field("path", path)?;
+                state.serialize_field("reason", reason)?;
+            }
+            ReportError::InvalidConfig { .. }
+            | ReportError::NoFindings
+            | ReportError::Cancelled
+            | ReportError::Busy
+            | ReportError::Other { .. } => {}
+        }
+        state.end()
+    }
+}
+
+/// 列出所有不一致的表头列（列字母、两个文件的表头），一次性给出全部差异
+fn header_mismatch_detail(expected: &[String], got: &[String]) -> String {
+    let mut details = Vec::new();
+    if expected.len() != got.len() {
+        details.push(format!(
+            "列数为 {}，第一个文件为 {}",
+            got.len(),
+            expected.len()
+        ));
+    }
+
+    // 按去除首尾空白后比较；只有首尾空白不同时（完全一致模式）按原文列出
+    let mut columns = differing_columns(expected, got, str::trim);
+    if columns.is_empty() {
+        columns = differing_columns(expected, got, |header| header);
+    }
+    details.extend(columns);
+
+    format!("表头与第一个文件不一致：{}", details.join("；"))
+}
+
+/// 逐列比较（含只在一个文件中存在的列），返回差异描述
+fn differing_columns(
+    expected: &[String],
+    got: &[String],
+    prepare: impl Fn(&str) -> &str,
+) -> Vec<String> {
+    (0..expected.len().max(got.len()))
+        .filter_map(|index| {
+            let expected = expected.get(index).map(|header| prepare(header));
+            let got = got.get(index).map(|header| prepare(header));
+            (expected != got).then(|| {
+                format!(
+                    "{}列 第一个文件{}，当前文件{}",
+                    column_letter(index),
+                    visible_header(expected),
+                    visible_header(got)
+                )
+            })
+        })
+        .collect()
+}
+
+/// 列出处理结果中缺少和多出的列（表头名称或列字母），列相同仅顺序不同时注明
+fn column_mismatch_detail(expected: &[String], got: &[String]) -> String {
+    let missing: Vec<&str> = expected
+        .iter()
+        .filter(|column| !got.contains(column))
+        .map(String::as_str)
+        .collect();
+    let extra: Vec<&str> = got
+        .iter()
+        .filter(|column| !expected.contains(column))
+        .map(String::as_str)
+        .collect();
+
+    let mut details = vec![format!(
+        "当前结果有 {} 列，基线结果有 {} 列",
+        got.len(),
+        expected.len()
+    )];
+    if !missing.is_empty() {
+        details.push(format!("缺少 {} 列", missing.join("、")));
+    }
+    if !extra.is_empty() {
+        details.push(format!("多出 {} 列", extra.join("、")));
+    }
+    if missing.is_empty() && extra.is_empty() {
+        details.push("列的顺序不同".to_string());
+    }
+
+    format!(
+        "处理结果的列与基线结果不一致，可能来自表头不同的Excel文件：{}",
+        details.join("；")
+    )
+}
+
+/// 表头显示为「…」，空白字符替换为 ␣ 以便看出差异
+fn visible_header(header: Option<&str>) -> String {
+    match header {
+        Some(header) => {
+            let header: String = header
+                .chars()
+                .map(|c| if c.is_whitespace() { '␣' } else { c })
+                .collect();
+            format!("「{}」", header)
+        }
+        None => "（无此列）".to_string(),
+    }
+}
+
+/// 列序号（从0开始）转换为Excel列字母：0 -> A，25 -> Z，26 -> AA
+pub fn column_letter(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        letters.push((b'A' + rem as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_kind_message_and_fields() {
+        let error = ReportError::HeaderMismatch {
+            file: "b.xlsx".to_string(),
+            expected: vec!["序号".to_string(), "问题名称".to_string()],
+            got: vec!["序号".to_string(), "名称".to_string()],
+        };
+
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["kind"], "HeaderMismatch");
+        assert_eq!(
+            json["message"],
+            "文件 b.xlsx 的表头与第一个文件不一致：B列 第一个文件「问题名称」，当前文件「名称」"
+        );
+        assert_eq!(json["file"], "b.xlsx");
+        assert_eq!(json["got"][1], "名称");
+    }
+
+    #[test]
+    fn test_header_mismatch_lists_all_differences() {
+        let headers = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let error = ReportError::HeaderMismatch {
+            file: "b.xlsx".to_string(),
+            expected: headers(&["序号", "问题名称", "风险", "SQL注入 "]),
+            got: headers(&["序号 ", "名称", "风险", "SQL 注入", "备注"]),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "文件 b.xlsx 的表头与第一个文件不一致：列数为 5，第一个文件为 4；\
+             B列 第一个文件「问题名称」，当前文件「名称」；\
+             D列 第一个文件「SQL注入」，当前文件「SQL␣注入」；\
+             E列 第一个文件（无此列），当前文件「备注」"
+        );
+    }
+
+    #[test]
+    fn test_column_letter() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(26), "AA");
+        assert_eq!(column_letter(701), "ZZ");
+        assert_eq!(column_letter(702), "AAA");
+    }
+
+    #[test]
+    fn test_from_anyhow_keeps_structured_errors() {
+        let error = anyhow::Error::new(ReportError::EmptyWorkbook {
+            file: "a.xlsx".to_string(),
+        });
+        let error = ReportError::from_anyhow("合并Excel文件失败", error);
+        assert_eq!(error.kind(), "EmptyWorkbook");
+
+        let error = ReportError::from_anyhow("合并Excel文件失败", anyhow::anyhow!("磁盘已满"));
+        assert_eq!(error.kind(), "Other");
+        assert_eq!(error.to_string(), "合并Excel文件失败: 磁盘已满");
+    }
+}
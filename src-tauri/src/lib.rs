@@ -1,10 +1,14 @@
 mod commands;
-mod models;
-mod processors;
+pub mod error;
+pub mod models;
+pub mod processors;
 
 use commands::{
-    clear_logs, clear_progress, generate_report, get_logs, get_progress, process_excel_file,
-    AppState,
+    clear_logs, clear_progress, compute_metrics, estimate_report, export_statistics_csv,
+    generate_report, generate_report_bytes, generate_reports_by_severity, get_dropped_log_count,
+    get_logs, get_logs_filtered, get_progress, inspect_workbook, load_config_preset,
+    merge_and_preview, preview_report_text, preview_sections, process_excel_file, process_raw_dto,
+    resume_generation, save_config_preset, set_log_file, validate_excel_file, AppState,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -21,11 +25,28 @@ pub fn run() {
         .manage(app_state)
         .invoke_handler(tauri::generate_handler![
             process_excel_file,
+            process_raw_dto,
+            validate_excel_file,
+            inspect_workbook,
+            merge_and_preview,
             generate_report,
+            generate_report_bytes,
+            generate_reports_by_severity,
+            resume_generation,
+            compute_metrics,
+            estimate_report,
+            preview_sections,
+            preview_report_text,
+            export_statistics_csv,
+            save_config_preset,
+            load_config_preset,
             get_logs,
+            get_dropped_log_count,
+            get_logs_filtered,
             get_progress,
             clear_logs,
             clear_progress,
+            set_log_file,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,8 +1,34 @@
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::path::Path;
+
+/// 内置严重性关键字，按顺序匹配
+const BUILTIN_SEVERITY_KEYWORDS: [(RiskLevel, &[&str]); 3] = [
+    (RiskLevel::High, &["高危", "高"]),
+    (RiskLevel::Medium, &["中危", "中"]),
+    (RiskLevel::Low, &["低危", "低"]),
+];
+
+/// 风险等级的显示名称（中文、英文），用于统计表格及严重性小计
+const SEVERITY_LABELS: [(RiskLevel, &str, &str); 4] = [
+    (RiskLevel::High, "高", "High"),
+    (RiskLevel::Medium, "中", "Medium"),
+    (RiskLevel::Low, "低", "Low"),
+    (RiskLevel::Unknown, "未知", "Unknown"),
+];
+
+/// 严重性级别复选框中各风险等级的名称（中文、英文），按显示顺序排列
+const RISK_OPTION_LABELS: [(RiskLevel, &str, &str); 3] = [
+    (RiskLevel::High, "高危风险", "High Risk"),
+    (RiskLevel::Medium, "中危风险", "Medium Risk"),
+    (RiskLevel::Low, "低危风险", "Low Risk"),
+];
 
 /// 风险等级枚举
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum RiskLevel {
     High,
     Medium,
@@ -11,16 +37,59 @@ pub enum RiskLevel {
 }
 
 impl RiskLevel {
-    /// 从严重性字符串获取风险等级
+    /// 从严重性字符串获取风险等级（内置关键字）
     pub fn from_severity(severity: &str) -> Self {
-        if severity.contains("高危") || severity.contains("高") {
+        BUILTIN_SEVERITY_KEYWORDS
+            .iter()
+            .find(|(_, keywords)| keywords.iter().any(|keyword| severity.contains(keyword)))
+            .map(|(level, _)| level.clone())
+            .unwrap_or(RiskLevel::Unknown)
+    }
+
+    /// 使用自定义关键字获取风险等级，按高、中、低、未知的顺序匹配，均未匹配时为未知
+    pub fn from_severity_keywords(
+        severity: &str,
+        keywords: &HashMap<RiskLevel, Vec<String>>,
+    ) -> Self {
+        [
+            RiskLevel::High,
+            RiskLevel::Medium,
+            RiskLevel::Low,
+            RiskLevel::Unknown,
+        ]
+        .into_iter()
+        .find(|level| {
+            keywords.get(level).is_some_and(|words| {
+                words
+                    .iter()
+                    .map(|word| word.trim())
+                    .any(|word| !word.is_empty() && severity.contains(word))
+            })
+        })
+        .unwrap_or(RiskLevel::Unknown)
+    }
+
+    /// 从CVSS评分获取风险等级：7.0-10.0 高危（含9.0以上的严重），4.0-6.9 中危，
+    /// 0.1-3.9 低危，0 及超出范围的评分为未知
+    pub fn from_cvss(score: f32) -> Self {
+        if !(0.0..=10.0).contains(&score) || score == 0.0 {
+            RiskLevel::Unknown
+        } else if score >= 7.0 {
             RiskLevel::High
-        } else if severity.contains("中危") || severity.contains("中") {
+        } else if score >= 4.0 {
             RiskLevel::Medium
-        } else if severity.contains("低危") || severity.contains("低") {
-            RiskLevel::Low
         } else {
-            RiskLevel::Unknown
+            RiskLevel::Low
+        }
+    }
+
+    /// 获取严重性级别名称（与Excel中的写法一致）
+    pub fn severity_name(&self) -> &'static str {
+        match self {
+            RiskLevel::High => "高危",
+            RiskLevel::Medium => "中危",
+            RiskLevel::Low => "低危",
+            RiskLevel::Unknown => "未知",
         }
     }
 
@@ -34,17 +103,252 @@ impl RiskLevel {
         }
     }
 
-    /// 获取风险等级文本（带复选框）
-    pub fn text(&self) -> String {
+    /// 获取风险等级文本（带复选框），未知等级不勾选任何一项
+    pub fn text(&self, language: Language) -> String {
+        RISK_OPTION_LABELS
+            .iter()
+            .map(|(level, zh, en)| {
+                let checkbox = if level == self { "☑" } else { "☐" };
+                format!("{} {}", checkbox, language.pick(zh, en))
+            })
+            .collect::<Vec<String>>()
+            .join("  ")
+    }
+}
+
+/// 报告语言，决定统计表格严重性级别、复选框等显示文本
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Language {
+    /// 中文
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl Language {
+    /// 风险等级的显示名称（如 "高" / "High"）
+    pub fn severity_label(&self, level: &RiskLevel) -> &'static str {
+        SEVERITY_LABELS
+            .iter()
+            .find(|(l, _, _)| l == level)
+            .map(|&(_, zh, en)| self.pick(zh, en))
+            .unwrap_or_default()
+    }
+
+    /// 报告中的固定文本（标签、表头等）
+    pub fn labels(&self) -> &'static Labels {
+        match self {
+            Language::Zh => &Labels::ZH,
+            Language::En => &Labels::EN,
+        }
+    }
+
+    /// 按语言选择中文或英文文本
+    fn pick<'a>(&self, zh: &'a str, en: &'a str) -> &'a str {
         match self {
-            RiskLevel::High => "☑ 高危风险  ☐ 中危风险  ☐ 低危风险".to_string(),
-            RiskLevel::Medium => "☐ 高危风险  ☑ 中危风险  ☐ 低危风险".to_string(),
-            RiskLevel::Low => "☐ 高危风险  ☐ 中危风险  ☑ 低危风险".to_string(),
-            RiskLevel::Unknown => "☐ 高危风险  ☐ 中危风险  ☐ 低危风险".to_string(),
+            Language::Zh => zh,
+            Language::En => en,
         }
     }
 }
 
+/// 报告中的固定文本（封面、统计表格、章节表格的标签及表头），由报告语言选择
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Labels {
+    /// 标签与取值之间的分隔符（如 "测试人：张三"）
+    pub colon: &'static str,
+    pub identifier: &'static str,
+    pub report_number: &'static str,
+    pub software_version: &'static str,
+    pub tester: &'static str,
+    pub test_time: &'static str,
+    pub generated_date: &'static str,
+    pub no_findings: &'static str,
+    pub statistics_title: &'static str,
+    pub statistics_columns: [&'static str; 6],
+    pub total: &'static str,
+    pub table_of_contents: &'static str,
+    pub description: &'static str,
+    pub phenomenon: &'static str,
+    pub severity: &'static str,
+    pub raw_severity: &'static str,
+    pub file_path: &'static str,
+    pub vulnerability: &'static str,
+    pub suggestion: &'static str,
+    pub record_details: &'static str,
+    /// 缺陷明细表格的列：序号、文件路径、相关代码
+    pub record_detail_columns: [&'static str; 3],
+    /// 变化报告封面上标题下方的说明
+    pub delta_report: &'static str,
+    pub added_findings: &'static str,
+    pub resolved_findings: &'static str,
+    /// 已修复问题表格的列：序号、问题名称、严重性级别、上次问题数量
+    pub resolved_columns: [&'static str; 4],
+    pub increased_findings: &'static str,
+    /// 数量增加的问题表格的列：序号、问题名称、严重性级别、上次问题数量、本次问题数量
+    pub increased_columns: [&'static str; 5],
+    /// 默认封面标题（也用作文档属性中的主题）
+    pub report_title: &'static str,
+    /// 默认页脚页码模板
+    pub footer_template: &'static str,
+    /// 默认统计汇总语句模板
+    pub statistics_summary_template: &'static str,
+    /// 默认章节标题模板
+    pub section_title_template: &'static str,
+    /// 默认文件路径标签模板
+    pub path_label_template: &'static str,
+    /// 每条缺陷相关代码前的标签，{seq} 为缺陷序号
+    pub code_label_template: &'static str,
+    /// 超出条数限制时的省略说明，{count} 为省略的条数
+    pub omitted_note_template: &'static str,
+    /// 合并相同代码时每段代码前的标签，{seq} 为代码序号，{count} 为使用该代码的缺陷数
+    pub collapsed_code_label_template: &'static str,
+    /// 合并相同代码时每段代码的文件路径标签，{seq} 为代码序号
+    pub collapsed_path_label_template: &'static str,
+    /// 报告末尾的生成说明，{version} 为工具版本，{time} 为生成时间
+    pub generation_stamp_template: &'static str,
+    /// 默认的问题名称为空的分组名称
+    pub uncategorized_label: &'static str,
+}
+
+impl Labels {
+    /// 中文（默认）
+    pub const ZH: Labels = Labels {
+        colon: "：",
+        identifier: "问题标识",
+        report_number: "问题报告编号",
+        software_version: "软件版本",
+        tester: "测试人",
+        test_time: "测试时间",
+        generated_date: "生成日期",
+        no_findings: "本次测试未发现问题",
+        statistics_title: "问题统计表格",
+        statistics_columns: STATISTICS_COLUMNS,
+        total: "合计",
+        table_of_contents: "目录",
+        description: "问题描述",
+        phenomenon: "缺陷描述",
+        severity: "问题严重性级别",
+        raw_severity: "原始级别",
+        file_path: "相关文件路径",
+        vulnerability: "漏洞说明",
+        suggestion: "整改建议",
+        record_details: "缺陷明细",
+        record_detail_columns: ["序号", "文件路径", "相关代码"],
+        delta_report: "变化报告（仅列出相比上次测试新增的问题）",
+        added_findings: "新增问题",
+        resolved_findings: "已修复问题",
+        resolved_columns: ["序号", "问题名称", "严重性级别", "上次问题数量"],
+        increased_findings: "数量增加的问题",
+        increased_columns: [
+            "序号",
+            "问题名称",
+            "严重性级别",
+            "上次问题数量",
+            "本次问题数量",
+        ],
+        report_title: "安全测试报告",
+        footer_template: "第 {page} 页 / 共 {total} 页",
+        statistics_summary_template: "共发现 {total_records} 条问题，归并为 {total_groups} 类",
+        section_title_template: "{seq}、{name}",
+        path_label_template: "缺陷{seq}文件路径：",
+        code_label_template: "缺陷{seq}相关代码如下：",
+        omitted_note_template: "…（其余 {count} 处省略）",
+        collapsed_code_label_template: "缺陷代码{seq}（共 {count} 处）如下：",
+        collapsed_path_label_template: "缺陷代码{seq}文件路径：",
+        generation_stamp_template: "由 report-forge v{version} 于 {time} 生成",
+        uncategorized_label: "未分类",
+    };
+
+    /// 英文
+    pub const EN: Labels = Labels {
+        colon: ": ",
+        identifier: "Identifier",
+        report_number: "Report No.",
+        software_version: "Software Version",
+        tester: "Tester",
+        test_time: "Test Date",
+        generated_date: "Generated",
+        no_findings: "No issues were found in this test",
+        statistics_title: "Summary of Findings",
+        statistics_columns: [
+            "No.",
+            "Report No.",
+            "Issue",
+            "Severity",
+            "Count",
+            "Percentage",
+        ],
+        total: "Total",
+        table_of_contents: "Contents",
+        description: "Description",
+        phenomenon: "Defect",
+        severity: "Severity",
+        raw_severity: "Original Severity",
+        file_path: "Affected Files",
+        vulnerability: "Vulnerability",
+        suggestion: "Recommendation",
+        record_details: "Defect Details",
+        record_detail_columns: ["No.", "File Path", "Code"],
+        delta_report: "Changes Report (findings new since the previous test)",
+        added_findings: "New Findings",
+        resolved_findings: "Resolved Findings",
+        resolved_columns: ["No.", "Issue", "Severity", "Previous Count"],
+        increased_findings: "Findings with More Occurrences",
+        increased_columns: [
+            "No.",
+            "Issue",
+            "Severity",
+            "Previous Count",
+            "Current Count",
+        ],
+        report_title: "Security Test Report",
+        footer_template: "Page {page} of {total}",
+        statistics_summary_template: "{total_records} findings in {total_groups} categories",
+        section_title_template: "{seq}. {name}",
+        path_label_template: "Finding {seq} file path:",
+        code_label_template: "Finding {seq} code:",
+        omitted_note_template: "… ({count} more omitted)",
+        collapsed_code_label_template: "Code {seq} ({count} occurrences):",
+        collapsed_path_label_template: "Code {seq} file paths:",
+        generation_stamp_template: "Generated by report-forge v{version} at {time}",
+        uncategorized_label: "Uncategorized",
+    };
+
+    /// 单独成行的标签（如 "缺陷描述：" / "Defect:"），分隔符末尾不留空格
+    pub fn line_label(&self, label: &str) -> String {
+        format!("{}{}", label, self.colon.trim_end())
+    }
+
+    /// 第 seq 条缺陷相关代码前的标签
+    pub fn code_label(&self, seq: usize) -> String {
+        Self::fill(self.code_label_template, seq, 0)
+    }
+
+    /// 省略 count 条时的省略说明
+    pub fn omitted_note(&self, count: usize) -> String {
+        Self::fill(self.omitted_note_template, 0, count)
+    }
+
+    /// 合并相同代码时第 seq 段代码（count 处使用）前的标签
+    pub fn collapsed_code_label(&self, seq: usize, count: usize) -> String {
+        Self::fill(self.collapsed_code_label_template, seq, count)
+    }
+
+    /// 合并相同代码时第 seq 段代码的文件路径标签
+    pub fn collapsed_path_label(&self, seq: usize) -> String {
+        Self::fill(self.collapsed_path_label_template, seq, 0)
+    }
+
+    /// 替换固定文本中的 {seq}、{count}
+    fn fill(template: &str, seq: usize, count: usize) -> String {
+        template
+            .replace("{seq}", &seq.to_string())
+            .replace("{count}", &count.to_string())
+    }
+}
+
 /// 风险信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskInfo {
@@ -56,7 +360,7 @@ pub struct RiskInfo {
 impl RiskInfo {
     pub fn from_severity(severity: &str) -> Self {
         let level = RiskLevel::from_severity(severity);
-        let text = level.text();
+        let text = level.text(Language::default());
         let priority = level.priority();
 
         RiskInfo {
@@ -78,12 +382,942 @@ pub struct ReportConfig {
     pub ceshi_time: String,
     pub code_version: String,
     pub ceshi_user: String,
+    #[serde(default)]
+    pub document_style: DocumentStyle,
+    /// 章节之间是否插入分页符（最后一个章节之后不插入）
+    #[serde(default = "default_true")]
+    pub section_page_break: bool,
+    /// 页脚页码模板，{page} 为当前页码，{total} 为总页数
+    #[serde(default = "default_footer_template")]
+    pub footer_template: String,
+    /// 封面报告标题
+    #[serde(default = "default_report_title")]
+    pub report_title: String,
+    /// 是否在统计表格之后插入目录（需在Word中按F9更新域）
+    #[serde(default)]
+    pub include_toc: bool,
+    /// 读取、去重及分组参数（JSON中与其他配置项平铺在同一层）
+    #[serde(flatten)]
+    pub process: ProcessOptions,
+    /// 流式读取Excel（逐行去重，适用于超大文件）
+    #[serde(default)]
+    pub streaming_read: bool,
+    /// 截图路径所在列（如 "O"），为空时不嵌入图片
+    #[serde(default)]
+    pub image_column: Option<String>,
+    /// 问题报告编号模板，支持 {tag}、{seq}、{seq:05}、{year}
+    #[serde(default = "default_report_number_format")]
+    pub report_number_format: String,
+    /// 输出文件名模板（不含扩展名），支持 {tag}、{version}、{user}、{date}、{timestamp}
+    #[serde(default = "default_output_filename_template")]
+    pub output_filename_template: String,
+    /// 文件路径、相关代码所在列
+    #[serde(default)]
+    pub column_mapping: ColumnMapping,
+    /// 文件路径需要去除的前缀，为空时保留原路径
+    #[serde(default = "default_strip_path_prefix")]
+    pub strip_path_prefix: Option<String>,
+    /// 丢弃问题名称为空的分组；为 false 时将其归入 uncategorized_label
+    #[serde(default)]
+    pub drop_uncategorized: bool,
+    /// 问题名称为空的分组使用的名称
+    #[serde(default = "default_uncategorized_label")]
+    pub uncategorized_label: String,
+    /// 缺陷描述、漏洞说明、整改建议合并分组内所有记录的不同取值（默认只取第一条记录）
+    #[serde(default)]
+    pub merge_record_fields: bool,
+    /// 不合并字段时，缺陷描述、漏洞说明、整改建议及额外字段取自哪条记录（默认最先出现的记录）
+    #[serde(default)]
+    pub representative_selector: RepresentativeSelector,
+    /// 报告输出格式
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    /// 分组的最少记录数，记录数低于该值的分组（如只出现一次的噪声问题）不进入统计表格和章节
+    #[serde(default = "default_min_record_count")]
+    pub min_record_count: usize,
+    /// 严重性过滤，设置时只保留列出的风险等级的分组
+    #[serde(default)]
+    pub severity_filter: Option<Vec<RiskLevel>>,
+    /// 按风险等级分别生成报告：每个出现的等级一份，文件名加等级后缀，每份单独编号
+    #[serde(default)]
+    pub split_by_severity: bool,
+    /// 水印文字（如 "机密"），在每页中央斜置并衬于文字下方；为空时不添加水印
+    #[serde(default)]
+    pub watermark: Option<String>,
+    /// 在严重性级别复选框下方附上Excel中的原始严重性级别文本（如 "高危 - CVSS 8.1"）
+    #[serde(default)]
+    pub show_raw_severity: bool,
+    /// 统计表格列宽（单位twip，1厘米约567），数量须与统计表格列数一致；为空时使用默认列宽
+    #[serde(default)]
+    pub statistics_column_widths: Option<Vec<usize>>,
+    /// 统计表格标题下方的汇总语句，支持 {total_records}、{total_groups}；为空时不添加
+    #[serde(default = "default_statistics_summary_template")]
+    pub statistics_summary_template: String,
+    /// 章节标题模板，支持 {seq}、{name}、{severity}、{count}（分组的序号、问题名称、严重性级别、问题个数）
+    #[serde(default = "default_section_title_template")]
+    pub section_title_template: String,
+    /// 相关文件路径中每条路径前的标签模板，支持 {seq}（缺陷序号）；为空时不加标签
+    #[serde(default = "default_path_label_template")]
+    pub path_label_template: String,
+    /// 相关文件路径的列表样式（逐行列出、编号或项目符号）
+    #[serde(default)]
+    pub path_list_style: PathListStyle,
+    /// 没有任何问题时生成“未发现问题”报告或返回错误
+    #[serde(default)]
+    pub no_findings_mode: NoFindingsMode,
+    /// 在每个章节中添加缺陷明细表格（每条记录一行：文件路径、相关代码）
+    #[serde(default)]
+    pub record_details_table: bool,
+    /// 每个分组在问题描述和相关文件路径中最多列出的缺陷条数，其余以“…（其余 M 处省略）”代替；为空时不限制
+    #[serde(default)]
+    pub max_snippets_per_group: Option<usize>,
+    /// 合并分组内相同的相关代码：每段代码只列出一次，文件路径按代码分组列出；默认逐条记录列出
+    #[serde(default)]
+    pub collapse_identical_code: bool,
+    /// 缺陷描述、相关代码、文件路径、漏洞说明、整改建议为空（或仅含空白）时显示的占位文本，如“无”“N/A”；
+    /// 为空时保持空白
+    #[serde(default)]
+    pub empty_field_placeholder: Option<String>,
+    /// 章节中追加的字段（标签、所在列），如 ("CWE编号", "P")，每个字段在整改建议之后追加一行；
+    /// 取值方式同 merge_record_fields，分组内的记录都没有该列时不添加
+    #[serde(default)]
+    pub extra_fields: Vec<(String, String)>,
+    /// 按风险等级分别编号的标识号前缀（如 High: "H-"），每个等级单独计数；未列出的等级使用 identifier_tag
+    #[serde(default)]
+    pub tag_by_severity: HashMap<RiskLevel, String>,
+    /// 上次报告保存的处理结果（JSON），设置时沿用其分组及编号，只在末尾追加新的分组
+    #[serde(default)]
+    pub baseline_result: Option<String>,
+    /// 生成报告时在同一目录保存处理结果（同名 .json），供后续追加使用
+    #[serde(default)]
+    pub save_result_snapshot: bool,
+    /// 上次测试保存的处理结果（JSON），设置时生成变化报告：按分组键对比，只包含本次新增的分组（仅Word报告）
+    #[serde(default)]
+    pub delta_baseline: Option<String>,
+    /// 变化报告中在统计表格之后附带已修复问题（上次有、本次已消失的分组）汇总表
+    #[serde(default)]
+    pub delta_include_resolved: bool,
+    /// 断点文件（JSON）：设置时流式读取，每处理完一个输入文件保存一次中间结果；
+    /// 输入未变化时再次生成从断点继续，报告生成成功后删除
+    #[serde(default)]
+    pub checkpoint_file: Option<String>,
+    /// 每处理完几个输入文件保存一次断点（最后一个文件处理完后总会保存），输入文件很多时可调大以减少写入
+    #[serde(default = "default_checkpoint_interval")]
+    pub checkpoint_interval: usize,
+    /// 附录文件（文本或Markdown，如术语表、测试方法说明），内容按行追加在所有章节之后；
+    /// 文件不存在或无法读取时记录警告并跳过
+    #[serde(default)]
+    pub appendix_file: Option<String>,
+    /// 在报告末尾注明生成工具版本及生成时间（“由 report-forge vX.Y.Z 于 … 生成”）
+    #[serde(default)]
+    pub generation_stamp: bool,
+    /// 报告语言（统计表格严重性级别、严重性复选框等），默认中文
+    #[serde(default)]
+    pub language: Language,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_footer_template() -> String {
+    Labels::ZH.footer_template.to_string()
+}
+
+fn default_report_title() -> String {
+    Labels::ZH.report_title.to_string()
+}
+
+fn default_report_number_format() -> String {
+    "{tag}{seq:04}".to_string()
+}
+
+fn default_statistics_summary_template() -> String {
+    Labels::ZH.statistics_summary_template.to_string()
+}
+
+fn default_section_title_template() -> String {
+    Labels::ZH.section_title_template.to_string()
+}
+
+fn default_min_record_count() -> usize {
+    1
+}
+
+fn default_path_label_template() -> String {
+    Labels::ZH.path_label_template.to_string()
+}
+
+fn default_checkpoint_interval() -> usize {
+    1
+}
+
+fn default_uncategorized_label() -> String {
+    Labels::ZH.uncategorized_label.to_string()
+}
+
+fn default_strip_path_prefix() -> Option<String> {
+    Some("root".to_string())
+}
+
+fn default_output_filename_template() -> String {
+    "{tag}_{version}_{timestamp}".to_string()
+}
+
+/// 替换模板中的 {占位符}，resolve 返回 None 时视为未知占位符
+fn render_template<F>(template: &str, resolve: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|i| start + i)
+            .with_context(|| format!("占位符缺少右括号: {}", &rest[start..]))?;
+
+        let placeholder = &rest[start + 1..end];
+        let value =
+            resolve(placeholder).with_context(|| format!("未知占位符: {{{}}}", placeholder))?;
+        output.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        ReportConfig {
+            excel_files: Vec::new(),
+            template_file: String::new(),
+            output_dir: String::new(),
+            identifier_tag: String::new(),
+            wt_add: 0,
+            ceshi_time: String::new(),
+            code_version: String::new(),
+            ceshi_user: String::new(),
+            document_style: DocumentStyle::default(),
+            section_page_break: default_true(),
+            footer_template: default_footer_template(),
+            report_title: default_report_title(),
+            include_toc: false,
+            process: ProcessOptions::default(),
+            streaming_read: false,
+            image_column: None,
+            report_number_format: default_report_number_format(),
+            output_filename_template: default_output_filename_template(),
+            column_mapping: ColumnMapping::default(),
+            strip_path_prefix: default_strip_path_prefix(),
+            drop_uncategorized: false,
+            uncategorized_label: default_uncategorized_label(),
+            merge_record_fields: false,
+            representative_selector: RepresentativeSelector::default(),
+            output_format: OutputFormat::default(),
+            min_record_count: default_min_record_count(),
+            severity_filter: None,
+            split_by_severity: false,
+            watermark: None,
+            show_raw_severity: false,
+            statistics_column_widths: None,
+            statistics_summary_template: default_statistics_summary_template(),
+            section_title_template: default_section_title_template(),
+            path_label_template: default_path_label_template(),
+            path_list_style: PathListStyle::default(),
+            no_findings_mode: NoFindingsMode::default(),
+            record_details_table: false,
+            max_snippets_per_group: None,
+            collapse_identical_code: false,
+            empty_field_placeholder: None,
+            extra_fields: Vec::new(),
+            tag_by_severity: HashMap::new(),
+            baseline_result: None,
+            save_result_snapshot: false,
+            delta_baseline: None,
+            delta_include_resolved: false,
+            checkpoint_file: None,
+            checkpoint_interval: default_checkpoint_interval(),
+            appendix_file: None,
+            generation_stamp: false,
+            language: Language::default(),
+        }
+    }
+}
+
+impl ReportConfig {
+    /// 按配置识别严重性级别，未配置或无法识别时按内置关键字处理
+    pub fn risk_level(&self, severity: &str) -> RiskLevel {
+        self.process.risk_level(severity)
+    }
+
+    /// 按配置（CVSS评分、自定义关键字）识别严重性级别；未配置或无法识别时返回 None，按内置关键字处理
+    pub fn classify_severity(&self, severity: &str) -> Option<RiskLevel> {
+        self.process.classify_severity(severity)
+    }
+
+    /// 校验配置，一次性返回所有问题
+    pub fn validate(&self) -> Result<()> {
+        self.validate_with(true)
+    }
+
+    /// 校验配置，不检查输出目录及输出文件名模板（不写入文件的生成方式使用）
+    pub fn validate_without_output(&self) -> Result<()> {
+        self.validate_with(false)
+    }
+
+    fn validate_with(&self, check_output: bool) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if self.excel_files.is_empty() {
+            errors.push("未选择Excel文件".to_string());
+        }
+        for file in &self.excel_files {
+            if !Path::new(file).is_file() {
+                errors.push(format!("Excel文件不存在: {}", file));
+            }
+        }
+
+        if check_output {
+            if self.output_dir.trim().is_empty() {
+                errors.push("未指定输出目录".to_string());
+            } else if let Err(e) = Self::check_output_dir(Path::new(&self.output_dir)) {
+                errors.push(format!("输出目录不可用: {} ({})", self.output_dir, e));
+            }
+            if let Err(e) = self.output_file_stem(0) {
+                errors.push(format!("输出文件名模板无效: {}", e));
+            }
+        }
+
+        if let Some(baseline) = &self.baseline_result {
+            if !Path::new(baseline).is_file() {
+                errors.push(format!("基线处理结果文件不存在: {}", baseline));
+            }
+        }
+
+        if let Some(delta_baseline) = &self.delta_baseline {
+            if !Path::new(delta_baseline).is_file() {
+                errors.push(format!(
+                    "变化报告的基线处理结果文件不存在: {}",
+                    delta_baseline
+                ));
+            }
+            if self.baseline_result.is_some() {
+                errors.push("变化报告不能与追加报告（baseline_result）同时使用".to_string());
+            }
+            if self.output_format != OutputFormat::Word {
+                errors.push("变化报告仅支持Word格式".to_string());
+            }
+            if self.split_by_severity {
+                errors.push("变化报告不支持按风险等级分别生成".to_string());
+            }
+        }
+
+        if let Some(checkpoint_file) = &self.checkpoint_file {
+            if checkpoint_file.trim().is_empty() {
+                errors.push("断点文件路径不能为空".to_string());
+            } else if Path::new(checkpoint_file).is_dir() {
+                errors.push(format!("断点文件路径是一个目录: {}", checkpoint_file));
+            }
+        }
+        if self.checkpoint_interval == 0 {
+            errors.push("断点保存间隔不能为0".to_string());
+        }
+
+        if self.identifier_tag.trim().is_empty() {
+            errors.push("标识号前缀不能为空".to_string());
+        }
+        for (level, tag) in &self.tag_by_severity {
+            if tag.trim().is_empty() {
+                errors.push(format!("{}的标识号前缀不能为空", level.severity_name()));
+            }
+        }
+
+        if self.wt_add < 0 {
+            errors.push(format!("问题编号偏移不能为负数: {}", self.wt_add));
+        }
+
+        if let Some(widths) = &self.statistics_column_widths {
+            if widths.len() != STATISTICS_COLUMNS.len() {
+                errors.push(format!(
+                    "统计表格列宽应为 {} 个（{}），实际为 {} 个",
+                    STATISTICS_COLUMNS.len(),
+                    STATISTICS_COLUMNS.join("、"),
+                    widths.len()
+                ));
+            } else if widths.contains(&0) {
+                errors.push("统计表格列宽不能为0".to_string());
+            }
+        }
+
+        let border_color = &self.document_style.border_color;
+        if border_color.len() != 6 || !border_color.chars().all(|c| c.is_ascii_hexdigit()) {
+            errors.push(format!(
+                "表格边框颜色应为6位十六进制RGB（如 000000）: {}",
+                border_color
+            ));
+        }
+        if self.document_style.border_size == 0 {
+            errors.push("表格边框宽度不能为0".to_string());
+        }
+
+        if let RepresentativeSelector::MaxColumn(column) = &self.representative_selector {
+            if column.trim().is_empty() {
+                errors.push("代表记录的排序列不能为空".to_string());
+            } else if !column.chars().all(|c| c.is_ascii_uppercase()) {
+                errors.push(format!("代表记录的排序列应为列字母（如 E）: {}", column));
+            }
+        }
+
+        if self.max_snippets_per_group == Some(0) {
+            errors.push("每组最多列出的缺陷条数不能为0".to_string());
+        }
+
+        if let Err(e) = self.report_number(1) {
+            errors.push(format!("问题报告编号模板无效: {}", e));
+        }
+
+        if let Err(e) = self.statistics_summary(0, 0) {
+            errors.push(format!("统计汇总语句模板无效: {}", e));
+        }
+
+        if self.section_title_template.trim().is_empty() {
+            errors.push("章节标题模板不能为空".to_string());
+        } else if let Err(e) = self.section_title(1, "", "", 0) {
+            errors.push(format!("章节标题模板无效: {}", e));
+        }
+
+        if let Err(e) = self.path_label(1) {
+            errors.push(format!("文件路径标签模板无效: {}", e));
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!("配置校验失败：\n{}", errors.join("\n"));
+        }
+
+        Ok(())
+    }
+
+    /// 按模板生成第 title_num 个章节的问题报告编号（序号已加上 wt_add）
+    pub fn report_number(&self, title_num: i32) -> Result<String> {
+        self.format_report_number(&self.identifier_tag, title_num)
+    }
+
+    /// 依次生成各分组的问题报告编号：tag_by_severity 中列出的风险等级使用各自的前缀和序号，
+    /// 其余分组使用 identifier_tag 并共用一个序号
+    pub fn report_numbers(&self, grouped_data: &[(String, GroupInfo)]) -> Result<Vec<String>> {
+        let mut counters: HashMap<Option<RiskLevel>, i32> = HashMap::new();
+
+        grouped_data
+            .iter()
+            .map(|(_, info)| {
+                let level = RiskLevel::from_severity(&info.d_column);
+                let tag = self.tag_by_severity.get(&level);
+                let counter = counters.entry(tag.map(|_| level)).or_default();
+                *counter += 1;
+                self.format_report_number(tag.unwrap_or(&self.identifier_tag), *counter)
+            })
+            .collect()
+    }
+
+    /// 按模板生成指定前缀的第 title_num 个编号（序号已加上 wt_add）
+    fn format_report_number(&self, tag: &str, title_num: i32) -> Result<String> {
+        let seq = title_num + self.wt_add;
+
+        render_template(
+            &self.report_number_format,
+            |placeholder| match placeholder {
+                "tag" => Some(tag.to_string()),
+                "seq" => Some(seq.to_string()),
+                "year" => Some(chrono::Local::now().format("%Y").to_string()),
+                _ => placeholder
+                    .strip_prefix("seq:")
+                    .and_then(|w| w.parse::<usize>().ok())
+                    .map(|width| format!("{:0width$}", seq, width = width)),
+            },
+        )
+    }
+
+    /// 按模板生成统计表格的汇总语句，模板为空时返回 None
+    pub fn statistics_summary(
+        &self,
+        total_records: usize,
+        total_groups: usize,
+    ) -> Result<Option<String>> {
+        if self.statistics_summary_template.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let resolve = |placeholder: &str| match placeholder {
+            "total_records" => Some(total_records.to_string()),
+            "total_groups" => Some(total_groups.to_string()),
+            _ => None,
+        };
+        let template = self.localized(&self.statistics_summary_template, |l| {
+            l.statistics_summary_template
+        });
+        render_template(template, resolve).map(Some)
+    }
+
+    /// 按模板生成第 seq 个章节的标题
+    pub fn section_title(
+        &self,
+        seq: i32,
+        name: &str,
+        severity: &str,
+        count: usize,
+    ) -> Result<String> {
+        let resolve = |placeholder: &str| match placeholder {
+            "seq" => Some(seq.to_string()),
+            "name" => Some(name.to_string()),
+            "severity" => Some(severity.to_string()),
+            "count" => Some(count.to_string()),
+            _ => None,
+        };
+        let template = self.localized(&self.section_title_template, |l| l.section_title_template);
+        render_template(template, resolve)
+    }
+
+    /// 第 seq 条缺陷的文件路径标签，模板为空时返回空字符串
+    pub fn path_label(&self, seq: usize) -> Result<String> {
+        let resolve = |placeholder: &str| (placeholder == "seq").then(|| seq.to_string());
+        let template = self.localized(&self.path_label_template, |l| l.path_label_template);
+        render_template(template, resolve)
+    }
+
+    /// 封面报告标题，未修改默认标题时按报告语言
+    pub fn title(&self) -> &str {
+        self.localized(&self.report_title, |l| l.report_title)
+    }
+
+    /// 页脚页码模板，未修改默认模板时按报告语言
+    pub fn footer(&self) -> &str {
+        self.localized(&self.footer_template, |l| l.footer_template)
+    }
+
+    /// 问题名称为空的分组名称，未修改默认名称时按报告语言
+    pub fn uncategorized(&self) -> &str {
+        self.localized(&self.uncategorized_label, |l| l.uncategorized_label)
+    }
+
+    /// 取值为中文默认值时改用报告语言的默认值，使未修改默认值的配置随报告语言切换
+    fn localized<'a>(&'a self, value: &'a str, default: fn(&Labels) -> &'static str) -> &'a str {
+        if value == default(&Labels::ZH) {
+            default(self.language.labels())
+        } else {
+            value
+        }
+    }
+
+    /// 报告末尾的生成说明（工具版本及当前时间），未启用 generation_stamp 时返回 None
+    pub fn generation_stamp_text(&self) -> Option<String> {
+        self.generation_stamp.then(|| {
+            self.language
+                .labels()
+                .generation_stamp_template
+                .replace("{version}", env!("CARGO_PKG_VERSION"))
+                .replace(
+                    "{time}",
+                    &chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                )
+        })
+    }
+
+    /// 按模板生成输出文件名（不含扩展名），并替换文件名中的非法字符
+    pub fn output_file_stem(&self, timestamp_millis: i64) -> Result<String> {
+        let name = render_template(
+            &self.output_filename_template,
+            |placeholder| match placeholder {
+                "tag" => Some(self.identifier_tag.clone()),
+                "version" => Some(self.code_version.clone()),
+                "user" => Some(self.ceshi_user.clone()),
+                "date" => Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+                "timestamp" => Some(timestamp_millis.to_string()),
+                _ => None,
+            },
+        )?;
+
+        let name = name.trim();
+        let name = name.strip_suffix(".docx").unwrap_or(name);
+        let stem: String = name
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect();
+
+        if stem.trim_matches(|c| c == '.' || c == ' ').is_empty() {
+            anyhow::bail!("生成的文件名为空");
+        }
+
+        Ok(stem)
+    }
+
+    /// 将配置保存为JSON预设文件
+    pub fn save_preset<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("无法创建预设目录: {:?}", parent))?;
+        }
+
+        let json = serde_json::to_string_pretty(self).context("无法序列化配置")?;
+        std::fs::write(path, json).with_context(|| format!("无法写入预设文件: {:?}", path))?;
+
+        Ok(())
+    }
+
+    /// 从JSON预设文件加载配置
+    pub fn load_preset<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            anyhow::bail!("预设文件不存在: {:?}", path);
+        }
+
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("无法读取预设文件: {:?}", path))?;
+        serde_json::from_str(&json).with_context(|| format!("预设文件格式错误: {:?}", path))
+    }
+
+    /// 检查输出目录可写入：目录不存在时检查最近的已存在上级目录（生成时再创建），
+    /// 在其中创建并立即删除探测文件，不改动文件系统
+    fn check_output_dir(dir: &Path) -> std::io::Result<()> {
+        let existing = dir
+            .ancestors()
+            .map(|path| {
+                if path.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    path
+                }
+            })
+            .find(|path| path.exists())
+            .unwrap_or(Path::new("."));
+        if !existing.is_dir() {
+            return Err(std::io::Error::other(format!(
+                "{} 不是目录",
+                existing.display()
+            )));
+        }
+
+        let probe = existing.join(format!(
+            ".report_forge_probe_{}_{}",
+            std::process::id(),
+            chrono::Local::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+        ));
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&probe)?;
+        std::fs::remove_file(&probe)
+    }
+}
+
+/// 报告输出格式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Word文档（.docx）
+    #[default]
+    Word,
+    /// Markdown文档（.md）
+    Markdown,
+    /// 自包含HTML文档（.html，内联样式）
+    Html,
+}
+
+impl OutputFormat {
+    /// 格式名称（用于进度提示）
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputFormat::Word => "Word",
+            OutputFormat::Markdown => "Markdown",
+            OutputFormat::Html => "HTML",
+        }
+    }
+}
+
+/// 过滤、去重后没有任何问题时的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NoFindingsMode {
+    /// 生成注明“本次测试未发现问题”的报告
+    #[default]
+    EmptyReport,
+    /// 返回 NoFindings 错误，不生成文件
+    Error,
+}
+
+/// 相关文件路径的列表样式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PathListStyle {
+    /// 每条路径单独一行
+    #[default]
+    Plain,
+    /// 编号列表（1. 2. 3.）
+    Numbered,
+    /// 项目符号列表（•）
+    Bulleted,
+}
+
+impl PathListStyle {
+    /// 按样式格式化第 seq 条路径
+    pub fn item(&self, seq: usize, path: &str) -> String {
+        match self {
+            PathListStyle::Plain => path.to_string(),
+            PathListStyle::Numbered => format!("{}. {}", seq, path),
+            PathListStyle::Bulleted => format!("• {}", path),
+        }
+    }
+}
+
+/// 分组代表记录的选取方式（章节字段取自该记录），取值相同时都取最先出现的记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RepresentativeSelector {
+    /// 最先出现的记录
+    #[default]
+    FirstSeen,
+    /// 漏洞说明（K列）最长的记录
+    LongestDescription,
+    /// 严重性级别（D列）最高的记录；等级相同时按原始取值比较：数字（如CVSS评分）高于文本，
+    /// 数字之间按数值比较，文本之间按文本比较
+    HighestSeverity,
+    /// 指定列（列字母，如 "E"）取值最大的记录，取值的比较方式同 HighestSeverity
+    MaxColumn(String),
+}
+
+/// 分组排序方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GroupSortOrder {
+    /// 按风险等级，再按记录数降序
+    #[default]
+    RiskThenCount,
+    /// 按问题名称字母顺序
+    Alphabetical,
+    /// 按记录数降序
+    CountDesc,
+    /// 按首次出现顺序
+    OriginalOrder,
+}
+
+/// 风险升级规则：from_level 的分组记录数达到 min_count 时按 to_level 报告
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SeverityEscalation {
+    pub from_level: RiskLevel,
+    pub min_count: usize,
+    pub to_level: RiskLevel,
+}
+
+impl SeverityEscalation {
+    /// 按第一条匹配的规则返回升级后的风险等级，没有匹配的规则时返回 None
+    pub fn escalate(
+        rules: &[SeverityEscalation],
+        level: &RiskLevel,
+        record_count: usize,
+    ) -> Option<RiskLevel> {
+        rules
+            .iter()
+            .find(|rule| &rule.from_level == level && record_count >= rule.min_count)
+            .map(|rule| rule.to_level.clone())
+    }
+}
+
+/// CSV文件编码
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CsvEncoding {
+    /// 按BOM识别；没有BOM时为有效UTF-8则按UTF-8，否则按GBK
+    #[default]
+    Auto,
+    Utf8,
+    /// GBK（按GB18030解码，兼容GB2312）
+    Gbk,
+}
+
+/// 批量读取时某个文件读取失败（无法打开、为空、表头不一致等）的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FileErrorPolicy {
+    /// 中止生成，返回该文件的错误
+    #[default]
+    Abort,
+    /// 记录警告并跳过该文件，全部文件都失败时才返回错误
+    Skip,
+}
+
+/// 因读取失败被跳过的文件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkippedFile {
+    pub file: String,
+    pub reason: String,
+}
+
+/// 合并多个Excel文件时表头的比较方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HeaderMatchMode {
+    /// 完全一致
+    Exact,
+    /// 去除首尾空白后一致
+    #[default]
+    Trimmed,
+    /// 去除所有空白、全角转半角、忽略末尾括号注释（如 "(必填)"）后一致
+    Normalized,
+}
+
+impl HeaderMatchMode {
+    /// 按比较方式转换表头，转换结果相同即视为一致
+    pub fn comparable(&self, header: &str) -> String {
+        match self {
+            HeaderMatchMode::Exact => header.to_string(),
+            HeaderMatchMode::Trimmed => header.trim().to_string(),
+            HeaderMatchMode::Normalized => normalize_header(header),
+        }
+    }
+
+    /// 比较方式名称（用于日志）
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeaderMatchMode::Exact => "完全一致",
+            HeaderMatchMode::Trimmed => "去除首尾空白",
+            HeaderMatchMode::Normalized => "宽松匹配",
+        }
+    }
+}
+
+/// 宽松匹配的表头转换：全角转半角、去除空白、去掉末尾的括号注释
+fn normalize_header(header: &str) -> String {
+    let mut normalized: String = header
+        .chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    while normalized.ends_with(')') {
+        match normalized.rfind('(') {
+            Some(start) => normalized.truncate(start),
+            None => break,
+        }
+    }
+
+    normalized
+}
+
+/// 去重选项（只影响比较键，保留的记录仍为原始值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupOptions {
+    /// 去除首尾空白并将连续空白合并为一个空格
+    pub normalize_whitespace: bool,
+    /// 忽略大小写
+    pub ignore_case: bool,
+}
+
+/// 读取、合并、去重及分组参数，各项默认值：
+/// 不规范化去重比较键、按风险等级再按记录数排序、不升级风险、
+/// 表头去除首尾空白后比较、CSV编码自动识别、文件读取失败时中止、按内置关键字识别严重性级别
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessOptions {
+    /// 去重选项
+    pub dedup: DedupOptions,
+    /// 分组排序方式
+    pub group_sort_order: GroupSortOrder,
+    /// 风险升级规则，如中危分组出现50次以上按高危报告（按顺序取第一条匹配的规则）
+    pub severity_escalation: Vec<SeverityEscalation>,
+    /// 合并多个Excel文件时表头的比较方式
+    pub header_match_mode: HeaderMatchMode,
+    /// CSV文件的编码，默认自动识别（BOM、UTF-8，否则按GBK）
+    pub csv_encoding: CsvEncoding,
+    /// 某个文件读取失败时中止生成，或跳过该文件继续处理其余文件
+    pub on_file_error: FileErrorPolicy,
+    /// 严重性级别列为CVSS评分（0-10），按评分划分风险等级；无法解析为数字的值按原文处理
+    pub severity_from_cvss: bool,
+    /// 自定义严重性关键字（如 High: ["严重", "重要"]），设置时替代内置的高/中/低关键字
+    pub severity_keywords: Option<HashMap<RiskLevel, Vec<String>>>,
+}
+
+impl ProcessOptions {
+    /// 按CVSS评分或自定义关键字识别严重性级别，未配置或无法识别时按内置关键字处理
+    pub fn risk_level(&self, severity: &str) -> RiskLevel {
+        self.classify_severity(severity)
+            .unwrap_or_else(|| RiskLevel::from_severity(severity))
+    }
+
+    /// 按CVSS评分或自定义关键字识别严重性级别；未配置或无法识别时返回 None，按内置关键字处理
+    pub fn classify_severity(&self, severity: &str) -> Option<RiskLevel> {
+        if self.severity_from_cvss {
+            if let Ok(score) = severity.trim().parse::<f32>() {
+                return Some(RiskLevel::from_cvss(score));
+            }
+        }
+
+        self.severity_keywords
+            .as_ref()
+            .map(|keywords| RiskLevel::from_severity_keywords(severity, keywords))
+    }
+}
+
+/// 文档样式（字体与字号，字号单位为半磅；表格边框宽度单位为八分之一磅）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentStyle {
+    pub east_asia_font: String,
+    pub ascii_font: String,
+    pub body_size: usize,
+    pub header_size: usize,
+    pub heading_size: usize,
+    pub title_size: usize,
+    pub border_color: String,
+    pub border_size: usize,
+}
+
+impl Default for DocumentStyle {
+    fn default() -> Self {
+        DocumentStyle {
+            east_asia_font: "宋体".to_string(),
+            ascii_font: "Times New Roman".to_string(),
+            body_size: 24,    // 小四 = 12磅
+            header_size: 24,  // 小四 = 12磅
+            heading_size: 28, // 章节标题 14磅
+            title_size: 32,   // 统计表格标题 16磅
+            border_color: "000000".to_string(),
+            border_size: 2, // 单实线 1/4磅
+        }
+    }
+}
+
+/// 章节内容使用的Excel列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnMapping {
+    /// 文件路径所在列
+    pub path_column: String,
+    /// 相关代码所在列
+    pub code_column: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        ColumnMapping {
+            path_column: "I".to_string(),
+            code_column: "J".to_string(),
+        }
+    }
 }
 
 /// Excel记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExcelRecord {
     pub data: HashMap<String, Option<String>>,
+    /// 去重组合键（参与去重的各列规范化后以 `|` 连接），用于将章节追溯到原始数据行
+    #[serde(default)]
+    pub dedup_key: Option<String>,
 }
 
 /// 分组信息
@@ -93,23 +1327,412 @@ pub struct GroupInfo {
     pub d_column: String,       // 严重性级别
     pub record_count: usize,
     pub records: Vec<ExcelRecord>,
+    #[serde(default)]
+    pub original_severity: Option<String>, // 风险升级前的严重性级别（未升级时为空）
 }
 
 /// Excel处理结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExcelProcessResult {
     pub total_groups: usize,
     pub total_records: usize,
     pub grouped_data: Vec<(String, GroupInfo)>,  // 保持顺序的分组数据
+    #[serde(default)]
+    pub removed_duplicates: Vec<RemovedDuplicate>, // 去重时被移除的记录
+    #[serde(default)]
+    pub severity_counts: HashMap<RiskLevel, usize>, // 各风险等级的记录数
+    #[serde(default)]
+    pub skipped_files: Vec<SkippedFile>, // 按 FileErrorPolicy::Skip 跳过的文件
+    #[serde(default)]
+    pub headers: Vec<String>, // 输入文件的表头（旧版本保存的结果及仅由分组创建的结果为空）
 }
 
+impl ExcelProcessResult {
+    /// 由分组数据创建处理结果，分组数、记录数及各风险等级的记录数按分组汇总
+    pub fn from_groups(grouped_data: Vec<(String, GroupInfo)>) -> Self {
+        ExcelProcessResult {
+            total_groups: grouped_data.len(),
+            total_records: grouped_data.iter().map(|(_, info)| info.record_count).sum(),
+            severity_counts: Self::count_by_severity(&grouped_data),
+            grouped_data,
+            ..ExcelProcessResult::default()
+        }
+    }
+
+    /// 以新的分组数据替换本结果的分组（过滤、归类、追加之后），其余字段（被移除的重复记录、跳过的文件、表头）保持不变
+    pub fn regrouped(&self, grouped_data: Vec<(String, GroupInfo)>) -> Self {
+        ExcelProcessResult {
+            removed_duplicates: self.removed_duplicates.clone(),
+            skipped_files: self.skipped_files.clone(),
+            headers: self.headers.clone(),
+            ..Self::from_groups(grouped_data)
+        }
+    }
+
+    /// 按风险等级汇总分组的记录数，四个等级均包含（无记录时为0）
+    pub fn count_by_severity(grouped_data: &[(String, GroupInfo)]) -> HashMap<RiskLevel, usize> {
+        let mut counts: HashMap<RiskLevel, usize> = [
+            RiskLevel::High,
+            RiskLevel::Medium,
+            RiskLevel::Low,
+            RiskLevel::Unknown,
+        ]
+        .into_iter()
+        .map(|level| (level, 0))
+        .collect();
+
+        for (_, info) in grouped_data {
+            *counts
+                .entry(RiskLevel::from_severity(&info.d_column))
+                .or_default() += info.record_count;
+        }
+
+        counts
+    }
+
+    /// 记录中出现的所有列（按列顺序 A、B、…、Z、AA），用于检查两个处理结果能否合并
+    pub fn columns(&self) -> Vec<String> {
+        let mut columns: Vec<String> = self
+            .grouped_data
+            .iter()
+            .flat_map(|(_, info)| &info.records)
+            .flat_map(|record| record.data.keys())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .cloned()
+            .collect();
+        columns.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        columns
+    }
+
+    /// 将处理结果保存为JSON文件
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self).context("无法序列化处理结果")?;
+        std::fs::write(path, json).with_context(|| format!("无法写入处理结果文件: {:?}", path))
+    }
+
+    /// 从JSON文件加载处理结果
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("无法读取处理结果文件: {:?}", path))?;
+        serde_json::from_str(&json).with_context(|| format!("处理结果文件格式错误: {:?}", path))
+    }
+}
+
+/// 去重时被移除的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovedDuplicate {
+    pub row_index: usize, // 在合并后数据行中的位置（从0开始，不含表头）
+    pub record: ExcelRecord,
+}
+
+/// 列检查项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnCheck {
+    pub label: String,  // 语义名称，如“问题名称”
+    pub column: String, // 列字母，如“B”
+    pub header: Option<String>,
+    pub present: bool,
+}
+
+/// Excel文件校验报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExcelValidationReport {
+    pub file_path: String,
+    pub valid: bool,
+    pub column_count: usize,
+    pub data_row_count: usize,
+    pub headers: Vec<String>,
+    pub column_checks: Vec<ColumnCheck>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>, // 不影响校验结果的提示（如严重性级别列大多无法识别）
+}
+
+/// 工作簿概况（各工作表的表头及行数），供界面选择列映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkbookInfo {
+    pub file_path: String,
+    pub sheets: Vec<SheetInfo>,
+}
+
+/// 工作表概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetInfo {
+    pub name: String,
+    pub headers: Vec<String>, // 第一行（表头）
+    pub row_count: usize,     // 数据行数（不含表头）
+}
+
+/// 合并后的原始数据预览（表头及前若干行），供生成前确认合并结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedDataPreview {
+    pub headers: Vec<String>,
+    pub sample_rows: Vec<Vec<String>>,
+    pub total_rows: usize, // 合并后的数据行总数（不含表头）
+}
+
+/// 处理指标（读取、合并、去重、分组后的汇总数字），供仪表盘展示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportMetrics {
+    pub total_files: usize,    // 读取成功的文件数
+    pub skipped_files: usize,  // 按 FileErrorPolicy::Skip 跳过的文件数
+    pub total_raw_rows: usize, // 读取的数据行总数（去重前，不含表头及空行）
+    pub duplicates_removed: usize,
+    pub total_records: usize, // 进入报告的记录数（去重、过滤后）
+    pub total_groups: usize,  // 进入报告的分组数
+    pub severity_counts: HashMap<RiskLevel, usize>,
+    pub excluded_groups: usize, // 记录数低于 min_record_count、不进入报告的分组数
+    pub excluded_records: usize, // 上述分组的记录数合计
+}
+
+/// 两次扫描处理结果的差异（按分组键对比），用于"相比上次扫描的变化"报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResultDiff {
+    pub added_groups: Vec<(String, GroupInfo)>, // 本次新出现的分组（按本次结果中的顺序）
+    pub removed_groups: Vec<(String, GroupInfo)>, // 本次已消失的分组（按上次结果中的顺序）
+    pub count_changes: Vec<GroupCountChange>,   // 两次都存在但记录数变化的分组
+}
+
+impl ResultDiff {
+    /// 两次结果的分组及记录数完全一致
+    pub fn is_empty(&self) -> bool {
+        self.added_groups.is_empty()
+            && self.removed_groups.is_empty()
+            && self.count_changes.is_empty()
+    }
+}
+
+/// 两次扫描中同一分组的记录数变化
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupCountChange {
+    pub group_key: String,
+    pub old_count: usize,
+    pub new_count: usize,
+}
+
+impl GroupCountChange {
+    /// 记录数变化量（减少时为负数）
+    pub fn delta(&self) -> i64 {
+        self.new_count as i64 - self.old_count as i64
+    }
+
+    /// 从分组键（问题名称|严重性级别）中拆出问题名称和严重性级别
+    pub fn name_and_severity(&self) -> (&str, &str) {
+        self.group_key
+            .rsplit_once('|')
+            .unwrap_or((self.group_key.as_str(), ""))
+    }
+}
+
+/// 报告生成断点：流式处理时每处理完一个输入文件保存一次，报告生成成功后删除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationCheckpoint {
+    /// 保存断点时的输入指纹，与当前输入不一致时断点作废（见 input_fingerprint）
+    pub input_fingerprint: String,
+    /// 生成报告所用的配置，resume_generation 据此继续生成
+    pub config: ReportConfig,
+    pub progress: ProcessingProgress,
+}
+
+impl GenerationCheckpoint {
+    /// 输入指纹：各输入文件的路径、大小及修改时间，以及读取、去重、分组参数的SHA-256
+    ///
+    /// 各项按固定格式逐行写入后计算摘要，处理参数先转为键有序的JSON，不同版本的程序计算结果一致
+    pub fn input_fingerprint(config: &ReportConfig) -> String {
+        let mut hasher = Sha256::new();
+        for file in &config.excel_files {
+            let line = match std::fs::metadata(file) {
+                Ok(metadata) => {
+                    let modified = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|since| since.as_nanos().to_string())
+                        .unwrap_or_default();
+                    format!("file\t{}\t{}\t{}\n", file, metadata.len(), modified)
+                }
+                Err(_) => format!("file\t{}\t<missing>\n", file),
+            };
+            hasher.update(line.as_bytes());
+        }
+        // serde_json::Value 的对象按键排序，HashMap 字段的顺序不影响结果
+        let process = serde_json::to_value(&config.process)
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        hasher.update(format!("process\t{}\n", process).as_bytes());
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// 断点与按 config 计算的当前输入一致
+    pub fn matches_inputs(&self, config: &ReportConfig) -> bool {
+        self.input_fingerprint == Self::input_fingerprint(config)
+    }
+
+    /// 将断点保存为JSON文件（先写临时文件再替换，中途崩溃不会留下不完整的断点）
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Self::write(path, &self.input_fingerprint, &self.config, &self.progress)
+    }
+
+    /// 由各部分直接写入断点文件，不复制配置及中间结果
+    pub fn write<P: AsRef<Path>>(
+        path: P,
+        input_fingerprint: &str,
+        config: &ReportConfig,
+        progress: &ProcessingProgress,
+    ) -> Result<()> {
+        /// 与 GenerationCheckpoint 序列化结果相同的借用视图
+        #[derive(Serialize)]
+        struct CheckpointRef<'a> {
+            input_fingerprint: &'a str,
+            config: &'a ReportConfig,
+            progress: &'a ProcessingProgress,
+        }
+
+        let path = path.as_ref();
+        let checkpoint = CheckpointRef {
+            input_fingerprint,
+            config,
+            progress,
+        };
+        let json = serde_json::to_string(&checkpoint).context("无法序列化断点")?;
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, json).with_context(|| format!("无法写入断点文件: {:?}", temp))?;
+        std::fs::rename(&temp, path).with_context(|| format!("无法写入断点文件: {:?}", path))
+    }
+
+    /// 从JSON文件加载断点
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("无法读取断点文件: {:?}", path))?;
+        serde_json::from_str(&json).with_context(|| format!("断点文件格式错误: {:?}", path))
+    }
+}
+
+/// 流式处理进度：已处理的文件数及截至此时的去重状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingProgress {
+    /// 已处理（读取成功或按策略跳过）的文件数，按 excel_files 顺序
+    pub files_done: usize,
+    /// 已读取的数据行数（含空行及重复行），继续处理时被移除记录的行号由此接续
+    pub rows_read: usize,
+    pub blank_rows: usize,
+    /// 数据列数（以第一行为准）
+    pub column_count: usize,
+    /// 第一个读取成功的文件的表头，后续文件与之比较
+    pub headers: Option<Vec<String>>,
+    /// 去重后保留的记录，按读取顺序，尚未分组
+    pub records: Vec<ExcelRecord>,
+    pub removed_duplicates: Vec<RemovedDuplicate>,
+    pub skipped_files: Vec<SkippedFile>,
+}
+
+/// 报告规模预估（按处理结果估算，不构建文档），供生成前提示报告可能过大
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportEstimate {
+    pub section_count: usize,
+    pub total_records: usize,
+    pub code_chars: usize,       // 各章节相关代码的字符数合计
+    pub path_chars: usize,       // 各章节文件路径的字符数合计
+    pub image_bytes: u64,        // 嵌入截图的文件大小合计（未设置 image_column 时为0）
+    pub estimated_bytes: u64,    // 预计Word文件大小
+    pub estimated_seconds: f64,  // 预计生成耗时
+    pub warning: Option<String>, // 章节数或文件大小超过提示阈值时的说明
+}
+
+/// 章节预览（与Word报告中每个章节渲染的内容一致）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionPreview {
+    pub report_number: String,
+    pub title: String,
+    pub risk_text: String,
+    pub phenomenon: String,
+    pub code_path: String,
+    pub code: String,
+    pub vulnerability: String,
+    pub suggestion: String,
+    pub extra_fields: Vec<(String, String)>, // 配置的额外字段（标签、内容）
+}
+
+/// 统计表格的列（列宽配置须与列数一致）
+pub const STATISTICS_COLUMNS: [&str; 6] = [
+    "序号",
+    "问题报告编号",
+    "问题名称",
+    "严重性级别",
+    "问题个数",
+    "占比",
+];
+
 /// 统计项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatisticItem {
     pub seq_num: usize,
+    /// 与章节一致的问题报告编号
+    #[serde(default)]
+    pub report_number: String,
     pub problem_name: String,
+    /// 按报告语言显示的严重性级别（如 "高" / "High"）
     pub severity_level: String,
+    pub risk_level: RiskLevel,
     pub problem_count: usize,
+    /// 占总记录数的百分比（保留一位小数）
+    #[serde(default)]
+    pub percentage: f32,
+}
+
+/// 统计汇总（合计行）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatisticSummary {
+    pub total_count: usize,
+    pub high_count: usize,
+    pub medium_count: usize,
+    pub low_count: usize,
+    pub unknown_count: usize,
+}
+
+impl StatisticSummary {
+    /// 汇总统计项的问题个数，并按严重性分别小计
+    pub fn from_items(statistics: &[StatisticItem]) -> Self {
+        let mut summary = StatisticSummary::default();
+
+        for stat in statistics {
+            summary.total_count += stat.problem_count;
+            match stat.risk_level {
+                RiskLevel::High => summary.high_count += stat.problem_count,
+                RiskLevel::Medium => summary.medium_count += stat.problem_count,
+                RiskLevel::Low => summary.low_count += stat.problem_count,
+                RiskLevel::Unknown => summary.unknown_count += stat.problem_count,
+            }
+        }
+
+        summary
+    }
+
+    /// 获取严重性小计文本（未知等级没有记录时省略）
+    pub fn severity_text(&self, language: Language) -> String {
+        let mut counts = vec![
+            (RiskLevel::High, self.high_count),
+            (RiskLevel::Medium, self.medium_count),
+            (RiskLevel::Low, self.low_count),
+        ];
+        if self.unknown_count > 0 {
+            counts.push((RiskLevel::Unknown, self.unknown_count));
+        }
+
+        counts
+            .iter()
+            .map(|(level, count)| format!("{} {}", language.severity_label(level), count))
+            .collect::<Vec<String>>()
+            .join(" / ")
+    }
 }
 
 /// 进度信息
@@ -130,6 +1753,27 @@ pub enum LogLevel {
     Success,
 }
 
+impl LogLevel {
+    /// 严重程度序数，用于按级别过滤：Error > Warning > Success = Info
+    pub fn severity(&self) -> u8 {
+        match self {
+            LogLevel::Info | LogLevel::Success => 0,
+            LogLevel::Warning => 1,
+            LogLevel::Error => 2,
+        }
+    }
+
+    /// 日志文件中使用的级别标签
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "INFO",
+            LogLevel::Warning => "WARN",
+            LogLevel::Error => "ERROR",
+            LogLevel::Success => "SUCCESS",
+        }
+    }
+}
+
 /// 日志消息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogMessage {
@@ -137,3 +1781,508 @@ pub struct LogMessage {
     pub message: String,
     pub timestamp: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_collects_all_errors() {
+        let config = ReportConfig {
+            excel_files: vec!["/nonexistent/a.xlsx".to_string()],
+            wt_add: -1,
+            ..ReportConfig::default()
+        };
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("Excel文件不存在: /nonexistent/a.xlsx"));
+        assert!(message.contains("未指定输出目录"));
+        assert!(message.contains("标识号前缀不能为空"));
+        assert!(message.contains("问题编号偏移不能为负数"));
+    }
+
+    #[test]
+    fn test_validate_statistics_column_widths() {
+        let mut config = ReportConfig {
+            statistics_column_widths: Some(vec![800, 2200, 2900]),
+            ..ReportConfig::default()
+        };
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("统计表格列宽应为 6 个"));
+
+        config.statistics_column_widths = Some(vec![800, 2200, 2900, 1300, 0, 1000]);
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("统计表格列宽不能为0"));
+    }
+
+    #[test]
+    fn test_validate_table_borders() {
+        let mut config = ReportConfig::default();
+        config.document_style.border_color = "#000000".to_string();
+        config.document_style.border_size = 0;
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("表格边框颜色应为6位十六进制RGB（如 000000）: #000000"));
+        assert!(message.contains("表格边框宽度不能为0"));
+    }
+
+    #[test]
+    fn test_validate_representative_column() {
+        let message = |column: &str| {
+            let config = ReportConfig {
+                representative_selector: RepresentativeSelector::MaxColumn(column.to_string()),
+                ..ReportConfig::default()
+            };
+            config.validate().unwrap_err().to_string()
+        };
+
+        assert!(message("漏洞说明").contains("代表记录的排序列应为列字母（如 E）: 漏洞说明"));
+        assert!(message("e").contains("代表记录的排序列应为列字母（如 E）: e"));
+        assert!(message(" ").contains("代表记录的排序列不能为空"));
+        assert!(!message("E").contains("代表记录的排序列"));
+    }
+
+    #[test]
+    fn test_validate_delta_baseline() {
+        let config = ReportConfig {
+            delta_baseline: Some("/nonexistent/last.json".to_string()),
+            baseline_result: Some("/nonexistent/baseline.json".to_string()),
+            output_format: OutputFormat::Markdown,
+            split_by_severity: true,
+            ..ReportConfig::default()
+        };
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("变化报告的基线处理结果文件不存在: /nonexistent/last.json"));
+        assert!(message.contains("变化报告不能与追加报告（baseline_result）同时使用"));
+        assert!(message.contains("变化报告仅支持Word格式"));
+        assert!(message.contains("变化报告不支持按风险等级分别生成"));
+    }
+
+    #[test]
+    fn test_process_options_flattened_in_config_json() {
+        let mut json = serde_json::to_value(ReportConfig::default()).unwrap();
+        assert!(json.get("process").is_none());
+        assert_eq!(json["header_match_mode"], "Trimmed");
+
+        json["group_sort_order"] = "CountDesc".into();
+        json["on_file_error"] = "Skip".into();
+        json["dedup"] = serde_json::json!({ "ignore_case": true });
+        let config: ReportConfig = serde_json::from_value(json).unwrap();
+
+        assert_eq!(config.process.group_sort_order, GroupSortOrder::CountDesc);
+        assert_eq!(config.process.on_file_error, FileErrorPolicy::Skip);
+        assert!(config.process.dedup.ignore_case);
+        assert!(!config.process.dedup.normalize_whitespace);
+    }
+
+    #[test]
+    fn test_checkpoint_fingerprint_tracks_inputs() {
+        let dir = std::env::temp_dir().join("report_forge_checkpoint_fingerprint_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("findings.csv");
+        std::fs::write(&file, "A,B\n1,2\n").unwrap();
+
+        let config = ReportConfig {
+            excel_files: vec![file.display().to_string()],
+            ..ReportConfig::default()
+        };
+        let fingerprint = GenerationCheckpoint::input_fingerprint;
+        let original = fingerprint(&config);
+        assert_eq!(fingerprint(&config), original);
+        assert_eq!(original.len(), 64);
+
+        // 自定义关键字（HashMap）的插入顺序不影响指纹
+        let with_keywords = |levels: &[RiskLevel]| {
+            let mut keyworded = config.clone();
+            keyworded.process.severity_keywords = Some(
+                levels
+                    .iter()
+                    .map(|level| (level.clone(), vec![level.severity_name().to_string()]))
+                    .collect(),
+            );
+            fingerprint(&keyworded)
+        };
+        let levels = [
+            RiskLevel::High,
+            RiskLevel::Medium,
+            RiskLevel::Low,
+            RiskLevel::Unknown,
+        ];
+        let reversed: Vec<RiskLevel> = levels.iter().rev().cloned().collect();
+        assert_eq!(with_keywords(&levels), with_keywords(&reversed));
+        assert_ne!(with_keywords(&levels), original);
+
+        // 处理选项变化
+        let mut changed = config.clone();
+        changed.process.group_sort_order = GroupSortOrder::Alphabetical;
+        assert_ne!(fingerprint(&changed), original);
+
+        // 文件内容（大小）变化
+        std::fs::write(&file, "A,B\n1,2\n3,4\n").unwrap();
+        assert_ne!(fingerprint(&config), original);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_statistics_summary_template() {
+        let mut config = ReportConfig::default();
+        assert_eq!(
+            config.statistics_summary(12, 3).unwrap().as_deref(),
+            Some("共发现 12 条问题，归并为 3 类")
+        );
+
+        config.statistics_summary_template =
+            "{total_records} findings in {total_groups} categories".to_string();
+        assert_eq!(
+            config.statistics_summary(12, 3).unwrap().as_deref(),
+            Some("12 findings in 3 categories")
+        );
+
+        config.statistics_summary_template = "{total}".to_string();
+        assert!(config.statistics_summary(12, 3).is_err());
+
+        config.statistics_summary_template = String::new();
+        assert_eq!(config.statistics_summary(12, 3).unwrap(), None);
+    }
+
+    #[test]
+    fn test_section_title_template() {
+        let mut config = ReportConfig::default();
+        assert_eq!(config.section_title(2, "XSS", "中危", 5).unwrap(), "2、XSS");
+
+        config.section_title_template = "{seq}. {name}（{severity}，{count}处）".to_string();
+        assert_eq!(
+            config.section_title(2, "XSS", "中危", 5).unwrap(),
+            "2. XSS（中危，5处）"
+        );
+
+        config.section_title_template = "{title}".to_string();
+        assert!(config.section_title(2, "XSS", "中危", 5).is_err());
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("章节标题模板无效"));
+
+        config.section_title_template = " ".to_string();
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("章节标题模板不能为空"));
+    }
+
+    #[test]
+    fn test_risk_text_by_language() {
+        assert_eq!(
+            RiskLevel::Medium.text(Language::Zh),
+            "☐ 高危风险  ☑ 中危风险  ☐ 低危风险"
+        );
+        assert_eq!(
+            RiskLevel::Unknown.text(Language::Zh),
+            "☐ 高危风险  ☐ 中危风险  ☐ 低危风险"
+        );
+        assert_eq!(
+            RiskLevel::Low.text(Language::En),
+            "☐ High Risk  ☐ Medium Risk  ☑ Low Risk"
+        );
+        assert_eq!(Language::Zh.severity_label(&RiskLevel::Unknown), "未知");
+        assert_eq!(Language::En.severity_label(&RiskLevel::High), "High");
+    }
+
+    #[test]
+    fn test_generation_stamp_text() {
+        let mut config = ReportConfig::default();
+        assert_eq!(config.generation_stamp_text(), None);
+
+        config.generation_stamp = true;
+        let stamp = config.generation_stamp_text().unwrap();
+        let prefix = format!("由 report-forge v{} 于 ", env!("CARGO_PKG_VERSION"));
+        assert!(stamp.starts_with(&prefix));
+        assert!(stamp.ends_with(" 生成"));
+
+        config.language = Language::En;
+        let stamp = config.generation_stamp_text().unwrap();
+        let prefix = format!(
+            "Generated by report-forge v{} at ",
+            env!("CARGO_PKG_VERSION")
+        );
+        assert!(stamp.starts_with(&prefix));
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_config() {
+        let dir = std::env::temp_dir().join("report_forge_validate_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let excel = dir.join("input.xlsx");
+        std::fs::write(&excel, b"").unwrap();
+
+        let output_dir = dir.join("out");
+        let _ = std::fs::remove_dir_all(&output_dir);
+
+        let config = ReportConfig {
+            excel_files: vec![excel.display().to_string()],
+            output_dir: output_dir.display().to_string(),
+            identifier_tag: "SZ25QT9B00WT".to_string(),
+            ..ReportConfig::default()
+        };
+
+        assert!(config.validate().is_ok());
+        // 校验不创建输出目录，也不留下探测文件
+        assert!(!output_dir.exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_validate_rejects_output_dir_under_file() {
+        let dir = std::env::temp_dir().join("report_forge_validate_output_file_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("report.docx");
+        std::fs::write(&file, b"").unwrap();
+
+        let config = ReportConfig {
+            output_dir: file.join("out").display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("输出目录不可用"));
+        assert!(message.contains("不是目录"));
+    }
+
+    #[test]
+    fn test_validate_without_output_skips_output_dir() {
+        let dir = std::env::temp_dir().join("report_forge_validate_without_output_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let excel = dir.join("input.xlsx");
+        std::fs::write(&excel, b"").unwrap();
+
+        let config = ReportConfig {
+            excel_files: vec![excel.display().to_string()],
+            identifier_tag: "TAG".to_string(),
+            output_filename_template: "{unknown}".to_string(),
+            ..ReportConfig::default()
+        };
+
+        assert!(config.validate().is_err());
+        assert!(config.validate_without_output().is_ok());
+    }
+
+    #[test]
+    fn test_report_number_format() {
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            wt_add: 2,
+            ..ReportConfig::default()
+        };
+        assert_eq!(config.report_number(1).unwrap(), "TAG0003");
+
+        config.report_number_format = "{tag}-{seq:05}".to_string();
+        assert_eq!(config.report_number(1).unwrap(), "TAG-00003");
+
+        config.report_number_format = "{tag}-{year}-{seq}".to_string();
+        let year = chrono::Local::now().format("%Y").to_string();
+        assert_eq!(
+            config.report_number(10).unwrap(),
+            format!("TAG-{}-12", year)
+        );
+
+        config.report_number_format = "{tag}-{num}".to_string();
+        assert!(config.report_number(1).is_err());
+        config.report_number_format = "{tag}-{seq".to_string();
+        assert!(config.report_number(1).is_err());
+    }
+
+    #[test]
+    fn test_report_numbers_by_severity() {
+        let group = |name: &str, severity: &str| {
+            (
+                format!("{}|{}", name, severity),
+                GroupInfo {
+                    b_column: name.to_string(),
+                    d_column: severity.to_string(),
+                    record_count: 1,
+                    records: Vec::new(),
+                    original_severity: None,
+                },
+            )
+        };
+        let grouped_data = vec![
+            group("SQL注入", "高危"),
+            group("XSS", "中危"),
+            group("命令注入", "高危"),
+            group("日志泄露", "低危"),
+            group("CSRF", "中危"),
+        ];
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            report_number_format: "{tag}{seq:04}".to_string(),
+            ..ReportConfig::default()
+        };
+
+        assert_eq!(
+            config.report_numbers(&grouped_data).unwrap(),
+            vec!["TAG0001", "TAG0002", "TAG0003", "TAG0004", "TAG0005"]
+        );
+
+        config.tag_by_severity = HashMap::from([
+            (RiskLevel::High, "H-".to_string()),
+            (RiskLevel::Medium, "M-".to_string()),
+        ]);
+        assert_eq!(
+            config.report_numbers(&grouped_data).unwrap(),
+            vec!["H-0001", "M-0001", "H-0002", "TAG0001", "M-0002"]
+        );
+    }
+
+    #[test]
+    fn test_output_file_stem() {
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            code_version: "V1.0".to_string(),
+            ceshi_user: "张三".to_string(),
+            ..ReportConfig::default()
+        };
+        assert_eq!(config.output_file_stem(123).unwrap(), "TAG_V1.0_123");
+
+        config.output_filename_template = "{date}_{tag}_{user}.docx".to_string();
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            config.output_file_stem(0).unwrap(),
+            format!("{}_TAG_张三", date)
+        );
+
+        config.code_version = "V1/2:3".to_string();
+        config.output_filename_template = "{version}".to_string();
+        assert_eq!(config.output_file_stem(0).unwrap(), "V1_2_3");
+
+        config.output_filename_template = "{user}".to_string();
+        config.ceshi_user = String::new();
+        assert!(config.output_file_stem(0).is_err());
+        config.output_filename_template = "{name}".to_string();
+        assert!(config.output_file_stem(0).is_err());
+    }
+
+    #[test]
+    fn test_classify_severity_with_custom_keywords() {
+        let keywords: HashMap<RiskLevel, Vec<String>> = serde_json::from_str(
+            r#"{"High": ["严重", "重要"], "Medium": ["一般"], "Low": ["提示"]}"#,
+        )
+        .unwrap();
+        let config = ReportConfig {
+            process: ProcessOptions {
+                severity_keywords: Some(keywords),
+                ..ProcessOptions::default()
+            },
+            ..ReportConfig::default()
+        };
+
+        assert_eq!(config.classify_severity("严重"), Some(RiskLevel::High));
+        assert_eq!(config.classify_severity("重要"), Some(RiskLevel::High));
+        assert_eq!(config.classify_severity("一般"), Some(RiskLevel::Medium));
+        assert_eq!(config.classify_severity("提示"), Some(RiskLevel::Low));
+        // 自定义关键字替代内置关键字
+        assert_eq!(config.classify_severity("高危"), Some(RiskLevel::Unknown));
+
+        // 未配置时使用内置关键字
+        assert_eq!(ReportConfig::default().classify_severity("严重"), None);
+        assert_eq!(RiskLevel::from_severity("高危"), RiskLevel::High);
+        assert_eq!(RiskLevel::from_severity("严重"), RiskLevel::Unknown);
+    }
+
+    #[test]
+    fn test_header_match_modes() {
+        let matches =
+            |mode: HeaderMatchMode, a: &str, b: &str| mode.comparable(a) == mode.comparable(b);
+
+        assert!(!matches(HeaderMatchMode::Exact, "问题名称 ", "问题名称"));
+        assert!(matches(HeaderMatchMode::Trimmed, "问题名称 ", "问题名称"));
+        assert!(!matches(
+            HeaderMatchMode::Trimmed,
+            "问题名称(必填)",
+            "问题名称"
+        ));
+        assert!(matches(
+            HeaderMatchMode::Normalized,
+            "问题名称（必填）",
+            "问题名称"
+        ));
+        assert!(matches(
+            HeaderMatchMode::Normalized,
+            "问题 名称 (必填)",
+            "问题名称"
+        ));
+        assert!(matches(
+            HeaderMatchMode::Normalized,
+            "ＣＶＳＳ：评分",
+            "CVSS:评分"
+        ));
+        assert!(!matches(
+            HeaderMatchMode::Normalized,
+            "问题名称",
+            "问题描述"
+        ));
+    }
+
+    #[test]
+    fn test_preset_round_trip() {
+        let path = std::env::temp_dir()
+            .join("report_forge_preset_test")
+            .join("preset.json");
+        let config = ReportConfig {
+            excel_files: vec!["a.xlsx".to_string(), "b.xlsx".to_string()],
+            output_dir: "out".to_string(),
+            identifier_tag: "SZ25QT9B00WT".to_string(),
+            wt_add: 3,
+            ceshi_user: "张三".to_string(),
+            include_toc: true,
+            process: ProcessOptions {
+                group_sort_order: GroupSortOrder::Alphabetical,
+                ..ProcessOptions::default()
+            },
+            ..ReportConfig::default()
+        };
+
+        config.save_preset(&path).unwrap();
+        let loaded = ReportConfig::load_preset(&path).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::to_value(&loaded).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_process_result_round_trip() {
+        let path = std::env::temp_dir().join("report_forge_result_snapshot_test.json");
+        let result = ExcelProcessResult::from_groups(vec![(
+            "XSS|中危".to_string(),
+            GroupInfo {
+                b_column: "XSS".to_string(),
+                d_column: "中危".to_string(),
+                records: Vec::new(),
+                original_severity: None,
+                record_count: 2,
+            },
+        )]);
+
+        result.save(&path).unwrap();
+        let loaded = ExcelProcessResult::load(&path).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::to_value(&loaded).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_load_preset_errors() {
+        let dir = std::env::temp_dir().join("report_forge_preset_error_test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let missing = ReportConfig::load_preset(dir.join("missing.json")).unwrap_err();
+        assert!(missing.to_string().contains("预设文件不存在"));
+
+        let malformed_path = dir.join("malformed.json");
+        std::fs::write(&malformed_path, "{ not json").unwrap();
+        let malformed = ReportConfig::load_preset(&malformed_path).unwrap_err();
+        assert!(malformed.to_string().contains("预设文件格式错误"));
+    }
+}
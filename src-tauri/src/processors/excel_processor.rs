@@ -1,57 +1,154 @@
-use crate::models::{ExcelProcessResult, ExcelRecord, GroupInfo, RiskInfo};
+use crate::error::{column_letter, ReportError};
+use crate::models::{
+    ColumnCheck, CsvEncoding, DedupOptions, ExcelProcessResult, ExcelRecord, ExcelValidationReport,
+    FileErrorPolicy, GroupCountChange, GroupInfo, GroupSortOrder, HeaderMatchMode,
+    MergedDataPreview, ProcessOptions, ProcessingProgress, RemovedDuplicate, ResultDiff, RiskInfo,
+    RiskLevel, SeverityEscalation, SheetInfo, SkippedFile, WorkbookInfo,
+};
 use anyhow::{Context, Result};
-use calamine::{open_workbook, Reader, Xlsx};
-use std::collections::HashMap;
+use calamine::{open_workbook, open_workbook_from_rs, Data, Ods, Reader, Xlsx};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
 use std::path::Path;
 
 pub struct ExcelProcessor;
 
-/// Excel原始数据结构
-#[derive(Debug, Clone)]
+/// 报告生成依赖的语义列（名称、列字母）
+const EXPECTED_COLUMNS: [(&str, &str); 6] = [
+    ("问题名称", "B"),
+    ("严重性级别", "D"),
+    ("文件路径", "I"),
+    ("相关代码", "J"),
+    ("漏洞说明", "K"),
+    ("整改建议", "N"),
+];
+
+/// 问题名称列、严重性级别列（分组依据）
+const NAME_COLUMN: &str = "B";
+const SEVERITY_COLUMN: &str = "D";
+
+/// 严重性级别列检查：抽样行数、能识别为已知风险等级的最低比例、提示中列出的无法识别取值个数
+const SEVERITY_SAMPLE_ROWS: usize = 1000;
+const MIN_KNOWN_SEVERITY_RATIO: f64 = 0.5;
+const SEVERITY_EXAMPLE_COUNT: usize = 5;
+
+/// 从内存数据读取时，错误信息中代替文件路径的名称
+const IN_MEMORY_SOURCE: &str = "<内存中的Excel数据>";
+
+/// OLE复合文档的文件头签名，加密的xlsx以此格式保存
+const OLE_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Excel原始数据结构（可传给前端编辑后再送回处理，单元格文本原样保留）
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawExcelData {
     pub headers: Vec<String>,
     pub rows: Vec<Vec<String>>,
 }
 
+impl RawExcelData {
+    /// 生成预览：表头、前 max_rows 行及总行数
+    pub fn preview(&self, max_rows: usize) -> MergedDataPreview {
+        MergedDataPreview {
+            headers: self.headers.clone(),
+            sample_rows: self.rows.iter().take(max_rows).cloned().collect(),
+            total_rows: self.rows.len(),
+        }
+    }
+}
+
 impl ExcelProcessor {
-    /// 读取Excel文件的原始数据（不进行去重和分组）
-    pub fn read_excel_raw<P: AsRef<Path>>(excel_file: P) -> Result<RawExcelData> {
-        let excel_file = excel_file.as_ref();
-        log::info!("读取Excel文件原始数据: {:?}", excel_file);
+    /// 将CSV文件内容解码为UTF-8文本（去除BOM），按指定编码解码失败时返回错误
+    pub fn decode_csv_text(bytes: &[u8], encoding: CsvEncoding) -> Result<String> {
+        let encoding = match encoding {
+            CsvEncoding::Auto => match encoding_rs::Encoding::for_bom(bytes) {
+                Some((encoding, _)) => encoding,
+                None if std::str::from_utf8(bytes).is_ok() => encoding_rs::UTF_8,
+                None => {
+                    log::info!("CSV文件不是UTF-8编码，按GBK解码");
+                    encoding_rs::GB18030
+                }
+            },
+            CsvEncoding::Utf8 => encoding_rs::UTF_8,
+            CsvEncoding::Gbk => encoding_rs::GB18030,
+        };
 
-        // 打开Excel文件
-        let mut workbook: Xlsx<_> = open_workbook(excel_file)
-            .with_context(|| format!("无法打开Excel文件: {:?}", excel_file))?;
+        let (text, had_errors) = encoding.decode_with_bom_removal(bytes);
+        if had_errors {
+            anyhow::bail!("CSV文件不是有效的{}编码", encoding.name());
+        }
 
-        // 获取第一个工作表
-        let sheet_name = workbook
-            .sheet_names()
-            .first()
-            .context("Excel文件中没有工作表")?
-            .clone();
+        Ok(text.into_owned())
+    }
 
-        let range = workbook
-            .worksheet_range(&sheet_name)
-            .context("无法读取工作表")?;
+    /// 读取CSV文件的原始数据（自动识别编码和分隔符），结构与Excel原始数据相同
+    pub fn read_csv_raw<P: AsRef<Path>>(csv_file: P) -> Result<RawExcelData> {
+        Self::read_csv_raw_with_encoding(csv_file, CsvEncoding::default())
+    }
 
-        log::info!("工作表尺寸: {:?}", range.get_size());
+    /// 按指定编码读取CSV文件的原始数据
+    pub fn read_csv_raw_with_encoding<P: AsRef<Path>>(
+        csv_file: P,
+        encoding: CsvEncoding,
+    ) -> Result<RawExcelData> {
+        let csv_file = csv_file.as_ref();
+        log::info!("读取CSV文件原始数据: {:?}", csv_file);
 
-        // 转换为行数据
-        let rows: Vec<Vec<String>> = range
-            .rows()
-            .map(|row| {
-                row.iter()
-                    .map(|cell| cell.to_string())
-                    .collect()
-            })
-            .collect();
+        let rows = Self::read_csv_rows(csv_file, encoding)?;
+        Self::split_header(csv_file, rows)
+    }
+
+    /// 读取Excel文件的原始数据（不进行去重和分组），.csv 文件按CSV读取
+    pub fn read_excel_raw<P: AsRef<Path>>(excel_file: P) -> Result<RawExcelData> {
+        Self::read_raw(excel_file.as_ref(), CsvEncoding::default())
+    }
+
+    /// 读取内存中的xlsx数据（如网页上传的文件）的原始数据，与 read_excel_raw 相同但不经过文件系统
+    pub fn read_excel_bytes(bytes: &[u8]) -> Result<RawExcelData> {
+        log::info!("读取内存中的Excel数据: {} 字节", bytes.len());
 
+        let source = Path::new(IN_MEMORY_SOURCE);
+        let mut workbook: Xlsx<_> = match open_workbook_from_rs(Cursor::new(bytes)) {
+            Ok(workbook) => workbook,
+            Err(_) if Self::is_encrypted_bytes(bytes) => {
+                return Err(ReportError::Encrypted {
+                    file: IN_MEMORY_SOURCE.to_string(),
+                }
+                .into())
+            }
+            Err(e) => {
+                return Err(anyhow::Error::new(e)
+                    .context("无法打开Excel数据（数据可能已损坏或不是.xlsx格式）"))
+            }
+        };
+        let rows = Self::first_sheet_rows(&mut workbook)?;
+        Self::split_header(source, rows)
+    }
+
+    /// 读取Excel或CSV文件的原始数据，csv_encoding 只对CSV文件生效
+    fn read_raw(excel_file: &Path, csv_encoding: CsvEncoding) -> Result<RawExcelData> {
+        log::info!("读取Excel文件原始数据: {:?}", excel_file);
+
+        let rows = Self::read_sheet_rows(excel_file, csv_encoding)?;
+        Self::split_header(excel_file, rows)
+    }
+
+    /// 第一行作为表头，其余为数据行；没有任何行或只有表头时返回对应错误
+    fn split_header(excel_file: &Path, rows: Vec<Vec<String>>) -> Result<RawExcelData> {
         if rows.is_empty() {
-            anyhow::bail!("Excel文件为空");
+            return Err(ReportError::EmptyWorkbook {
+                file: excel_file.display().to_string(),
+            }
+            .into());
         }
 
         if rows.len() <= 1 {
-            anyhow::bail!("Excel文件只有表头，没有数据行");
+            return Err(ReportError::NoDataRows {
+                file: excel_file.display().to_string(),
+            }
+            .into());
         }
 
         // 第一行是表头
@@ -66,259 +163,2600 @@ impl ExcelProcessor {
         })
     }
 
-    /// 合并多个Excel文件的原始数据，验证表头一致性
-    pub fn merge_excel_files<P: AsRef<Path>>(excel_files: &[P]) -> Result<RawExcelData> {
-        if excel_files.is_empty() {
-            anyhow::bail!("没有提供Excel文件");
+    /// 校验Excel文件（不进行处理），返回可供界面展示的检查结果；严重性级别按 options 识别
+    pub fn validate_excel_file<P: AsRef<Path>>(
+        excel_file: P,
+        options: &ProcessOptions,
+    ) -> ExcelValidationReport {
+        let excel_file = excel_file.as_ref();
+        log::info!("校验Excel文件: {:?}", excel_file);
+
+        let mut report = ExcelValidationReport {
+            file_path: excel_file.display().to_string(),
+            valid: false,
+            column_count: 0,
+            data_row_count: 0,
+            headers: Vec::new(),
+            column_checks: Vec::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let rows = match Self::read_sheet_rows(excel_file, options.csv_encoding) {
+            Ok(rows) => rows,
+            Err(e) => {
+                report.errors.push(format!("{:#}", e));
+                return report;
+            }
+        };
+
+        match rows.split_first() {
+            None => report.errors.push("Excel文件为空".to_string()),
+            Some((headers, data_rows)) => {
+                report.headers = headers.clone();
+                report.column_count = headers.len();
+                report.data_row_count = data_rows.len();
+                if data_rows.is_empty() {
+                    report
+                        .errors
+                        .push("Excel文件只有表头，没有数据行".to_string());
+                }
+                if let Some(warning) = Self::check_severity_rows(data_rows, options) {
+                    report.warnings.push(warning);
+                }
+            }
         }
 
-        log::info!("开始合并 {} 个Excel文件", excel_files.len());
+        for (label, column) in EXPECTED_COLUMNS {
+            let index = Self::column_index(column);
+            let header = report
+                .headers
+                .get(index)
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty());
+            let present = header.is_some();
+            if !present {
+                report
+                    .errors
+                    .push(format!("缺少{}列（{}列）", label, column));
+            }
+            report.column_checks.push(ColumnCheck {
+                label: label.to_string(),
+                column: column.to_string(),
+                header,
+                present,
+            });
+        }
 
-        // 读取第一个文件作为基准
-        let first_data = Self::read_excel_raw(&excel_files[0])?;
-        let mut merged_rows = first_data.rows.clone();
-        let reference_headers = first_data.headers.clone();
+        report.valid = report.errors.is_empty();
+        report
+    }
 
-        log::info!("基准表头: {:?}", reference_headers);
+    /// 按 options 检查原始数据行（不含表头）的严重性级别列，见 check_severity_column
+    pub fn check_severity_rows(rows: &[Vec<String>], options: &ProcessOptions) -> Option<String> {
+        let index = Self::column_index(SEVERITY_COLUMN);
+        let values = rows
+            .iter()
+            .filter_map(|row| row.get(index))
+            .map(String::as_str);
+        Self::check_severity_column(values, |value| options.risk_level(value))
+    }
 
-        // 逐个读取并合并其他文件
-        for (index, excel_file) in excel_files.iter().enumerate().skip(1) {
-            let current_data = Self::read_excel_raw(excel_file)?;
+    /// 按 options 检查处理结果中各记录的严重性级别原始取值，见 check_severity_column；
+    /// 适用于合并、流式及断点续读等所有处理方式
+    pub fn check_result_severity(
+        result: &ExcelProcessResult,
+        options: &ProcessOptions,
+    ) -> Option<String> {
+        let values = result
+            .grouped_data
+            .iter()
+            .flat_map(|(_, info)| &info.records)
+            .filter_map(|record| record.data.get(SEVERITY_COLUMN)?.as_deref());
+        Self::check_severity_column(values, |value| options.risk_level(value))
+    }
 
-            // 验证表头是否一致
-            if current_data.headers.len() != reference_headers.len() {
-                anyhow::bail!(
-                    "文件 {} 的表头列数({})与第一个文件({})不一致",
-                    excel_file.as_ref().display(),
-                    current_data.headers.len(),
-                    reference_headers.len()
-                );
+    /// 抽样检查严重性级别列：非空取值中能识别为已知风险等级的不足一半时（通常是列映射有误，
+    /// 所有分组都会成为未知等级），返回附带无法识别取值示例的提示
+    pub fn check_severity_column<'a, I, F>(values: I, classify: F) -> Option<String>
+    where
+        I: IntoIterator<Item = &'a str>,
+        F: Fn(&str) -> RiskLevel,
+    {
+        let mut total = 0;
+        let mut known = 0;
+        let mut examples: Vec<&str> = Vec::new();
+        for value in values
+            .into_iter()
+            .take(SEVERITY_SAMPLE_ROWS)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+        {
+            total += 1;
+            if classify(value) != RiskLevel::Unknown {
+                known += 1;
+            } else if examples.len() < SEVERITY_EXAMPLE_COUNT && !examples.contains(&value) {
+                examples.push(value);
             }
+        }
 
-            // 验证每一列的表头内容是否一致
-            for (i, (current_header, reference_header)) in current_data.headers.iter()
-                .zip(reference_headers.iter())
-                .enumerate()
-            {
-                if current_header.trim() != reference_header.trim() {
-                    anyhow::bail!(
-                        "文件 {} 的第{}列表头(\"{}\")与第一个文件(\"{}\")不一致",
-                        excel_file.as_ref().display(),
-                        i + 1,
-                        current_header,
-                        reference_header
+        if total == 0 || known as f64 >= total as f64 * MIN_KNOWN_SEVERITY_RATIO {
+            return None;
+        }
+        Some(format!(
+            "严重性级别列（{}列）中只有 {}/{} 个取值能识别为风险等级，请确认列映射是否正确；无法识别的取值如：{}",
+            SEVERITY_COLUMN,
+            known,
+            total,
+            examples.join("、")
+        ))
+    }
+
+    /// 单个列字母（A-Z）对应的下标
+    fn column_index(column: &str) -> usize {
+        (column.as_bytes()[0] - b'A') as usize
+    }
+
+    /// 读取第一个工作表的全部行（含表头），.csv 文件按CSV读取，.ods 文件按OpenDocument读取
+    fn read_sheet_rows(excel_file: &Path, csv_encoding: CsvEncoding) -> Result<Vec<Vec<String>>> {
+        if Self::is_csv_file(excel_file) {
+            return Self::read_csv_rows(excel_file, csv_encoding);
+        }
+        if Self::has_extension(excel_file, "ods") {
+            let mut workbook = Self::open_ods(excel_file)?;
+            return Self::first_sheet_rows(&mut workbook);
+        }
+
+        // 打开Excel文件
+        let mut workbook = Self::open_xlsx(excel_file)?;
+        Self::first_sheet_rows(&mut workbook)
+    }
+
+    /// 读取工作簿第一个非空工作表的全部行（含表头），所有工作表都为空时返回第一个工作表
+    fn first_sheet_rows<RS, R>(workbook: &mut R) -> Result<Vec<Vec<String>>>
+    where
+        RS: std::io::Read + std::io::Seek,
+        R: Reader<RS>,
+        R::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let sheet_names = workbook.sheet_names();
+        let first_sheet = sheet_names.first().context("Excel文件中没有工作表")?;
+
+        let mut range = None;
+        for sheet_name in &sheet_names {
+            let sheet = workbook
+                .worksheet_range(sheet_name)
+                .map_err(|e| Self::sheet_error(sheet_name, e))?;
+            if !sheet.is_empty() {
+                if sheet_name != first_sheet {
+                    log::info!(
+                        "第一个工作表 {:?} 为空，改用工作表 {:?}",
+                        first_sheet,
+                        sheet_name
                     );
                 }
+                range = Some(sheet);
+                break;
             }
-
-            // 表头一致，合并数据行
-            log::info!("文件 {} 表头验证通过，合并 {} 行数据", index + 1, current_data.rows.len());
-            merged_rows.extend(current_data.rows);
+            range.get_or_insert(sheet);
         }
+        let range = range.unwrap_or_default();
 
-        log::info!("合并完成！总数据行数: {}", merged_rows.len());
+        log::info!("工作表尺寸: {:?}", range.get_size());
 
-        Ok(RawExcelData {
-            headers: reference_headers,
-            rows: merged_rows,
+        // 转换为行数据
+        let rows: Vec<Vec<String>> = range
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(Self::cell_to_string)
+                    .collect()
+            })
+            .collect();
+
+        Ok(rows)
+    }
+
+    /// 按扩展名判断是否为CSV文件
+    fn is_csv_file(path: &Path) -> bool {
+        Self::has_extension(path, "csv")
+    }
+
+    /// 扩展名是否为 extension（不区分大小写）
+    fn has_extension(path: &Path, extension: &str) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+    }
+
+    /// 读取工作表失败的错误，消息中包含工作表名称及原因
+    fn sheet_error<E>(sheet_name: &str, error: E) -> anyhow::Error
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let message = format!("无法读取工作表 {:?}: {}", sheet_name, error);
+        anyhow::Error::new(error).context(message)
+    }
+
+    /// 打开OpenDocument表格（.ods），文件不存在时返回 FileNotFound
+    fn open_ods(ods_file: &Path) -> Result<Ods<BufReader<File>>> {
+        Self::ensure_file_exists(ods_file)?;
+
+        open_workbook(ods_file).with_context(|| {
+            format!(
+                "无法打开ODS文件（文件可能已损坏或不是.ods格式）: {:?}",
+                ods_file
+            )
         })
     }
 
-    /// 从合并后的原始数据处理为结构化结果
-    pub fn process_raw_data(raw_data: RawExcelData) -> Result<ExcelProcessResult> {
-        log::info!("开始处理合并后的数据");
+    /// 读取CSV文件的全部行（含表头），各行补齐到相同列数
+    fn read_csv_rows(csv_file: &Path, encoding: CsvEncoding) -> Result<Vec<Vec<String>>> {
+        Self::ensure_file_exists(csv_file)?;
 
-        let rows = raw_data.rows;
+        let bytes =
+            std::fs::read(csv_file).with_context(|| format!("无法读取CSV文件: {:?}", csv_file))?;
+        let text = Self::decode_csv_text(&bytes, encoding)
+            .with_context(|| format!("无法解码CSV文件: {:?}", csv_file))?;
 
-        // 创建列名（A-P）
-        let column_count = if !rows.is_empty() {
-            rows[0].len()
-        } else {
-            0
-        };
-        let column_names: Vec<String> = (0..column_count)
-            .map(|i| format!("{}", (b'A' + i as u8) as char))
-            .collect();
+        let mut rows = Self::parse_csv(&text);
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut rows {
+            row.resize(width, String::new());
+        }
 
-        log::info!("列数: {}, 列名: {:?}", column_count, column_names);
-
-        // 转换为记录格式
-        let mut records: Vec<HashMap<String, Option<String>>> = Vec::new();
-
-        for row in &rows {
-            let mut record = HashMap::new();
-            for (i, value) in row.iter().enumerate() {
-                if i < column_names.len() {
-                    let col_name: &String = &column_names[i];
-                    let cleaned = value.trim();
-                    record.insert(
-                        col_name.clone(),
-                        if cleaned.is_empty() {
-                            None
-                        } else {
-                            Some(cleaned.to_string())
-                        },
-                    );
+        Ok(rows)
+    }
+
+    /// 按表头行中出现次数最多的字符识别分隔符（逗号、分号、制表符），默认逗号
+    fn detect_csv_delimiter(text: &str) -> char {
+        let header = text.lines().next().unwrap_or("");
+        let mut delimiter = ',';
+        let mut max_count = 0;
+        for candidate in [',', ';', '\t'] {
+            let count = header.matches(candidate).count();
+            if count > max_count {
+                delimiter = candidate;
+                max_count = count;
+            }
+        }
+        delimiter
+    }
+
+    /// 解析CSV文本：支持双引号包裹的字段（"" 转义为 "，可包含分隔符和换行），跳过空行
+    fn parse_csv(text: &str) -> Vec<Vec<String>> {
+        let delimiter = Self::detect_csv_delimiter(text);
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut at_field_start = true;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                match c {
+                    '"' if chars.peek() == Some(&'"') => {
+                        field.push('"');
+                        chars.next();
+                    }
+                    '"' => in_quotes = false,
+                    c => field.push(c),
+                }
+                continue;
+            }
+
+            // 只有字段开头的引号表示带引号的字段，字段中间的引号按普通字符保留
+            let field_start = std::mem::replace(&mut at_field_start, false);
+            match c {
+                '"' if field_start => in_quotes = true,
+                c if c == delimiter => {
+                    row.push(std::mem::take(&mut field));
+                    at_field_start = true;
                 }
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' | '\r' => {
+                    if !row.is_empty() || !field.is_empty() {
+                        row.push(std::mem::take(&mut field));
+                        rows.push(std::mem::take(&mut row));
+                    }
+                    at_field_start = true;
+                }
+                c => field.push(c),
             }
-            records.push(record);
         }
 
-        log::info!("转换后记录数: {}", records.len());
+        if !row.is_empty() || !field.is_empty() {
+            row.push(field);
+            rows.push(row);
+        }
 
-        // 基于前7列（A-G）去重
-        let before_dedup = records.len();
-        records = Self::deduplicate_records(&records, &column_names[..7.min(column_names.len())]);
-        let after_dedup = records.len();
+        rows
+    }
 
-        log::info!("去重前记录数: {}, 去重后记录数: {}", before_dedup, after_dedup);
+    /// 打开xlsx工作簿，文件不存在时返回 FileNotFound，设置了打开密码时返回 Encrypted
+    fn open_xlsx(excel_file: &Path) -> Result<Xlsx<BufReader<File>>> {
+        Self::ensure_file_exists(excel_file)?;
 
-        // 按B列和D列分组
-        let grouped_data = Self::group_data_by_columns(&records, "B", "D");
+        match open_workbook(excel_file) {
+            Ok(workbook) => Ok(workbook),
+            Err(_) if Self::is_encrypted_workbook(excel_file) => Err(ReportError::Encrypted {
+                file: excel_file.display().to_string(),
+            }
+            .into()),
+            Err(e) => Err(anyhow::Error::new(e).context(format!(
+                "无法打开Excel文件（文件可能已损坏或不是.xlsx格式）: {:?}",
+                excel_file
+            ))),
+        }
+    }
 
-        // 创建结构化结果
-        let result = Self::create_structured_result(grouped_data, records.len());
+    /// 加密的xlsx保存为OLE复合文档（而不是zip），只读取文件头的8字节签名判断
+    fn is_encrypted_workbook(excel_file: &Path) -> bool {
+        let mut signature = [0u8; OLE_SIGNATURE.len()];
+        File::open(excel_file)
+            .and_then(|mut file| file.read_exact(&mut signature))
+            .is_ok_and(|_| Self::is_encrypted_bytes(&signature))
+    }
 
-        log::info!(
-            "处理完成！总记录数: {}, 分组数: {}",
-            result.total_records,
-            result.total_groups
-        );
+    /// 无法按xlsx打开的数据是否为OLE复合文档（加密的xlsx）
+    fn is_encrypted_bytes(bytes: &[u8]) -> bool {
+        bytes.starts_with(&OLE_SIGNATURE)
+    }
 
-        Ok(result)
+    /// 列出各工作表的名称、表头及数据行数（只读取工作表，不进行去重和分组）
+    pub fn inspect_workbook<P: AsRef<Path>>(excel_file: P) -> Result<WorkbookInfo> {
+        let excel_file = excel_file.as_ref();
+        log::info!("读取工作簿概况: {:?}", excel_file);
+
+        // 与读取数据时相同按扩展名选择格式，CSV文件视为只有一个以文件名命名的工作表
+        let sheets = if Self::is_csv_file(excel_file) {
+            let rows = Self::read_csv_rows(excel_file, CsvEncoding::default())?;
+            let name = excel_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            vec![SheetInfo {
+                name,
+                row_count: rows.len().saturating_sub(1),
+                headers: rows.into_iter().next().unwrap_or_default(),
+            }]
+        } else if Self::has_extension(excel_file, "ods") {
+            Self::sheet_infos(&mut Self::open_ods(excel_file)?)?
+        } else {
+            Self::sheet_infos(&mut Self::open_xlsx(excel_file)?)?
+        };
+
+        Ok(WorkbookInfo {
+            file_path: excel_file.display().to_string(),
+            sheets,
+        })
     }
 
-    /// 处理Excel文件并返回结构化结果（保留向后兼容）
-    pub fn process_excel_to_json<P: AsRef<Path>>(excel_file: P) -> Result<ExcelProcessResult> {
-        // 读取原始数据
-        let raw_data = Self::read_excel_raw(excel_file)?;
-        // 处理原始数据
-        Self::process_raw_data(raw_data)
+    /// 工作簿中各工作表的名称、表头及数据行数
+    fn sheet_infos<RS, R>(workbook: &mut R) -> Result<Vec<SheetInfo>>
+    where
+        RS: std::io::Read + std::io::Seek,
+        R: Reader<RS>,
+        R::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let mut sheets = Vec::new();
+        for name in workbook.sheet_names() {
+            let range = workbook
+                .worksheet_range(&name)
+                .map_err(|e| Self::sheet_error(&name, e))?;
+
+            let headers = range
+                .rows()
+                .next()
+                .map(|row| row.iter().map(Self::cell_to_string).collect())
+                .unwrap_or_default();
+
+            sheets.push(SheetInfo {
+                name,
+                headers,
+                row_count: range.height().saturating_sub(1),
+            });
+        }
+        Ok(sheets)
     }
 
-    /// 基于指定列去重
-    fn deduplicate_records(
-        records: &[HashMap<String, Option<String>>],
-        check_columns: &[String],
-    ) -> Vec<HashMap<String, Option<String>>> {
-        let mut seen_keys: std::collections::HashSet<String> = std::collections::HashSet::new();
-        let mut unique_records = Vec::new();
+    /// 文件不存在时返回 FileNotFound，而不是打开失败的底层错误
+    fn ensure_file_exists(excel_file: &Path) -> Result<()> {
+        if !excel_file.is_file() {
+            return Err(ReportError::FileNotFound {
+                path: excel_file.display().to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
 
-        for record in records {
-            // 创建组合键
-            let key: String = check_columns
-                .iter()
-                .map(|col| {
-                    record
-                        .get(col)
-                        .and_then(|v| v.as_ref())
-                        .map(|s| s.as_str())
-                        .unwrap_or("")
-                })
-                .collect::<Vec<&str>>()
-                .join("|");
-
-            if seen_keys.insert(key) {
-                unique_records.push(record.clone());
+    /// 按单元格类型转换为文本：整数不带小数点，日期输出为ISO格式，其余保持原样
+    ///
+    /// 富文本单元格（共享字符串或内联字符串中的多个格式片段）由calamine按顺序拼接为完整文本，
+    /// 注音（rPh）不计入，见 tests/pipeline.rs 中的富文本测试
+    fn cell_to_string(cell: &Data) -> String {
+        match cell {
+            Data::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => (*f as i64).to_string(),
+            Data::DateTime(dt) if dt.is_datetime() => match dt.as_datetime() {
+                Some(datetime) if datetime.time() == chrono::NaiveTime::MIN => {
+                    datetime.format("%Y-%m-%d").to_string()
+                }
+                Some(datetime) => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+                None => cell.to_string(),
+            },
+            _ => cell.to_string(),
+        }
+    }
+
+    /// 按 options 中的表头比较方式、CSV编码合并多个Excel（或CSV）文件的原始数据，
+    /// 任一文件读取失败或表头不一致时返回错误（按 on_file_error 跳过文件见 merge_excel_files_with_policy）
+    pub fn merge_excel_files<P: AsRef<Path>>(
+        excel_files: &[P],
+        options: &ProcessOptions,
+    ) -> Result<RawExcelData> {
+        let options = ProcessOptions {
+            on_file_error: FileErrorPolicy::Abort,
+            ..options.clone()
+        };
+        let (merged, _) = Self::merge_excel_files_with_policy(excel_files, &options)?;
+        Ok(merged)
+    }
+
+    /// 按 options 中的表头比较方式、CSV编码合并多个Excel（或CSV）文件的原始数据；
+    /// on_file_error 为 Skip 时跳过读取失败或表头不一致的文件，
+    /// 返回合并结果及被跳过的文件，全部文件都失败时返回错误
+    pub fn merge_excel_files_with_policy<P: AsRef<Path>>(
+        excel_files: &[P],
+        options: &ProcessOptions,
+    ) -> Result<(RawExcelData, Vec<SkippedFile>)> {
+        let ProcessOptions {
+            header_match_mode: header_mode,
+            csv_encoding,
+            on_file_error,
+            ..
+        } = *options;
+
+        if excel_files.is_empty() {
+            anyhow::bail!("没有提供Excel文件");
+        }
+
+        log::info!("开始合并 {} 个Excel文件", excel_files.len());
+
+        // 第一个读取成功的文件作为基准
+        let mut merged: Option<RawExcelData> = None;
+        let mut skipped_files = Vec::new();
+
+        for (index, excel_file) in excel_files.iter().enumerate() {
+            let excel_file = excel_file.as_ref();
+            let read = Self::read_raw(excel_file, csv_encoding).and_then(|data| {
+                // 验证表头是否一致
+                if let Some(reference) = &merged {
+                    Self::validate_headers(
+                        excel_file,
+                        &data.headers,
+                        &reference.headers,
+                        header_mode,
+                    )?;
+                }
+                Ok(data)
+            });
+
+            let current_data = match read {
+                Ok(data) => data,
+                Err(e) => {
+                    Self::skip_file(excel_file, e, on_file_error, &mut skipped_files)?;
+                    continue;
+                }
+            };
+
+            match &mut merged {
+                Some(merged) => {
+                    // 表头一致，合并数据行
+                    log::info!("文件 {} 表头验证通过，合并 {} 行数据", index + 1, current_data.rows.len());
+                    merged.rows.extend(current_data.rows);
+                }
+                None => {
+                    log::info!("基准表头: {:?}", current_data.headers);
+                    merged = Some(current_data);
+                }
             }
         }
 
-        unique_records
+        let merged = Self::ensure_any_succeeded(merged, &skipped_files)?;
+        log::info!("合并完成！总数据行数: {}", merged.rows.len());
+
+        Ok((merged, skipped_files))
     }
 
-    /// 按指定列分组数据
-    fn group_data_by_columns(
-        records: &[HashMap<String, Option<String>>],
-        col_b: &str,
-        col_d: &str,
-    ) -> HashMap<String, Vec<HashMap<String, Option<String>>>> {
-        let mut grouped: HashMap<String, Vec<HashMap<String, Option<String>>>> = HashMap::new();
+    /// 文件读取失败：Abort 时返回该错误，Skip 时记录警告并加入跳过列表
+    fn skip_file(
+        excel_file: &Path,
+        error: anyhow::Error,
+        on_file_error: FileErrorPolicy,
+        skipped_files: &mut Vec<SkippedFile>,
+    ) -> Result<()> {
+        if on_file_error == FileErrorPolicy::Abort {
+            return Err(error);
+        }
 
-        for record in records {
-            let key_b = record
-                .get(col_b)
-                .and_then(|v| v.as_ref())
-                .map(|s| s.as_str())
-                .unwrap_or("")
-                .to_string();
-            let key_d = record
-                .get(col_d)
-                .and_then(|v| v.as_ref())
-                .map(|s| s.as_str())
-                .unwrap_or("")
-                .to_string();
-            let group_key = format!("{}|{}", key_b, key_d);
+        log::warn!("跳过读取失败的文件 {}: {:#}", excel_file.display(), error);
+        skipped_files.push(SkippedFile {
+            file: excel_file.display().to_string(),
+            reason: format!("{:#}", error),
+        });
+        Ok(())
+    }
+
+    /// 没有任何文件读取成功时返回错误，列出各文件的失败原因
+    fn ensure_any_succeeded<T>(result: Option<T>, skipped_files: &[SkippedFile]) -> Result<T> {
+        result.with_context(|| {
+            let reasons: Vec<String> = skipped_files
+                .iter()
+                .map(|skipped| format!("{}: {}", skipped.file, skipped.reason))
+                .collect();
+            format!("所有文件均读取失败：\n{}", reasons.join("\n"))
+        })
+    }
+
+    /// 从合并后的原始数据处理为结构化结果（按 options 中的去重选项、分组排序方式和风险升级规则）
+    pub fn process_raw_data(
+        raw_data: RawExcelData,
+        options: &ProcessOptions,
+    ) -> Result<ExcelProcessResult> {
+        let mut result = Self::process_rows(raw_data.rows, options)?;
+        result.headers = raw_data.headers;
+        Ok(result)
+    }
+
+    /// 逐行处理数据为结构化结果，行在读取时即完成去重，不要求一次性持有全部数据行
+    pub fn process_rows<I>(rows: I, options: &ProcessOptions) -> Result<ExcelProcessResult>
+    where
+        I: IntoIterator<Item = Vec<String>>,
+    {
+        log::info!("开始处理合并后的数据");
 
-            grouped
-                .entry(group_key)
-                .or_insert_with(Vec::new)
-                .push(record.clone());
+        let mut deduplicator = RowDeduplicator::new(&options.dedup);
+        for row in rows {
+            deduplicator.push_row(row);
         }
 
-        grouped
+        Ok(Self::finish_processing(deduplicator, options))
     }
 
-    /// 创建结构化结果
-    fn create_structured_result(
-        grouped_data: HashMap<String, Vec<HashMap<String, Option<String>>>>,
-        total_records: usize,
-    ) -> ExcelProcessResult {
-        // 创建每个组的结构化数据
-        let mut grouped_structured: Vec<(String, GroupInfo, i32)> = Vec::new();
+    /// 流式读取并处理多个Excel文件，验证表头一致性；
+    /// 被移除的重复记录只保留行号及组合键（不含单元格数据），峰值内存主要取决于去重后的记录数
+    pub fn process_excel_files_streaming<P: AsRef<Path>>(
+        excel_files: &[P],
+        options: &ProcessOptions,
+    ) -> Result<ExcelProcessResult> {
+        Self::process_excel_files_resumable(excel_files, options, None, None, &mut |_, _| {})
+    }
 
-        for (group_key, records) in grouped_data {
-            let parts: Vec<&str> = group_key.split('|').collect();
-            let b_value = parts.get(0).unwrap_or(&"").to_string();
-            let d_value = parts.get(1).unwrap_or(&"").to_string();
-
-            let risk_info = RiskInfo::from_severity(&d_value);
-
-            let group_info = GroupInfo {
-                b_column: b_value,
-                d_column: d_value,
-                record_count: records.len(),
-                records: records
-                    .into_iter()
-                    .map(|data| ExcelRecord { data })
-                    .collect(),
-            };
+    /// 可继续的流式处理：按 hook 的间隔，每处理完若干个文件（读取成功或按策略跳过）
+    /// 将当前进度交给 hook（如保存断点），最后一个文件处理完后总会调用；
+    /// 每处理完一个文件另调用 on_file_done(已处理文件数, 文件总数)，用于显示进度
+    ///
+    /// resume 为之前保存的进度时沿用其去重状态，跳过已处理的文件（先按已处理的文件数调用一次 on_file_done）；
+    /// 调用方需保证输入文件及处理选项与保存进度时一致
+    pub fn process_excel_files_resumable<P: AsRef<Path>>(
+        excel_files: &[P],
+        options: &ProcessOptions,
+        resume: Option<ProcessingProgress>,
+        mut hook: Option<ProgressHook<'_>>,
+        on_file_done: &mut dyn FnMut(usize, usize),
+    ) -> Result<ExcelProcessResult> {
+        let ProcessOptions {
+            header_match_mode: header_mode,
+            csv_encoding,
+            on_file_error,
+            ..
+        } = *options;
 
-            grouped_structured.push((group_key, group_info, risk_info.priority));
+        if excel_files.is_empty() {
+            anyhow::bail!("没有提供Excel文件");
         }
 
-        // 按风险等级和记录数排序
-        grouped_structured.sort_by(|a, b| {
-            match a.2.cmp(&b.2) {
-                std::cmp::Ordering::Equal => b.1.record_count.cmp(&a.1.record_count),
-                other => other,
+        log::info!("开始流式处理 {} 个Excel文件", excel_files.len());
+
+        let mut deduplicator = RowDeduplicator::streaming(&options.dedup);
+        let mut reference_headers: Option<Vec<String>> = None;
+        let mut skipped_files = Vec::new();
+        let mut files_done = 0;
+
+        if let Some(progress) = resume {
+            if progress.files_done > excel_files.len() {
+                anyhow::bail!(
+                    "处理进度与输入文件不符：已处理 {} 个文件，共 {} 个",
+                    progress.files_done,
+                    excel_files.len()
+                );
             }
-        });
+            log::info!(
+                "从已保存的进度继续：已处理 {}/{} 个文件，{} 条记录",
+                progress.files_done,
+                excel_files.len(),
+                progress.records.len()
+            );
+            files_done = progress.files_done;
+            reference_headers = progress.headers;
+            skipped_files = progress.skipped_files;
+            deduplicator.restore(
+                progress.rows_read,
+                progress.blank_rows,
+                progress.column_count,
+                progress.records,
+                progress.removed_duplicates,
+            );
+            on_file_done(files_done, excel_files.len());
+        }
 
-        // 移除优先级信息
-        let grouped_data: Vec<(String, GroupInfo)> = grouped_structured
-            .into_iter()
-            .map(|(key, info, _)| (key, info))
-            .collect();
+        for excel_file in &excel_files[files_done..] {
+            let excel_file = excel_file.as_ref();
+            let reference = reference_headers.as_deref();
 
-        ExcelProcessResult {
-            total_groups: grouped_data.len(),
-            total_records,
-            grouped_data,
+            // 表头在数据行之前验证，数据行直接加入去重；
+            // 之后读取失败时撤销该文件已加入的记录，跳过的文件不留下部分数据
+            let mark = deduplicator.mark();
+            let streamed =
+                Self::stream_file_rows(excel_file, csv_encoding, reference, header_mode, |row| {
+                    deduplicator.push_row(row)
+                });
+
+            match streamed {
+                Ok(headers) => {
+                    reference_headers.get_or_insert(headers);
+                }
+                Err(e) => {
+                    deduplicator.rollback(mark);
+                    Self::skip_file(excel_file, e, on_file_error, &mut skipped_files)?
+                }
+            }
+
+            files_done += 1;
+            on_file_done(files_done, excel_files.len());
+            if let Some(hook) = hook.as_mut() {
+                if files_done % hook.every_files.max(1) == 0 || files_done == excel_files.len() {
+                    let headers = reference_headers.as_deref();
+                    (hook.on_progress)(deduplicator.progress(files_done, headers, &skipped_files))?;
+                }
+            }
         }
+
+        let headers = Self::ensure_any_succeeded(reference_headers, &skipped_files)?;
+
+        let mut result = Self::finish_processing(deduplicator, options);
+        result.skipped_files = skipped_files;
+        result.headers = headers;
+        Ok(result)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// 逐行读取一个文件：表头与 reference 比较（为 None 时不比较），数据行交给 on_row，返回表头
+    fn stream_file_rows<F>(
+        excel_file: &Path,
+        csv_encoding: CsvEncoding,
+        reference: Option<&[String]>,
+        header_mode: HeaderMatchMode,
+        mut on_row: F,
+    ) -> Result<Vec<String>>
+    where
+        F: FnMut(Vec<String>),
+    {
+        let mut headers: Option<Vec<String>> = None;
+        let mut data_rows = 0;
 
-    #[test]
-    fn test_risk_level_from_severity() {
-        use crate::models::RiskLevel;
+        Self::for_each_excel_row(excel_file, csv_encoding, |row| {
+            if headers.is_none() {
+                if let Some(reference) = reference {
+                    Self::validate_headers(excel_file, &row, reference, header_mode)?;
+                }
+                headers = Some(row);
+            } else {
+                on_row(row);
+                data_rows += 1;
+            }
+            Ok(())
+        })?;
 
-        assert_eq!(RiskLevel::from_severity("高危"), RiskLevel::High);
-        assert_eq!(RiskLevel::from_severity("中危"), RiskLevel::Medium);
-        assert_eq!(RiskLevel::from_severity("低危"), RiskLevel::Low);
-        assert_eq!(RiskLevel::from_severity("未知"), RiskLevel::Unknown);
+        let Some(headers) = headers else {
+            return Err(ReportError::EmptyWorkbook {
+                file: excel_file.display().to_string(),
+            }
+            .into());
+        };
+        if data_rows == 0 {
+            return Err(ReportError::NoDataRows {
+                file: excel_file.display().to_string(),
+            }
+            .into());
+        }
+
+        log::info!(
+            "文件 {} 流式读取完成，{} 行数据",
+            excel_file.display(),
+            data_rows
+        );
+        Ok(headers)
+    }
+
+    /// 逐行读取Excel第一个工作表（含表头行），不一次性加载全部单元格；
+    /// CSV、ODS文件整体读取后逐行回调
+    pub fn for_each_excel_row<P, F>(
+        excel_file: P,
+        csv_encoding: CsvEncoding,
+        mut on_row: F,
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+        F: FnMut(Vec<String>) -> Result<()>,
+    {
+        let excel_file = excel_file.as_ref();
+        if Self::is_csv_file(excel_file) || Self::has_extension(excel_file, "ods") {
+            return Self::read_sheet_rows(excel_file, csv_encoding)?
+                .into_iter()
+                .try_for_each(on_row);
+        }
+
+        let mut workbook = Self::open_xlsx(excel_file)?;
+
+        let sheet_names = workbook.sheet_names();
+        let first_sheet = sheet_names.first().context("Excel文件中没有工作表")?;
+
+        // 跳过开头的空工作表（没有任何单元格），所有工作表都为空时读取第一个
+        let mut sheet_name = first_sheet;
+        for name in &sheet_names {
+            let mut reader = workbook
+                .worksheet_cells_reader(name)
+                .map_err(|e| Self::sheet_error(name, e))?;
+            if reader
+                .next_cell()
+                .map_err(|e| Self::sheet_error(name, e))?
+                .is_some()
+            {
+                if name != first_sheet {
+                    log::info!("第一个工作表 {:?} 为空，改用工作表 {:?}", first_sheet, name);
+                }
+                sheet_name = name;
+                break;
+            }
+        }
+
+        let mut reader = workbook
+            .worksheet_cells_reader(sheet_name)
+            .map_err(|e| Self::sheet_error(sheet_name, e))?;
+
+        let dimensions = reader.dimensions();
+        let width = (dimensions.end.1.saturating_sub(dimensions.start.1) + 1) as usize;
+        let mut current_row: Option<u32> = None;
+        let mut values = vec![String::new(); width];
+
+        while let Some(cell) = reader.next_cell().context("无法读取单元格")? {
+            let (row, col) = cell.get_position();
+
+            if current_row != Some(row) {
+                if let Some(previous) = current_row {
+                    on_row(std::mem::replace(&mut values, vec![String::new(); width]))?;
+                    // 补齐中间的空行，与一次性读取的结果保持一致
+                    for _ in previous + 1..row {
+                        on_row(vec![String::new(); width])?;
+                    }
+                }
+                current_row = Some(row);
+            }
+
+            if let Some(slot) = col
+                .checked_sub(dimensions.start.1)
+                .and_then(|c| values.get_mut(c as usize))
+            {
+                *slot = Self::cell_to_string(&Data::from(cell.get_value().clone()));
+            }
+        }
+
+        if current_row.is_some() {
+            on_row(values)?;
+        }
+
+        Ok(())
+    }
+
+    /// 完成去重后的分组与排序：按 options 识别严重性级别后分组，记录保留Excel中的原始值
+    fn finish_processing(
+        deduplicator: RowDeduplicator,
+        options: &ProcessOptions,
+    ) -> ExcelProcessResult {
+        let before_dedup = deduplicator.row_count - deduplicator.blank_rows;
+        let records = deduplicator.records;
+        let removed_duplicates = deduplicator.removed;
+
+        if deduplicator.blank_rows > 0 {
+            log::info!("跳过空行: {} 行", deduplicator.blank_rows);
+        }
+        log::info!(
+            "去重前记录数: {}, 去重后记录数: {}",
+            before_dedup,
+            records.len()
+        );
+
+        // 按B列和识别后的D列分组
+        let grouped_data =
+            Self::group_data_by_columns(&records, NAME_COLUMN, SEVERITY_COLUMN, |value| {
+                options.classify_severity(value)
+            });
+
+        // 创建结构化结果
+        let mut result = Self::create_structured_result(
+            grouped_data,
+            records.len(),
+            options.group_sort_order,
+            &options.severity_escalation,
+        );
+        result.removed_duplicates = removed_duplicates;
+
+        log::info!(
+            "处理完成！总记录数: {}, 分组数: {}",
+            result.total_records,
+            result.total_groups
+        );
+
+        result
+    }
+
+    /// 按指定方式验证表头与基准表头一致，非逐字一致时记录实际采用的宽松匹配
+    fn validate_headers(
+        excel_file: &Path,
+        headers: &[String],
+        reference: &[String],
+        mode: HeaderMatchMode,
+    ) -> Result<()> {
+        let matches = headers.len() == reference.len()
+            && headers
+                .iter()
+                .zip(reference)
+                .all(|(current, reference)| mode.comparable(current) == mode.comparable(reference));
+
+        if !matches {
+            return Err(ReportError::HeaderMismatch {
+                file: excel_file.display().to_string(),
+                expected: reference.to_vec(),
+                got: headers.to_vec(),
+            }
+            .into());
+        }
+
+        let tolerated: Vec<String> = headers
+            .iter()
+            .zip(reference)
+            .enumerate()
+            .filter(|(_, (current, reference))| current != reference)
+            .map(|(i, (current, reference))| {
+                format!("{}列「{}」≈「{}」", column_letter(i), current, reference)
+            })
+            .collect();
+        if !tolerated.is_empty() {
+            log::warn!(
+                "文件 {} 的表头与第一个文件不完全相同，已按「{}」方式匹配: {}",
+                excel_file.display(),
+                mode.label(),
+                tolerated.join("，")
+            );
+        }
+
+        Ok(())
+    }
+
+    /// 处理Excel文件并返回结构化结果（保留向后兼容）
+    pub fn process_excel_to_json<P: AsRef<Path>>(
+        excel_file: P,
+        options: &ProcessOptions,
+    ) -> Result<ExcelProcessResult> {
+        // 读取原始数据
+        let raw_data = Self::read_raw(excel_file.as_ref(), options.csv_encoding)?;
+        // 处理原始数据
+        Self::process_raw_data(raw_data, options)
+    }
+
+    /// 基于指定列去重
+    pub fn deduplicate_records(
+        records: &[HashMap<String, Option<String>>],
+        check_columns: &[String],
+        options: &DedupOptions,
+    ) -> Vec<HashMap<String, Option<String>>> {
+        Self::deduplicate_records_with_removed(records, check_columns, options).0
+    }
+
+    /// 基于指定列去重，同时返回被移除的重复记录
+    pub fn deduplicate_records_with_removed(
+        records: &[HashMap<String, Option<String>>],
+        check_columns: &[String],
+        options: &DedupOptions,
+    ) -> (Vec<HashMap<String, Option<String>>>, Vec<RemovedDuplicate>) {
+        let mut seen_keys: HashSet<String> = HashSet::new();
+        let mut unique_records = Vec::new();
+        let mut removed = Vec::new();
+
+        for (row_index, record) in records.iter().enumerate() {
+            let key = Self::dedup_key(record, check_columns, options);
+
+            if seen_keys.insert(key.clone()) {
+                unique_records.push(record.clone());
+            } else {
+                removed.push(RemovedDuplicate {
+                    row_index,
+                    record: ExcelRecord {
+                        data: record.clone(),
+                        dedup_key: Some(key),
+                    },
+                });
+            }
+        }
+
+        (unique_records, removed)
+    }
+
+    /// 创建去重组合键
+    fn dedup_key(
+        record: &HashMap<String, Option<String>>,
+        check_columns: &[String],
+        options: &DedupOptions,
+    ) -> String {
+        check_columns
+            .iter()
+            .map(|col| {
+                record
+                    .get(col)
+                    .and_then(|v| v.as_ref())
+                    .map(|s| Self::normalize_dedup_value(s, options))
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<String>>()
+            .join("|")
+    }
+
+    /// 规范化去重比较值
+    fn normalize_dedup_value(value: &str, options: &DedupOptions) -> String {
+        let value = if options.normalize_whitespace {
+            value.split_whitespace().collect::<Vec<&str>>().join(" ")
+        } else {
+            value.to_string()
+        };
+
+        if options.ignore_case {
+            value.to_lowercase()
+        } else {
+            value
+        }
+    }
+
+    /// 按指定列分组数据（保持分组首次出现的顺序）
+    ///
+    /// 严重性级别列的值经 classify 识别为风险等级时按等级名称分组（如CVSS评分 9.8 归入“高危”），
+    /// 返回 None 时按原文分组；记录本身保留原始值
+    fn group_data_by_columns<F>(
+        records: &[ExcelRecord],
+        col_b: &str,
+        col_d: &str,
+        classify: F,
+    ) -> IndexMap<String, Vec<ExcelRecord>>
+    where
+        F: Fn(&str) -> Option<RiskLevel>,
+    {
+        let mut grouped: IndexMap<String, Vec<ExcelRecord>> = IndexMap::new();
+
+        for record in records {
+            let key_b = record
+                .data
+                .get(col_b)
+                .and_then(|v| v.as_ref())
+                .map(|s| s.as_str())
+                .unwrap_or("")
+                .to_string();
+            let value_d = record
+                .data
+                .get(col_d)
+                .and_then(|v| v.as_ref())
+                .map(|s| s.as_str())
+                .unwrap_or("");
+            let key_d = match classify(value_d) {
+                Some(level) => level.severity_name().to_string(),
+                None => value_d.to_string(),
+            };
+            let group_key = format!("{}|{}", key_b, key_d);
+
+            grouped.entry(group_key).or_default().push(record.clone());
+        }
+
+        grouped
+    }
+
+    /// 处理问题名称为空的分组：丢弃，或以指定名称归类
+    pub fn handle_uncategorized(
+        result: &ExcelProcessResult,
+        drop: bool,
+        label: &str,
+    ) -> ExcelProcessResult {
+        let mut grouped_data = Vec::with_capacity(result.grouped_data.len());
+        let mut uncategorized = 0;
+
+        for (group_key, info) in &result.grouped_data {
+            if !info.b_column.trim().is_empty() {
+                grouped_data.push((group_key.clone(), info.clone()));
+                continue;
+            }
+
+            uncategorized += 1;
+            if !drop {
+                let mut info = info.clone();
+                info.b_column = label.to_string();
+                grouped_data.push((format!("{}|{}", label, info.d_column), info));
+            }
+        }
+
+        if uncategorized > 0 {
+            if drop {
+                log::warn!("丢弃 {} 个问题名称为空的分组", uncategorized);
+            } else {
+                log::warn!("{} 个问题名称为空的分组归入「{}」", uncategorized, label);
+            }
+        }
+
+        result.regrouped(grouped_data)
+    }
+
+    /// 在基线结果之后追加新的分组：分组键（问题名称|严重性级别）不在基线中的才算新分组，
+    /// 基线中已有的分组保持原内容和顺序，因此已有章节的编号不变；
+    /// 两个结果的列不一致时返回 ColumnMismatch，避免合并后的记录缺少列
+    pub fn append_new_groups(
+        baseline: &ExcelProcessResult,
+        current: &ExcelProcessResult,
+    ) -> Result<ExcelProcessResult> {
+        Self::ensure_compatible_results(baseline, current)?;
+
+        let existing: HashSet<&str> = baseline
+            .grouped_data
+            .iter()
+            .map(|(group_key, _)| group_key.as_str())
+            .collect();
+
+        let mut grouped_data = baseline.grouped_data.clone();
+        grouped_data.extend(
+            current
+                .grouped_data
+                .iter()
+                .filter(|(group_key, _)| !existing.contains(group_key.as_str()))
+                .cloned(),
+        );
+
+        log::info!(
+            "基线报告 {} 个分组，新增 {} 个分组，编号从 {} 开始",
+            baseline.grouped_data.len(),
+            grouped_data.len() - baseline.grouped_data.len(),
+            baseline.grouped_data.len() + 1
+        );
+
+        Ok(current.regrouped(grouped_data))
+    }
+
+    /// 按分组键对比两次扫描的处理结果：新出现的分组、已消失的分组，以及两次都存在但记录数变化的分组
+    pub fn diff_results(old: &ExcelProcessResult, new: &ExcelProcessResult) -> ResultDiff {
+        let old_groups: HashMap<&str, &GroupInfo> = old
+            .grouped_data
+            .iter()
+            .map(|(group_key, info)| (group_key.as_str(), info))
+            .collect();
+        let new_keys: HashSet<&str> = new
+            .grouped_data
+            .iter()
+            .map(|(group_key, _)| group_key.as_str())
+            .collect();
+
+        let mut diff = ResultDiff::default();
+        for (group_key, info) in &new.grouped_data {
+            match old_groups.get(group_key.as_str()) {
+                None => diff.added_groups.push((group_key.clone(), info.clone())),
+                Some(old_info) if old_info.record_count != info.record_count => {
+                    diff.count_changes.push(GroupCountChange {
+                        group_key: group_key.clone(),
+                        old_count: old_info.record_count,
+                        new_count: info.record_count,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed_groups = old
+            .grouped_data
+            .iter()
+            .filter(|(group_key, _)| !new_keys.contains(group_key.as_str()))
+            .cloned()
+            .collect();
+
+        log::info!(
+            "结果对比：新增 {} 个分组，消失 {} 个分组，{} 个分组记录数变化",
+            diff.added_groups.len(),
+            diff.removed_groups.len(),
+            diff.count_changes.len()
+        );
+
+        diff
+    }
+
+    /// 检查两个处理结果的列是否一致（没有记录的结果与任何结果兼容）：
+    /// 两个结果都保存了表头时按宽松匹配比较表头名称，否则（旧版本保存的结果）比较记录中的列字母
+    fn ensure_compatible_results(
+        baseline: &ExcelProcessResult,
+        current: &ExcelProcessResult,
+    ) -> Result<()> {
+        if baseline.total_records == 0 || current.total_records == 0 {
+            return Ok(());
+        }
+
+        if !baseline.headers.is_empty() && !current.headers.is_empty() {
+            let comparable = |headers: &[String]| -> Vec<String> {
+                let mode = HeaderMatchMode::Normalized;
+                headers
+                    .iter()
+                    .map(|header| mode.comparable(header))
+                    .collect()
+            };
+            if comparable(&baseline.headers) == comparable(&current.headers) {
+                return Ok(());
+            }
+            return Err(ReportError::ColumnMismatch {
+                expected: baseline.headers.clone(),
+                got: current.headers.clone(),
+            }
+            .into());
+        }
+
+        let expected = baseline.columns();
+        let got = current.columns();
+        if expected.is_empty() || got.is_empty() || expected == got {
+            return Ok(());
+        }
+        Err(ReportError::ColumnMismatch { expected, got }.into())
+    }
+
+    /// 去掉记录数低于 min_record_count 的分组（分组之后执行）
+    pub fn filter_by_min_records(
+        result: &ExcelProcessResult,
+        min_record_count: usize,
+    ) -> ExcelProcessResult {
+        let (grouped_data, excluded): (Vec<_>, Vec<_>) = result
+            .grouped_data
+            .iter()
+            .cloned()
+            .partition(|(_, info)| info.record_count >= min_record_count);
+
+        if !excluded.is_empty() {
+            let excluded_records: usize = excluded.iter().map(|(_, info)| info.record_count).sum();
+            log::info!(
+                "按最少记录数 {} 过滤：排除 {} 个分组（共 {} 条记录）",
+                min_record_count,
+                excluded.len(),
+                excluded_records
+            );
+        }
+
+        result.regrouped(grouped_data)
+    }
+
+    /// 按风险等级过滤分组（分组之后执行，保留分组内的记录数不变）
+    pub fn filter_by_severity(
+        result: &ExcelProcessResult,
+        levels: &[RiskLevel],
+    ) -> ExcelProcessResult {
+        let grouped_data: Vec<(String, GroupInfo)> = result
+            .grouped_data
+            .iter()
+            .filter(|(_, info)| levels.contains(&RiskLevel::from_severity(&info.d_column)))
+            .cloned()
+            .collect();
+
+        log::info!(
+            "按严重性过滤：保留 {} 个分组，过滤掉 {} 个分组",
+            grouped_data.len(),
+            result.grouped_data.len() - grouped_data.len()
+        );
+
+        result.regrouped(grouped_data)
+    }
+
+    /// 创建结构化结果
+    ///
+    /// 记录数达到升级规则阈值的分组按升级后的风险等级排序和报告，原严重性级别保存在 original_severity；
+    /// 分组键改为升级后的级别（问题名称|升级后级别），与同名的已有分组合并为一个分组
+    fn create_structured_result(
+        grouped_data: IndexMap<String, Vec<ExcelRecord>>,
+        total_records: usize,
+        sort_order: GroupSortOrder,
+        escalation: &[SeverityEscalation],
+    ) -> ExcelProcessResult {
+        // 创建每个组的结构化数据（升级后的分组与同键分组合并，保持首次出现的位置）
+        let mut structured: IndexMap<String, GroupInfo> = IndexMap::new();
+
+        for (mut group_key, records) in grouped_data {
+            let parts: Vec<&str> = group_key.split('|').collect();
+            let b_value = parts.first().unwrap_or(&"").to_string();
+            let mut d_value = parts.get(1).unwrap_or(&"").to_string();
+
+            let mut original_severity = None;
+            let level = RiskLevel::from_severity(&d_value);
+            let escalated = SeverityEscalation::escalate(escalation, &level, records.len());
+            if let Some(escalated) = escalated {
+                log::info!(
+                    "分组 {} 共 {} 条记录，风险等级由{}升级为{}",
+                    group_key,
+                    records.len(),
+                    d_value,
+                    escalated.severity_name()
+                );
+                original_severity = Some(std::mem::replace(
+                    &mut d_value,
+                    escalated.severity_name().to_string(),
+                ));
+                group_key = format!("{}|{}", b_value, d_value);
+            }
+
+            match structured.entry(group_key) {
+                indexmap::map::Entry::Occupied(mut entry) => {
+                    log::info!("风险升级后的分组与已有分组 {} 合并", entry.key());
+                    let info = entry.get_mut();
+                    info.records.extend(records);
+                    info.record_count = info.records.len();
+                    info.original_severity = info.original_severity.take().or(original_severity);
+                }
+                indexmap::map::Entry::Vacant(entry) => {
+                    entry.insert(GroupInfo {
+                        b_column: b_value,
+                        d_column: d_value,
+                        record_count: records.len(),
+                        records,
+                        original_severity,
+                    });
+                }
+            }
+        }
+
+        let mut grouped_structured: Vec<(String, GroupInfo, i32)> = structured
+            .into_iter()
+            .map(|(group_key, group_info)| {
+                let priority = RiskInfo::from_severity(&group_info.d_column).priority;
+                (group_key, group_info, priority)
+            })
+            .collect();
+
+        // 按配置的方式排序（排序稳定，相同时保持首次出现顺序）；
+        // 风险等级和记录数都相同时再按分组键排序，保证章节顺序及编号可复现
+        match sort_order {
+            GroupSortOrder::RiskThenCount => grouped_structured.sort_by(|a, b| {
+                a.2.cmp(&b.2)
+                    .then_with(|| b.1.record_count.cmp(&a.1.record_count))
+                    .then_with(|| a.0.cmp(&b.0))
+            }),
+            GroupSortOrder::Alphabetical => {
+                grouped_structured.sort_by(|a, b| a.1.b_column.cmp(&b.1.b_column))
+            }
+            GroupSortOrder::CountDesc => {
+                grouped_structured.sort_by(|a, b| b.1.record_count.cmp(&a.1.record_count))
+            }
+            GroupSortOrder::OriginalOrder => {}
+        }
+
+        // 移除优先级信息
+        let grouped_data: Vec<(String, GroupInfo)> = grouped_structured
+            .into_iter()
+            .map(|(key, info, _)| (key, info))
+            .collect();
+
+        ExcelProcessResult {
+            total_records,
+            ..ExcelProcessResult::from_groups(grouped_data)
+        }
+    }
+}
+
+/// 流式处理的进度回调：每处理完 every_files 个文件（及最后一个文件）调用一次 on_progress
+pub struct ProgressHook<'a> {
+    pub every_files: usize,
+    pub on_progress: &'a mut dyn FnMut(ProcessingProgress) -> Result<()>,
+}
+
+/// 去重器在开始读取某个文件前的状态，该文件读取失败时据此回滚
+struct DedupMark {
+    records: usize,
+    removed: usize,
+    row_count: usize,
+    blank_rows: usize,
+}
+
+/// 流式去重器：逐行转换为记录并基于前7列（A-G）去重，只保留去重后的记录
+struct RowDeduplicator<'a> {
+    options: &'a DedupOptions,
+    /// 被移除的重复记录是否保留单元格数据（为 false 时只保留行号及组合键）
+    keep_removed_data: bool,
+    column_names: Vec<String>,
+    seen_keys: HashSet<String>,
+    records: Vec<ExcelRecord>,
+    removed: Vec<RemovedDuplicate>,
+    row_count: usize,
+    blank_rows: usize,
+}
+
+impl<'a> RowDeduplicator<'a> {
+    fn new(options: &'a DedupOptions) -> Self {
+        RowDeduplicator {
+            options,
+            keep_removed_data: true,
+            column_names: Vec::new(),
+            seen_keys: HashSet::new(),
+            records: Vec::new(),
+            removed: Vec::new(),
+            row_count: 0,
+            blank_rows: 0,
+        }
+    }
+
+    /// 流式处理使用的去重器：被移除的重复记录不保留单元格数据，内存不随重复行的内容增长
+    fn streaming(options: &'a DedupOptions) -> Self {
+        RowDeduplicator {
+            keep_removed_data: false,
+            ..Self::new(options)
+        }
+    }
+
+    /// 按列数创建列名（A-P）
+    fn column_names(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| format!("{}", (b'A' + i as u8) as char))
+            .collect()
+    }
+
+    /// 恢复之前保存的进度：按读取顺序还原已保留的记录及去重键
+    fn restore(
+        &mut self,
+        rows_read: usize,
+        blank_rows: usize,
+        column_count: usize,
+        records: Vec<ExcelRecord>,
+        removed: Vec<RemovedDuplicate>,
+    ) {
+        self.column_names = Self::column_names(column_count);
+        let check_columns = &self.column_names[..7.min(self.column_names.len())];
+
+        for mut record in records {
+            let key = record.dedup_key.clone().unwrap_or_else(|| {
+                ExcelProcessor::dedup_key(&record.data, check_columns, self.options)
+            });
+            self.seen_keys.insert(key.clone());
+            record.dedup_key = Some(key);
+            self.records.push(record);
+        }
+
+        self.removed = removed;
+        self.row_count = rows_read;
+        self.blank_rows = blank_rows;
+    }
+
+    /// 当前进度：已保留的记录按读取顺序保存，不分组
+    fn progress(
+        &self,
+        files_done: usize,
+        headers: Option<&[String]>,
+        skipped_files: &[SkippedFile],
+    ) -> ProcessingProgress {
+        ProcessingProgress {
+            files_done,
+            rows_read: self.row_count,
+            blank_rows: self.blank_rows,
+            column_count: self.column_names.len(),
+            headers: headers.map(<[String]>::to_vec),
+            records: self.records.clone(),
+            removed_duplicates: self.removed.clone(),
+            skipped_files: skipped_files.to_vec(),
+        }
+    }
+
+    /// 记录当前状态，之后加入的行可通过 rollback 撤销
+    fn mark(&self) -> DedupMark {
+        DedupMark {
+            records: self.records.len(),
+            removed: self.removed.len(),
+            row_count: self.row_count,
+            blank_rows: self.blank_rows,
+        }
+    }
+
+    /// 撤销 mark 之后加入的行：这些记录的去重键都是新加入的，一并移除
+    fn rollback(&mut self, mark: DedupMark) {
+        for record in self.records.drain(mark.records..) {
+            if let Some(key) = record.dedup_key {
+                self.seen_keys.remove(&key);
+            }
+        }
+        self.removed.truncate(mark.removed);
+        self.row_count = mark.row_count;
+        self.blank_rows = mark.blank_rows;
+    }
+
+    fn push_row(&mut self, row: Vec<String>) {
+        // 以第一行的列数创建列名（A-P）
+        if self.row_count == 0 {
+            self.column_names = Self::column_names(row.len());
+            log::info!("列数: {}, 列名: {:?}", row.len(), self.column_names);
+        }
+
+        // 跳过所有单元格都为空的行（行号仍计入，保证被移除记录的位置准确）
+        if row.iter().all(|value| value.trim().is_empty()) {
+            self.blank_rows += 1;
+            self.row_count += 1;
+            return;
+        }
+
+        let mut record = HashMap::new();
+        for (col_name, value) in self.column_names.iter().zip(row.iter()) {
+            let cleaned = value.trim();
+            record.insert(
+                col_name.clone(),
+                if cleaned.is_empty() {
+                    None
+                } else {
+                    Some(cleaned.to_string())
+                },
+            );
+        }
+
+        let check_columns = &self.column_names[..7.min(self.column_names.len())];
+        let key = ExcelProcessor::dedup_key(&record, check_columns, self.options);
+
+        if self.seen_keys.insert(key.clone()) {
+            self.records.push(ExcelRecord {
+                data: record,
+                dedup_key: Some(key),
+            });
+        } else {
+            self.removed.push(RemovedDuplicate {
+                row_index: self.row_count,
+                record: ExcelRecord {
+                    data: if self.keep_removed_data {
+                        record
+                    } else {
+                        HashMap::new()
+                    },
+                    dedup_key: Some(key),
+                },
+            });
+        }
+
+        self.row_count += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_to_string_handles_numbers_and_dates() {
+        use calamine::{ExcelDateTime, ExcelDateTimeType};
+
+        assert_eq!(ExcelProcessor::cell_to_string(&Data::Float(3.0)), "3");
+        assert_eq!(ExcelProcessor::cell_to_string(&Data::Float(2.5)), "2.5");
+        assert_eq!(ExcelProcessor::cell_to_string(&Data::Int(42)), "42");
+        assert_eq!(
+            ExcelProcessor::cell_to_string(&Data::String("高危".to_string())),
+            "高危"
+        );
+
+        let date = ExcelDateTime::new(45658.0, ExcelDateTimeType::DateTime, false);
+        assert_eq!(
+            ExcelProcessor::cell_to_string(&Data::DateTime(date)),
+            "2025-01-01"
+        );
+        let datetime = ExcelDateTime::new(45658.5, ExcelDateTimeType::DateTime, false);
+        assert_eq!(
+            ExcelProcessor::cell_to_string(&Data::DateTime(datetime)),
+            "2025-01-01 12:00:00"
+        );
+    }
+
+    #[test]
+    fn test_missing_file_is_reported_as_file_not_found() {
+        let error = ExcelProcessor::merge_excel_files(
+            &["/不存在/问题列表.xlsx"],
+            &ProcessOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ReportError>(),
+            Some(ReportError::FileNotFound { path }) if path == "/不存在/问题列表.xlsx"
+        ));
+    }
+
+    #[test]
+    fn test_inspect_workbook_lists_sheet_headers() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.xlsx");
+
+        let info = ExcelProcessor::inspect_workbook(&fixture).unwrap();
+
+        assert_eq!(info.sheets.len(), 1);
+        let sheet = &info.sheets[0];
+        assert_eq!(sheet.headers.len(), 14);
+        assert_eq!(sheet.headers[1], "问题名称");
+        assert_eq!(sheet.row_count, 7);
+    }
+
+    #[test]
+    fn test_empty_first_sheet_falls_back_to_next_sheet() {
+        let fixture =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/empty_first_sheet.xlsx");
+        let expected = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.xlsx");
+
+        let raw = ExcelProcessor::read_excel_raw(&fixture).unwrap();
+        let expected = ExcelProcessor::read_excel_raw(&expected).unwrap();
+        assert_eq!(raw.headers, expected.headers);
+        assert_eq!(raw.rows, expected.rows);
+
+        let mut streamed = Vec::new();
+        ExcelProcessor::for_each_excel_row(&fixture, CsvEncoding::default(), |row| {
+            streamed.push(row);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(streamed[0], expected.headers);
+        assert_eq!(streamed[1..], expected.rows[..]);
+    }
+
+    #[test]
+    fn test_check_severity_column() {
+        let rows = |severities: &[&str]| -> Vec<Vec<String>> {
+            severities
+                .iter()
+                .map(|severity| ["A", "B", "C", *severity].map(String::from).to_vec())
+                .collect()
+        };
+        let options = ProcessOptions::default();
+
+        let mapped = rows(&["高危", "中危", "低危", "", "提示"]);
+        assert_eq!(ExcelProcessor::check_severity_rows(&mapped, &options), None);
+
+        // 映射到了问题名称等其他列时大多无法识别
+        let mismapped = rows(&["SQL注入", "XSS", "SQL注入", "高危", "CSRF", "XSS"]);
+        let warning = ExcelProcessor::check_severity_rows(&mismapped, &options).unwrap();
+        assert!(warning.contains("严重性级别列（D列）中只有 1/6 个取值"));
+        assert!(warning.ends_with("SQL注入、XSS、CSRF"));
+
+        assert_eq!(
+            ExcelProcessor::check_severity_rows(&rows(&["", " "]), &options),
+            None
+        );
+
+        // 按配置识别：CVSS评分不会被误报
+        let scores = rows(&["9.8", "5.0", "2.1"]);
+        assert!(ExcelProcessor::check_severity_rows(&scores, &options).is_some());
+        let cvss = ProcessOptions {
+            severity_from_cvss: true,
+            ..ProcessOptions::default()
+        };
+        assert_eq!(ExcelProcessor::check_severity_rows(&scores, &cvss), None);
+
+        // 处理结果（流式、断点续读同样适用）检查记录中的原始取值
+        let result = ExcelProcessor::process_rows(scores, &cvss).unwrap();
+        assert_eq!(ExcelProcessor::check_result_severity(&result, &cvss), None);
+        let warning = ExcelProcessor::check_result_severity(&result, &options).unwrap();
+        assert!(warning.contains("只有 0/3 个取值"));
+    }
+
+    #[test]
+    fn test_read_excel_bytes_matches_path() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.xlsx");
+
+        let from_path = ExcelProcessor::read_excel_raw(&fixture).unwrap();
+        let bytes = std::fs::read(&fixture).unwrap();
+        let from_bytes = ExcelProcessor::read_excel_bytes(&bytes).unwrap();
+
+        assert_eq!(from_bytes.headers, from_path.headers);
+        assert_eq!(from_bytes.rows, from_path.rows);
+
+        assert!(ExcelProcessor::read_excel_bytes(b"not a workbook").is_err());
+    }
+
+    #[test]
+    fn test_merged_data_preview_caps_rows() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.xlsx");
+        let merged =
+            ExcelProcessor::merge_excel_files(&[&fixture, &fixture], &ProcessOptions::default())
+                .unwrap();
+
+        let preview = merged.preview(5);
+
+        assert_eq!(preview.headers.len(), 14);
+        assert_eq!(preview.sample_rows.len(), 5);
+        assert_eq!(preview.sample_rows[0], merged.rows[0]);
+        assert_eq!(preview.total_rows, 14);
+    }
+
+    #[test]
+    fn test_raw_data_json_round_trip_keeps_blank_cells() {
+        let raw = RawExcelData {
+            headers: vec!["序号".to_string(), "问题名称".to_string()],
+            rows: vec![
+                vec!["1".to_string(), String::new()],
+                vec![" ".to_string(), "\tXSS\n".to_string()],
+            ],
+        };
+
+        let json = serde_json::to_string(&raw).unwrap();
+        let restored: RawExcelData = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.headers, raw.headers);
+        assert_eq!(restored.rows, raw.rows);
+    }
+
+    #[test]
+    fn test_decode_csv_text_bom_and_gbk() {
+        let text = "序号,问题名称,严重性级别\n1,SQL注入,高危\n";
+        let mut utf8_bom = vec![0xEF, 0xBB, 0xBF];
+        utf8_bom.extend_from_slice(text.as_bytes());
+        let (gbk, _, _) = encoding_rs::GBK.encode(text);
+
+        let from_bom = ExcelProcessor::decode_csv_text(&utf8_bom, CsvEncoding::Auto).unwrap();
+        let from_gbk = ExcelProcessor::decode_csv_text(&gbk, CsvEncoding::Auto).unwrap();
+
+        assert_eq!(from_bom, text);
+        assert_eq!(from_gbk, text);
+        assert_eq!(
+            ExcelProcessor::decode_csv_text(&gbk, CsvEncoding::Gbk).unwrap(),
+            text
+        );
+        // 指定UTF-8时不会把GBK内容误解码
+        assert!(ExcelProcessor::decode_csv_text(&gbk, CsvEncoding::Utf8).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_quotes_and_delimiters() {
+        let rows = ExcelProcessor::parse_csv("a,b,c\r\n1,\"x, \"\"y\"\"\",\"多\n行\"\r\n\r\n2,,\n");
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a", "b", "c"],
+                vec!["1", "x, \"y\"", "多\n行"],
+                vec!["2", "", ""],
+            ]
+        );
+
+        let rows = ExcelProcessor::parse_csv("a;b;c\n1;2,5;3\n");
+        assert_eq!(rows[1], vec!["1", "2,5", "3"]);
+        let rows = ExcelProcessor::parse_csv("a\tb\n1\t2");
+        assert_eq!(rows[1], vec!["1", "2"]);
+
+        // 字段中间的引号按普通字符保留，不会吞掉之后的分隔符
+        let rows = ExcelProcessor::parse_csv("a,b,c\n12\" 屏幕,x,\"y\"\n");
+        assert_eq!(rows[1], vec!["12\" 屏幕", "x", "y"]);
+    }
+
+    #[test]
+    fn test_read_csv_utf8_bom_and_gbk_files_match() {
+        let text = "序号,问题名称,缺陷类型,严重性级别\n1,SQL注入,安全缺陷,高危\n2,\"跨站脚本\",安全缺陷,中危\n";
+        let dir = std::env::temp_dir().join("report_forge_csv_encoding_test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let utf8_path = dir.join("utf8_bom.csv");
+        let mut utf8_bom = vec![0xEF, 0xBB, 0xBF];
+        utf8_bom.extend_from_slice(text.as_bytes());
+        std::fs::write(&utf8_path, utf8_bom).unwrap();
+        let gbk_path = dir.join("gbk.csv");
+        std::fs::write(&gbk_path, encoding_rs::GBK.encode(text).0).unwrap();
+
+        let from_utf8 = ExcelProcessor::read_csv_raw(&utf8_path).unwrap();
+        let from_gbk = ExcelProcessor::read_csv_raw(&gbk_path).unwrap();
+
+        assert_eq!(from_utf8.headers[0], "序号");
+        assert_eq!(from_utf8.headers, from_gbk.headers);
+        assert_eq!(from_utf8.rows, from_gbk.rows);
+        assert_eq!(from_gbk.rows[1][1], "跨站脚本");
+
+        // 校验文件时使用 options 中的编码
+        let mut options = ProcessOptions::default();
+        let report = ExcelProcessor::validate_excel_file(&gbk_path, &options);
+        assert_eq!(report.headers[1], "问题名称");
+        options.csv_encoding = CsvEncoding::Utf8;
+        let report = ExcelProcessor::validate_excel_file(&gbk_path, &options);
+        assert!(report.errors[0].contains("CSV文件不是有效的UTF-8编码"));
+    }
+
+    #[test]
+    fn test_streaming_many_duplicate_rows_keeps_only_keys() {
+        let mut csv = String::from("序号,问题名称,缺陷类型,严重性级别,相关代码\n");
+        for _ in 0..5_000 {
+            csv.push_str("1,SQL注入,安全缺陷,高危,\"query(sql); /* 较长的相关代码 */\"\n");
+        }
+        csv.push_str("2,跨站脚本,安全缺陷,中危,echo\n");
+        let path = std::env::temp_dir().join("report_forge_many_duplicates.csv");
+        std::fs::write(&path, csv).unwrap();
+
+        let streamed =
+            ExcelProcessor::process_excel_files_streaming(&[&path], &ProcessOptions::default())
+                .unwrap();
+        assert_eq!(streamed.total_records, 2);
+        assert_eq!(streamed.removed_duplicates.len(), 4_999);
+        // 流式处理只保留行号及组合键，不持有重复行的单元格数据
+        let last = streamed.removed_duplicates.last().unwrap();
+        assert_eq!(last.row_index, 4_999);
+        assert!(last.record.data.is_empty());
+        assert_eq!(
+            last.record.dedup_key.as_deref(),
+            Some("1|SQL注入|安全缺陷|高危|query(sql); /* 较长的相关代码 */")
+        );
+        assert!(streamed
+            .removed_duplicates
+            .iter()
+            .all(|removed| removed.record.data.is_empty()));
+
+        // 一次性处理时保留完整的重复记录
+        let raw = ExcelProcessor::read_raw(&path, CsvEncoding::Auto).unwrap();
+        let eager = ExcelProcessor::process_raw_data(raw, &ProcessOptions::default()).unwrap();
+        assert_eq!(eager.removed_duplicates.len(), 4_999);
+        assert_eq!(
+            eager.removed_duplicates[0].record.data["B"].as_deref(),
+            Some("SQL注入")
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_inspect_corrupt_workbook_reports_open_error() {
+        let path = std::env::temp_dir().join("report_forge_corrupt.xlsx");
+        std::fs::write(&path, b"not a zip archive").unwrap();
+
+        let error = ExcelProcessor::inspect_workbook(&path).unwrap_err();
+
+        assert!(error.to_string().starts_with("无法打开Excel文件"));
+    }
+
+    #[test]
+    fn test_encrypted_workbook_is_reported() {
+        let path = std::env::temp_dir().join("report_forge_encrypted.xlsx");
+        let mut bytes = OLE_SIGNATURE.to_vec();
+        bytes.resize(512, 0);
+        std::fs::write(&path, bytes).unwrap();
+
+        let error = ExcelProcessor::read_excel_raw(&path).unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ReportError>(),
+            Some(ReportError::Encrypted { .. })
+        ));
+        assert!(error
+            .to_string()
+            .starts_with("文件已加密，无法读取，请先解除保护"));
+    }
+
+    #[test]
+    fn test_validate_headers_by_mode() {
+        let reference = vec!["序号".to_string(), "问题名称".to_string()];
+        let headers = vec!["序号 ".to_string(), "问题名称（必填）".to_string()];
+        let file = Path::new("b.xlsx");
+
+        let error =
+            ExcelProcessor::validate_headers(file, &headers, &reference, HeaderMatchMode::Trimmed)
+                .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ReportError>(),
+            Some(ReportError::HeaderMismatch { .. })
+        ));
+        assert!(ExcelProcessor::validate_headers(
+            file,
+            &headers,
+            &reference,
+            HeaderMatchMode::Normalized
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_risk_level_from_severity() {
+        assert_eq!(RiskLevel::from_severity("高危"), RiskLevel::High);
+        assert_eq!(RiskLevel::from_severity("中危"), RiskLevel::Medium);
+        assert_eq!(RiskLevel::from_severity("低危"), RiskLevel::Low);
+        assert_eq!(RiskLevel::from_severity("未知"), RiskLevel::Unknown);
+    }
+
+    #[test]
+    fn test_risk_level_from_cvss_boundaries() {
+        assert_eq!(RiskLevel::from_cvss(10.0), RiskLevel::High);
+        assert_eq!(RiskLevel::from_cvss(9.0), RiskLevel::High);
+        assert_eq!(RiskLevel::from_cvss(7.0), RiskLevel::High);
+        assert_eq!(RiskLevel::from_cvss(6.9), RiskLevel::Medium);
+        assert_eq!(RiskLevel::from_cvss(4.0), RiskLevel::Medium);
+        assert_eq!(RiskLevel::from_cvss(3.9), RiskLevel::Low);
+        assert_eq!(RiskLevel::from_cvss(0.1), RiskLevel::Low);
+        assert_eq!(RiskLevel::from_cvss(0.0), RiskLevel::Unknown);
+        assert_eq!(RiskLevel::from_cvss(-1.0), RiskLevel::Unknown);
+        assert_eq!(RiskLevel::from_cvss(10.1), RiskLevel::Unknown);
+        assert_eq!(RiskLevel::from_cvss(f32::NAN), RiskLevel::Unknown);
+    }
+
+    #[test]
+    fn test_classify_cvss_severity_regroups_by_level() {
+        let rows = vec![
+            vec!["1", "SQL注入", "", "9.8"],
+            vec!["2", "XSS", "", "5.4"],
+            vec!["3", "SQL注入", "", "7.5"],
+            vec!["4", "XSS", "", "中危"],
+        ]
+        .into_iter()
+        .map(|row| row.into_iter().map(String::from).collect::<Vec<_>>());
+        let options = ProcessOptions {
+            severity_from_cvss: true,
+            ..ProcessOptions::default()
+        };
+
+        let result = ExcelProcessor::process_rows(rows, &options).unwrap();
+
+        let keys: Vec<&str> = result
+            .grouped_data
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["SQL注入|高危", "XSS|中危"]);
+        assert_eq!(result.grouped_data[0].1.d_column, "高危");
+        assert_eq!(result.grouped_data[0].1.record_count, 2);
+        assert_eq!(result.grouped_data[1].1.record_count, 2);
+        assert_eq!(result.severity_counts[&RiskLevel::High], 2);
+        // 记录保留Excel中的原始评分
+        let raw: Vec<Option<&str>> = result.grouped_data[0]
+            .1
+            .records
+            .iter()
+            .map(|record| record.data["D"].as_deref())
+            .collect();
+        assert_eq!(raw, vec![Some("9.8"), Some("7.5")]);
+    }
+
+    #[test]
+    fn test_process_raw_data_counts_cvss_severity() {
+        let raw = RawExcelData {
+            headers: ["序号", "问题名称", "描述", "严重性级别"]
+                .iter()
+                .map(|header| header.to_string())
+                .collect(),
+            rows: [
+                ["1", "SQL注入", "", "9.8"],
+                ["2", "SQL注入", "", "7.5"],
+                ["3", "XSS", "", "5.4"],
+                ["4", "日志泄露", "", "2.1"],
+                ["5", "未知问题", "", "0"],
+            ]
+            .iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect(),
+        };
+        let options = ProcessOptions {
+            severity_from_cvss: true,
+            ..ProcessOptions::default()
+        };
+
+        let result = ExcelProcessor::process_raw_data(raw, &options).unwrap();
+
+        assert_eq!(result.severity_counts[&RiskLevel::High], 2);
+        assert_eq!(result.severity_counts[&RiskLevel::Medium], 1);
+        assert_eq!(result.severity_counts[&RiskLevel::Low], 1);
+        assert_eq!(result.severity_counts[&RiskLevel::Unknown], 1);
+        // 按评分识别后的等级排序
+        let levels: Vec<&str> = result
+            .grouped_data
+            .iter()
+            .map(|(_, info)| info.d_column.as_str())
+            .collect();
+        assert_eq!(levels, vec!["高危", "中危", "低危", "未知"]);
+    }
+
+    fn record(values: &[&str]) -> ExcelRecord {
+        ExcelRecord {
+            data: row(values),
+            dedup_key: None,
+        }
+    }
+
+    /// 按B、D列分组并保持首次出现顺序的处理结果
+    fn structured(records: &[ExcelRecord]) -> ExcelProcessResult {
+        let grouped = ExcelProcessor::group_data_by_columns(records, "B", "D", |_| None);
+        ExcelProcessor::create_structured_result(
+            grouped,
+            records.len(),
+            GroupSortOrder::OriginalOrder,
+            &[],
+        )
+    }
+
+    fn row(values: &[&str]) -> HashMap<String, Option<String>> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let col = format!("{}", (b'A' + i as u8) as char);
+                (col, Some(v.to_string()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_deduplicate_normalized_whitespace() {
+        let records = vec![
+            row(&["1", "SQL注入", "高危"]),
+            row(&["1", "SQL注入 ", "高危"]),
+            row(&["1", "SQL  注入", "高危"]),
+        ];
+        let columns: Vec<String> = vec!["A".into(), "B".into(), "C".into()];
+
+        let exact =
+            ExcelProcessor::deduplicate_records(&records, &columns, &DedupOptions::default());
+        assert_eq!(exact.len(), 3);
+
+        let options = DedupOptions {
+            normalize_whitespace: true,
+            ignore_case: false,
+        };
+        let normalized = ExcelProcessor::deduplicate_records(&records, &columns, &options);
+        assert_eq!(normalized.len(), 2);
+        // 保留的记录仍为原始值
+        assert_eq!(normalized[0].get("B").unwrap().as_deref(), Some("SQL注入"));
+        assert_eq!(
+            normalized[1].get("B").unwrap().as_deref(),
+            Some("SQL  注入")
+        );
+    }
+
+    #[test]
+    fn test_deduplicate_ignore_case() {
+        let records = vec![row(&["1", "XSS", "Medium"]), row(&["1", "xss", "medium"])];
+        let columns: Vec<String> = vec!["A".into(), "B".into(), "C".into()];
+
+        let exact =
+            ExcelProcessor::deduplicate_records(&records, &columns, &DedupOptions::default());
+        assert_eq!(exact.len(), 2);
+
+        let options = DedupOptions {
+            normalize_whitespace: true,
+            ignore_case: true,
+        };
+        let deduped = ExcelProcessor::deduplicate_records(&records, &columns, &options);
+        assert_eq!(deduped.len(), 1);
+        assert_eq!(deduped[0].get("B").unwrap().as_deref(), Some("XSS"));
+    }
+
+    #[test]
+    fn test_deduplicate_reports_removed_records() {
+        let records = vec![
+            row(&["1", "XSS", "中危"]),
+            row(&["2", "SQL注入", "高危"]),
+            row(&["1", "XSS", "中危"]),
+        ];
+        let columns: Vec<String> = vec!["A".into(), "B".into(), "C".into()];
+
+        let (unique, removed) = ExcelProcessor::deduplicate_records_with_removed(
+            &records,
+            &columns,
+            &DedupOptions::default(),
+        );
+        assert_eq!(unique.len(), 2);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].row_index, 2);
+        assert_eq!(
+            removed[0].record.data.get("B").unwrap().as_deref(),
+            Some("XSS")
+        );
+    }
+
+    fn sample_groups() -> IndexMap<String, Vec<ExcelRecord>> {
+        let records = vec![
+            record(&["1", "XSS", "", "中危"]),
+            record(&["2", "SQL注入", "", "高危"]),
+            record(&["3", "CSRF", "", "中危"]),
+            record(&["4", "CSRF", "", "中危"]),
+            record(&["5", "SQL注入", "", "高危"]),
+            record(&["6", "SQL注入", "", "高危"]),
+            record(&["7", "XSS", "", "中危"]),
+            record(&["8", "日志泄露", "", "低危"]),
+        ];
+        ExcelProcessor::group_data_by_columns(&records, "B", "D", |_| None)
+    }
+
+    fn sorted_names(sort_order: GroupSortOrder) -> Vec<String> {
+        ExcelProcessor::create_structured_result(sample_groups(), 8, sort_order, &[])
+            .grouped_data
+            .into_iter()
+            .map(|(_, info)| info.b_column)
+            .collect()
+    }
+
+    #[test]
+    fn test_process_rows_streams_without_materializing_input() {
+        // 惰性生成大量重复行，处理过程中只保留去重后的记录
+        let rows = (0..50_000).map(|i| {
+            let n = i % 100;
+            vec![
+                n.to_string(),
+                format!("问题{}", n % 5),
+                String::new(),
+                "高危".to_string(),
+            ]
+        });
+
+        let result = ExcelProcessor::process_rows(rows, &ProcessOptions::default()).unwrap();
+
+        assert_eq!(result.total_records, 100);
+        assert_eq!(result.total_groups, 5);
+        assert_eq!(result.removed_duplicates.len(), 49_900);
+        assert!(result
+            .grouped_data
+            .iter()
+            .all(|(_, g)| g.record_count == 20));
+    }
+
+    #[test]
+    fn test_process_rows_skips_blank_rows() {
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let rows = vec![
+            row(&["1", "XSS", "", "中危", "", "", "", ""]),
+            row(&["", "", "", "", "", "", "", ""]),
+            row(&["2", "XSS", "", "中危", "", "", "", ""]),
+            row(&["  ", "", "", "", "", "", "", ""]),
+            row(&["", "", "", "", "", "", "", "仅H列有数据"]),
+            row(&["", "", "", "", "", "", "", ""]),
+        ];
+
+        let result = ExcelProcessor::process_rows(rows, &ProcessOptions::default()).unwrap();
+
+        assert_eq!(result.total_records, 3);
+        assert_eq!(result.total_groups, 2);
+        assert!(result.removed_duplicates.is_empty());
+    }
+
+    #[test]
+    fn test_failed_file_rows_are_rolled_back() {
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let options = DedupOptions::default();
+        let mut deduplicator = RowDeduplicator::new(&options);
+        deduplicator.push_row(row(&["1", "XSS", "", "中危"]));
+
+        // 模拟读取到一半失败的文件：新记录、重复记录及空行都被撤销
+        let mark = deduplicator.mark();
+        deduplicator.push_row(row(&["2", "SQL注入", "", "高危"]));
+        deduplicator.push_row(row(&["1", "XSS", "", "中危"]));
+        deduplicator.push_row(row(&["", "", "", ""]));
+        deduplicator.rollback(mark);
+
+        assert_eq!(deduplicator.records.len(), 1);
+        assert!(deduplicator.removed.is_empty());
+        assert_eq!((deduplicator.row_count, deduplicator.blank_rows), (1, 0));
+
+        // 被撤销的记录在后续文件中再次出现时不算重复
+        deduplicator.push_row(row(&["2", "SQL注入", "", "高危"]));
+        assert_eq!(deduplicator.records.len(), 2);
+        assert_eq!(deduplicator.removed.len(), 0);
+    }
+
+    #[test]
+    fn test_resumed_streaming_matches_uninterrupted_run() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        // 第二个文件内容与第一个相同，继续处理时须沿用已保存的去重状态
+        let files = [
+            fixtures.join("findings.xlsx"),
+            fixtures.join("findings.csv"),
+        ];
+        let options = ProcessOptions {
+            group_sort_order: GroupSortOrder::OriginalOrder,
+            ..ProcessOptions::default()
+        };
+        let expected = ExcelProcessor::process_excel_files_streaming(&files, &options).unwrap();
+
+        let mut saved = Vec::new();
+        let mut save = |progress: ProcessingProgress| -> Result<()> {
+            saved.push(serde_json::to_string(&progress)?);
+            Ok(())
+        };
+        let hook = ProgressHook {
+            every_files: 1,
+            on_progress: &mut save,
+        };
+        let mut files_done = Vec::new();
+        let mut on_file_done = |done: usize, total: usize| files_done.push((done, total));
+        ExcelProcessor::process_excel_files_resumable(
+            &files,
+            &options,
+            None,
+            Some(hook),
+            &mut on_file_done,
+        )
+        .unwrap();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(files_done, vec![(1, 2), (2, 2)]);
+
+        let progress: ProcessingProgress = serde_json::from_str(&saved[0]).unwrap();
+        assert_eq!(progress.files_done, 1);
+        assert_eq!(progress.records.len(), 6);
+        let mut files_done = Vec::new();
+        let mut on_file_done = |done: usize, total: usize| files_done.push((done, total));
+        let resumed = ExcelProcessor::process_excel_files_resumable(
+            &files,
+            &options,
+            Some(progress),
+            None,
+            &mut on_file_done,
+        )
+        .unwrap();
+        // 从进度继续时先报告已处理的文件数
+        assert_eq!(files_done, vec![(1, 2), (2, 2)]);
+
+        let keys = |result: &ExcelProcessResult| -> Vec<(String, usize)> {
+            result
+                .grouped_data
+                .iter()
+                .map(|(key, info)| (key.clone(), info.record_count))
+                .collect()
+        };
+        assert_eq!(keys(&resumed), keys(&expected));
+        assert_eq!(resumed.total_records, 6);
+        let removed_rows = |result: &ExcelProcessResult| -> Vec<usize> {
+            result
+                .removed_duplicates
+                .iter()
+                .map(|r| r.row_index)
+                .collect()
+        };
+        assert_eq!(removed_rows(&resumed), removed_rows(&expected));
+        assert_eq!(resumed.removed_duplicates.len(), 8);
+
+        // 按间隔保存：间隔大于文件数时只在最后一个文件处理完后调用一次
+        let mut calls = Vec::new();
+        let mut record_call = |progress: ProcessingProgress| -> Result<()> {
+            calls.push(progress.files_done);
+            Ok(())
+        };
+        let hook = ProgressHook {
+            every_files: 5,
+            on_progress: &mut record_call,
+        };
+        ExcelProcessor::process_excel_files_resumable(
+            &files,
+            &options,
+            None,
+            Some(hook),
+            &mut |_, _| {},
+        )
+        .unwrap();
+        assert_eq!(calls, vec![2]);
+    }
+
+    #[test]
+    fn test_records_carry_dedup_key() {
+        let rows = vec![
+            vec!["1", "XSS ", "", "中危"],
+            vec!["1", "xss", "", "中危"],
+            vec!["2", "SQL注入", "", "9.8"],
+        ]
+        .into_iter()
+        .map(|row| row.into_iter().map(String::from).collect::<Vec<_>>());
+        let options = ProcessOptions {
+            dedup: DedupOptions {
+                normalize_whitespace: true,
+                ignore_case: true,
+            },
+            group_sort_order: GroupSortOrder::OriginalOrder,
+            severity_from_cvss: true,
+            ..ProcessOptions::default()
+        };
+
+        let result = ExcelProcessor::process_rows(rows, &options).unwrap();
+
+        let key = result.grouped_data[0].1.records[0].dedup_key.as_deref();
+        assert_eq!(key, Some("1|xss||中危"));
+        // 被移除的重复记录与保留的记录组合键相同，可据此对应
+        assert_eq!(
+            result.removed_duplicates[0].record.dedup_key.as_deref(),
+            key
+        );
+
+        // 按CVSS评分识别严重性级别分组后仍保留原始数据行的组合键
+        let (key, info) = &result.grouped_data[1];
+        assert_eq!(key, "SQL注入|高危");
+        assert_eq!(info.records[0].dedup_key.as_deref(), Some("2|sql注入||9.8"));
+    }
+
+    #[test]
+    fn test_handle_uncategorized_groups() {
+        let records = vec![
+            record(&["1", "XSS", "", "中危"]),
+            record(&["2", "", "", ""]),
+            record(&["3", "", "", ""]),
+        ];
+        let grouped = ExcelProcessor::group_data_by_columns(&records, "B", "D", |_| None);
+        let result = ExcelProcessor::create_structured_result(
+            grouped,
+            3,
+            GroupSortOrder::OriginalOrder,
+            &[],
+        );
+
+        let bucketed = ExcelProcessor::handle_uncategorized(&result, false, "未分类");
+        assert_eq!(bucketed.total_groups, 2);
+        assert_eq!(bucketed.grouped_data[1].0, "未分类|");
+        assert_eq!(bucketed.grouped_data[1].1.b_column, "未分类");
+        assert_eq!(bucketed.grouped_data[1].1.record_count, 2);
+
+        let dropped = ExcelProcessor::handle_uncategorized(&result, true, "未分类");
+        assert_eq!(dropped.total_groups, 1);
+        assert_eq!(dropped.total_records, 1);
+    }
+
+    #[test]
+    fn test_grouping_order_is_reproducible() {
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let rows = vec![
+            row(&["1", "XSS", "", "中危"]),
+            row(&["2", "SQL注入", "", "高危"]),
+            row(&["3", "CSRF", "", "中危"]),
+            row(&["4", "CSRF", "", "中危"]),
+            row(&["5", "SQL注入", "", "高危"]),
+            row(&["6", "XSS", "", "中危"]),
+            row(&["7", "日志泄露", "", "低危"]),
+        ];
+        let options = ProcessOptions {
+            group_sort_order: GroupSortOrder::OriginalOrder,
+            ..ProcessOptions::default()
+        };
+
+        // 同一输入处理两次，序列化结果逐字节相同，分组保持首次出现顺序；
+        // serde_json::Value 的对象按键排序，HashMap 字段的顺序不影响比较
+        let serialize =
+            |result: &ExcelProcessResult| serde_json::to_value(result).unwrap().to_string();
+        let first = ExcelProcessor::process_rows(rows.clone(), &options).unwrap();
+        let second = ExcelProcessor::process_rows(rows, &options).unwrap();
+        assert_eq!(serialize(&first), serialize(&second));
+        let keys: Vec<&str> = first.grouped_data.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["XSS|中危", "SQL注入|高危", "CSRF|中危", "日志泄露|低危"]
+        );
+    }
+
+    #[test]
+    fn test_group_sort_orders() {
+        assert_eq!(
+            sorted_names(GroupSortOrder::RiskThenCount),
+            vec!["SQL注入", "CSRF", "XSS", "日志泄露"]
+        );
+        assert_eq!(
+            sorted_names(GroupSortOrder::Alphabetical),
+            vec!["CSRF", "SQL注入", "XSS", "日志泄露"]
+        );
+        assert_eq!(
+            sorted_names(GroupSortOrder::CountDesc),
+            vec!["SQL注入", "XSS", "CSRF", "日志泄露"]
+        );
+        assert_eq!(
+            sorted_names(GroupSortOrder::OriginalOrder),
+            vec!["XSS", "SQL注入", "CSRF", "日志泄露"]
+        );
+    }
+
+    #[test]
+    fn test_risk_then_count_ties_sorted_by_group_key() {
+        // 风险等级和记录数相同的分组，无论出现顺序如何都按分组键排序
+        let orders = [["XSS", "CSRF", "SQL注入"], ["SQL注入", "CSRF", "XSS"]];
+        for names in orders {
+            let records: Vec<_> = names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| record(&[&(i + 1).to_string(), name, "", "中危"]))
+                .collect();
+            let grouped = ExcelProcessor::group_data_by_columns(&records, "B", "D", |_| None);
+            let result = ExcelProcessor::create_structured_result(
+                grouped,
+                3,
+                GroupSortOrder::RiskThenCount,
+                &[],
+            );
+
+            let keys: Vec<&str> = result
+                .grouped_data
+                .iter()
+                .map(|(k, _)| k.as_str())
+                .collect();
+            assert_eq!(keys, vec!["CSRF|中危", "SQL注入|中危", "XSS|中危"]);
+        }
+    }
+
+    #[test]
+    fn test_severity_counts_sum_group_records() {
+        let mut records = vec![record(&["9", "调试信息", "", "提示"])];
+        records.extend(sample_groups().into_values().flatten());
+        let grouped = ExcelProcessor::group_data_by_columns(&records, "B", "D", |_| None);
+        let result = ExcelProcessor::create_structured_result(
+            grouped,
+            9,
+            GroupSortOrder::OriginalOrder,
+            &[],
+        );
+
+        for level in [
+            RiskLevel::High,
+            RiskLevel::Medium,
+            RiskLevel::Low,
+            RiskLevel::Unknown,
+        ] {
+            let expected: usize = result
+                .grouped_data
+                .iter()
+                .filter(|(_, info)| RiskLevel::from_severity(&info.d_column) == level)
+                .map(|(_, info)| info.record_count)
+                .sum();
+            assert_eq!(result.severity_counts[&level], expected);
+        }
+        assert_eq!(result.severity_counts[&RiskLevel::High], 3);
+        assert_eq!(result.severity_counts[&RiskLevel::Medium], 4);
+        assert_eq!(result.severity_counts[&RiskLevel::Low], 1);
+        assert_eq!(result.severity_counts[&RiskLevel::Unknown], 1);
+
+        let filtered = ExcelProcessor::filter_by_severity(&result, &[RiskLevel::High]);
+        assert_eq!(filtered.severity_counts[&RiskLevel::High], 3);
+        assert_eq!(filtered.severity_counts[&RiskLevel::Medium], 0);
+    }
+
+    #[test]
+    fn test_filter_by_severity_keeps_only_selected_levels() {
+        let result = ExcelProcessor::create_structured_result(
+            sample_groups(),
+            8,
+            GroupSortOrder::OriginalOrder,
+            &[],
+        );
+
+        let filtered = ExcelProcessor::filter_by_severity(&result, &[RiskLevel::Medium]);
+
+        let names: Vec<&str> = filtered
+            .grouped_data
+            .iter()
+            .map(|(_, info)| info.b_column.as_str())
+            .collect();
+        assert_eq!(names, vec!["XSS", "CSRF"]);
+        assert_eq!(filtered.total_groups, 2);
+        assert_eq!(filtered.total_records, 4);
+    }
+
+    #[test]
+    fn test_filter_by_min_records_drops_single_occurrence_groups() {
+        let result = ExcelProcessor::create_structured_result(
+            sample_groups(),
+            8,
+            GroupSortOrder::OriginalOrder,
+            &[],
+        );
+
+        let filtered = ExcelProcessor::filter_by_min_records(&result, 2);
+
+        let names: Vec<&str> = filtered
+            .grouped_data
+            .iter()
+            .map(|(_, info)| info.b_column.as_str())
+            .collect();
+        assert_eq!(names, vec!["XSS", "SQL注入", "CSRF"]);
+        assert_eq!(filtered.total_groups, 3);
+        assert_eq!(filtered.total_records, 7);
+        assert_eq!(filtered.severity_counts[&RiskLevel::Low], 0);
+    }
+
+    #[test]
+    fn test_escalate_medium_group_above_threshold() {
+        let records = vec![
+            record(&["1", "XSS", "", "中危"]),
+            record(&["2", "CSRF", "", "中危"]),
+            record(&["3", "SQL注入", "", "高危"]),
+            record(&["4", "XSS", "", "中危"]),
+            record(&["5", "XSS", "", "中危"]),
+        ];
+        let grouped = ExcelProcessor::group_data_by_columns(&records, "B", "D", |_| None);
+        let escalation = [SeverityEscalation {
+            from_level: RiskLevel::Medium,
+            min_count: 3,
+            to_level: RiskLevel::High,
+        }];
+
+        let result = ExcelProcessor::create_structured_result(
+            grouped,
+            5,
+            GroupSortOrder::RiskThenCount,
+            &escalation,
+        );
+
+        let groups: Vec<(&str, &str, Option<&str>)> = result
+            .grouped_data
+            .iter()
+            .map(|(key, info)| {
+                (
+                    key.as_str(),
+                    info.d_column.as_str(),
+                    info.original_severity.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("XSS|高危", "高危", Some("中危")),
+                ("SQL注入|高危", "高危", None),
+                ("CSRF|中危", "中危", None),
+            ]
+        );
+        assert_eq!(result.severity_counts[&RiskLevel::High], 4);
+        assert_eq!(result.severity_counts[&RiskLevel::Medium], 1);
+    }
+
+    #[test]
+    fn test_escalated_group_merges_with_existing_key() {
+        let records = vec![
+            record(&["1", "XSS", "", "高危"]),
+            record(&["2", "XSS", "", "中危"]),
+            record(&["3", "XSS", "", "中危"]),
+            record(&["4", "CSRF", "", "中危"]),
+        ];
+        let grouped = ExcelProcessor::group_data_by_columns(&records, "B", "D", |_| None);
+        let escalation = [SeverityEscalation {
+            from_level: RiskLevel::Medium,
+            min_count: 2,
+            to_level: RiskLevel::High,
+        }];
+
+        let result = ExcelProcessor::create_structured_result(
+            grouped,
+            4,
+            GroupSortOrder::RiskThenCount,
+            &escalation,
+        );
+
+        // 升级后的 “XSS|中危” 与 “XSS|高危” 合并，分组键不重复
+        let keys: Vec<&str> = result
+            .grouped_data
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["XSS|高危", "CSRF|中危"]);
+        let xss = &result.grouped_data[0].1;
+        assert_eq!(xss.record_count, 3);
+        assert_eq!(xss.records.len(), 3);
+        assert_eq!(xss.original_severity.as_deref(), Some("中危"));
+        assert_eq!(result.total_groups, 2);
+        assert_eq!(result.severity_counts[&RiskLevel::High], 3);
+    }
+
+    #[test]
+    fn test_append_new_groups_keeps_baseline_numbering() {
+        let baseline = structured(&[
+            record(&["1", "XSS", "", "中危"]),
+            record(&["2", "SQL注入", "", "高危"]),
+        ]);
+        let current = structured(&[
+            record(&["1", "CSRF", "", "中危"]),
+            record(&["2", "SQL注入", "", "高危"]),
+            record(&["3", "SQL注入", "", "高危"]),
+            record(&["4", "XSS", "", "低危"]),
+        ]);
+
+        let appended = ExcelProcessor::append_new_groups(&baseline, &current).unwrap();
+
+        let keys: Vec<&str> = appended
+            .grouped_data
+            .iter()
+            .map(|(group_key, _)| group_key.as_str())
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["XSS|中危", "SQL注入|高危", "CSRF|中危", "XSS|低危"]
+        );
+        // 已有分组沿用基线内容
+        assert_eq!(appended.grouped_data[1].1.record_count, 1);
+        assert_eq!(appended.total_groups, 4);
+        assert_eq!(appended.total_records, 4);
+    }
+
+    #[test]
+    fn test_append_new_groups_rejects_different_columns() {
+        let baseline = structured(&[record(&["1", "XSS", "", "中危", "/src/a.c"])]);
+        let current = structured(&[record(&["1", "CSRF", "", "中危", "/src/b.c", "", "备注"])]);
+
+        let error = ExcelProcessor::append_new_groups(&baseline, &current).unwrap_err();
+
+        match error.downcast_ref::<ReportError>() {
+            Some(ReportError::ColumnMismatch { expected, got }) => {
+                assert_eq!(expected, &["A", "B", "C", "D", "E"]);
+                assert_eq!(got, &["A", "B", "C", "D", "E", "F", "G"]);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert_eq!(
+            error.to_string(),
+            "处理结果的列与基线结果不一致，可能来自表头不同的Excel文件：\
+             当前结果有 7 列，基线结果有 5 列；多出 F、G 列"
+        );
+
+        // 没有记录的结果可以与任何结果合并
+        let empty = structured(&[]);
+        assert!(ExcelProcessor::append_new_groups(&empty, &current).is_ok());
+    }
+
+    #[test]
+    fn test_append_new_groups_compares_header_names() {
+        let headers = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        let with_headers = |names: &[&str], severity: &str| ExcelProcessResult {
+            headers: headers(names),
+            ..structured(&[record(&["1", "XSS", "", severity])])
+        };
+        let baseline = with_headers(&["序号", "问题名称", "缺陷类型", "严重性级别"], "中危");
+
+        // 列数相同但表头不同（列字母一致）时拒绝合并
+        let renamed = with_headers(&["序号", "问题名称", "规则编号", "严重性级别"], "高危");
+        let error = ExcelProcessor::append_new_groups(&baseline, &renamed).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "处理结果的列与基线结果不一致，可能来自表头不同的Excel文件：\
+             当前结果有 4 列，基线结果有 4 列；缺少 缺陷类型 列；多出 规则编号 列"
+        );
+
+        // 表头按宽松匹配比较，过滤、追加后的结果保留表头
+        let spaced = with_headers(&["序号", " 问题名称 ", "缺陷类型", "严重性级别"], "高危");
+        let appended = ExcelProcessor::append_new_groups(&baseline, &spaced).unwrap();
+        assert_eq!(appended.headers, spaced.headers);
+        let filtered = ExcelProcessor::filter_by_severity(&appended, &[RiskLevel::High]);
+        assert_eq!(filtered.headers, spaced.headers);
+
+        // 旧版本保存的结果没有表头时比较列字母
+        let legacy = ExcelProcessResult {
+            headers: Vec::new(),
+            ..renamed
+        };
+        assert!(ExcelProcessor::append_new_groups(&baseline, &legacy).is_ok());
+    }
+
+    #[test]
+    fn test_diff_results_added_removed_and_count_changes() {
+        let old = structured(&[
+            record(&["1", "XSS", "", "中危"]),
+            record(&["2", "SQL注入", "", "高危"]),
+            record(&["3", "CSRF", "", "中危"]),
+            record(&["4", "CSRF", "", "中危"]),
+        ]);
+        let new = structured(&[
+            record(&["1", "SQL注入", "", "高危"]),
+            record(&["2", "SQL注入", "", "高危"]),
+            record(&["3", "路径遍历", "", "高危"]),
+            record(&["4", "CSRF", "", "中危"]),
+            record(&["5", "CSRF", "", "中危"]),
+        ]);
+
+        let diff = ExcelProcessor::diff_results(&old, &new);
+
+        let keys = |groups: &[(String, GroupInfo)]| -> Vec<String> {
+            groups
+                .iter()
+                .map(|(group_key, _)| group_key.clone())
+                .collect()
+        };
+        assert_eq!(keys(&diff.added_groups), vec!["路径遍历|高危"]);
+        assert_eq!(keys(&diff.removed_groups), vec!["XSS|中危"]);
+        // CSRF 记录数不变，不列入变化
+        assert_eq!(
+            diff.count_changes,
+            vec![GroupCountChange {
+                group_key: "SQL注入|高危".to_string(),
+                old_count: 1,
+                new_count: 2,
+            }]
+        );
+        assert_eq!(diff.count_changes[0].delta(), 1);
+        assert!(!diff.is_empty());
+        assert!(ExcelProcessor::diff_results(&new, &new).is_empty());
     }
 }
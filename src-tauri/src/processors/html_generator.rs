@@ -0,0 +1,458 @@
+use crate::models::{
+    ExcelProcessResult, Language, ReportConfig, RiskLevel, StatisticItem, StatisticSummary,
+};
+use crate::processors::word_generator::{AppendixLine, ReportSection, RISK_LEVEL_FILLS};
+use crate::processors::{normalize_newlines, WordGenerator};
+use anyhow::Result;
+
+pub struct HtmlGenerator;
+
+/// 表格通用样式
+const TABLE_STYLE: &str = "border-collapse:collapse;width:100%;margin:12px 0;";
+const CELL_STYLE: &str = "border:1px solid #999;padding:6px 8px;vertical-align:top;";
+const LABEL_STYLE: &str =
+    "border:1px solid #999;padding:6px 8px;background:#F2F2F2;font-weight:bold;width:18%;";
+const PRE_STYLE: &str =
+    "margin:0;padding:8px;background:#F5F5F5;font-family:Consolas,monospace;white-space:pre-wrap;";
+
+impl HtmlGenerator {
+    /// 生成自包含的HTML报告（内联样式），内容与Word报告一致；
+    /// 每写完一个章节调用 on_progress(已完成章节数, 章节总数)
+    pub fn generate_report<F>(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+        on_progress: F,
+    ) -> Result<String>
+    where
+        F: FnMut(usize, usize),
+    {
+        log::info!("开始生成HTML报告");
+
+        let html = Self::render(config, result_data, on_progress)?;
+
+        let timestamp = chrono::Local::now().timestamp_millis();
+        let file_stem = config.output_file_stem(timestamp)?;
+        let output_file = WordGenerator::write_output_file(
+            &config.output_dir,
+            &file_stem,
+            "html",
+            html.as_bytes(),
+        )?;
+
+        log::info!("报告生成完成！文件: {}", output_file);
+        Ok(output_file)
+    }
+
+    /// 渲染完整的HTML文档
+    fn render<F>(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+        mut on_progress: F,
+    ) -> Result<String>
+    where
+        F: FnMut(usize, usize),
+    {
+        let result_data = WordGenerator::prepare_groups(config, result_data);
+        let no_findings = WordGenerator::no_findings(config, &result_data)?;
+        let title = Self::escape(config.title());
+        let lang = match config.language {
+            Language::Zh => "zh-CN",
+            Language::En => "en",
+        };
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n\
+             <body style=\"font-family:'宋体','Times New Roman',serif;max-width:960px;margin:0 auto;padding:24px;\">\n\
+             <h1 style=\"text-align:center;\">{}</h1>\n",
+            lang, title, title
+        );
+
+        let generated_date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let labels = config.language.labels();
+        let metadata = [
+            (labels.identifier, config.identifier_tag.as_str()),
+            (labels.software_version, config.code_version.as_str()),
+            (labels.tester, config.ceshi_user.as_str()),
+            (labels.test_time, config.ceshi_time.as_str()),
+            (labels.generated_date, generated_date.as_str()),
+        ];
+        html.push_str("<p style=\"text-align:center;\">");
+        let metadata: Vec<String> = metadata
+            .iter()
+            .map(|(label, value)| format!("{}{}{}", label, labels.colon, Self::escape(value)))
+            .collect();
+        html.push_str(&metadata.join("<br>"));
+        html.push_str("</p>\n");
+
+        if no_findings {
+            // 没有问题时以说明代替统计表格
+            html.push_str(&format!(
+                "<p style=\"text-align:center;font-weight:bold;\">{}</p>\n",
+                labels.no_findings
+            ));
+        } else {
+            let statistics = WordGenerator::generate_statistics(&result_data, config)?;
+            let summary =
+                config.statistics_summary(result_data.total_records, result_data.total_groups)?;
+            html.push_str(&Self::statistics_table(
+                &statistics,
+                summary.as_deref(),
+                config.language,
+            ));
+        }
+
+        let report_numbers = config.report_numbers(&result_data.grouped_data)?;
+        for (title_num, ((group_key, group_info), report_number)) in
+            (1..).zip(result_data.grouped_data.iter().zip(report_numbers))
+        {
+            let section = WordGenerator::build_section(
+                config,
+                group_key,
+                group_info,
+                title_num,
+                report_number,
+            )?;
+            html.push_str(&Self::section(config, &section));
+            on_progress(title_num as usize, result_data.grouped_data.len());
+        }
+
+        if let Some(appendix) = WordGenerator::appendix_lines(config) {
+            html.push_str(&Self::appendix(&appendix));
+        }
+
+        if let Some(stamp) = config.generation_stamp_text() {
+            html.push_str(&format!(
+                "<p style=\"text-align:right;color:#808080;\">{}</p>\n",
+                Self::escape(&stamp)
+            ));
+        }
+
+        html.push_str("</body>\n</html>\n");
+        Ok(html)
+    }
+
+    /// 统计表格，summary 为标题下方的汇总语句
+    fn statistics_table(
+        statistics: &[StatisticItem],
+        summary: Option<&str>,
+        language: Language,
+    ) -> String {
+        let labels = language.labels();
+        let mut html = format!(
+            "<h2 style=\"text-align:center;\">{}</h2>\n",
+            labels.statistics_title
+        );
+        if let Some(summary) = summary {
+            html.push_str(&format!(
+                "<p style=\"text-align:center;\">{}</p>\n",
+                Self::escape(summary)
+            ));
+        }
+        html.push_str(&format!("<table style=\"{}\">\n<tr>", TABLE_STYLE));
+        for header in labels.statistics_columns {
+            html.push_str(&format!(
+                "<th style=\"{}background:#D9D9D9;\">{}</th>",
+                CELL_STYLE, header
+            ));
+        }
+        html.push_str("</tr>\n");
+
+        for stat in statistics {
+            html.push_str(&format!(
+                "<tr><td style=\"{cell}\">{}</td><td style=\"{cell}\">{}</td><td style=\"{cell}\">{}</td>\
+                 <td style=\"{cell}{}\">{}</td><td style=\"{cell}\">{}</td><td style=\"{cell}\">{:.1}%</td></tr>\n",
+                stat.seq_num,
+                Self::escape(&stat.report_number),
+                Self::escape(&stat.problem_name),
+                Self::risk_background(&stat.risk_level),
+                Self::escape(&stat.severity_level),
+                stat.problem_count,
+                stat.percentage,
+                cell = CELL_STYLE,
+            ));
+        }
+
+        let summary = StatisticSummary::from_items(statistics);
+        html.push_str(&format!(
+            "<tr style=\"font-weight:bold;background:#F2F2F2;\"><td style=\"{cell}\" colspan=\"3\">{}</td>\
+             <td style=\"{cell}\">{}</td><td style=\"{cell}\">{}</td><td style=\"{cell}\"></td></tr>\n</table>\n",
+            labels.total,
+            summary.severity_text(language),
+            summary.total_count,
+            cell = CELL_STYLE,
+        ));
+
+        html
+    }
+
+    /// 附录：标题比附录文件中低一级（报告标题使用 h1），正文每行一个段落
+    fn appendix(lines: &[AppendixLine]) -> String {
+        let mut html = String::new();
+        for line in lines {
+            match line {
+                AppendixLine::Heading(level, text) => html.push_str(&format!(
+                    "<h{0}>{1}</h{0}>\n",
+                    level + 1,
+                    Self::escape(text)
+                )),
+                AppendixLine::Text(text) if text.trim().is_empty() => {}
+                AppendixLine::Text(text) => {
+                    html.push_str(&format!("<p>{}</p>\n", Self::escape(text)))
+                }
+            }
+        }
+        html
+    }
+
+    /// 单个章节卡片
+    fn section(config: &ReportConfig, section: &ReportSection) -> String {
+        let labels = config.language.labels();
+        let mut html = format!(
+            "<div style=\"border:1px solid #CCC;border-radius:4px;padding:12px 16px;margin:24px 0;\">\n\
+             <h3>{}</h3>\n<table style=\"{}\">\n",
+            Self::escape(&section.title),
+            TABLE_STYLE
+        );
+
+        html.push_str(&format!(
+            "<tr><td style=\"{label}\">{}</td><td style=\"{cell}\">{}</td>\
+             <td style=\"{label}\">{}</td><td style=\"{cell}\">{}</td></tr>\n",
+            labels.report_number,
+            Self::escape(&section.report_number),
+            labels.software_version,
+            Self::escape(&config.code_version),
+            label = LABEL_STYLE,
+            cell = CELL_STYLE,
+        ));
+        html.push_str(&format!(
+            "<tr><td style=\"{label}\">{}</td><td style=\"{cell}\">{}</td>\
+             <td style=\"{label}\">{}</td><td style=\"{cell}\">{}</td></tr>\n",
+            labels.tester,
+            Self::escape(&config.ceshi_user),
+            labels.test_time,
+            Self::escape(&config.ceshi_time),
+            label = LABEL_STYLE,
+            cell = CELL_STYLE,
+        ));
+
+        let description = format!(
+            "{}<br>{}{}",
+            labels.line_label(labels.phenomenon),
+            Self::escape_multiline(&section.phenomenon),
+            Self::pre_block(&section.code)
+        );
+        let mut risk_text = Self::escape(&section.risk_level.text(config.language));
+        if let Some(raw) = &section.raw_severity {
+            risk_text.push_str(&format!(
+                "<br>{}{}{}",
+                labels.raw_severity,
+                labels.colon,
+                Self::escape(raw)
+            ));
+        }
+        let rows = [
+            (labels.description, description, String::new()),
+            (
+                labels.severity,
+                risk_text,
+                Self::risk_background(&section.risk_level),
+            ),
+            (
+                labels.file_path,
+                Self::pre_block(&section.code_path),
+                String::new(),
+            ),
+            (
+                labels.vulnerability,
+                Self::escape_multiline(&section.vulnerability),
+                String::new(),
+            ),
+            (
+                labels.suggestion,
+                Self::escape_multiline(&section.suggestion),
+                String::new(),
+            ),
+        ];
+        for (label, content, background) in rows {
+            html.push_str(&format!(
+                "<tr><td style=\"{}\">{}</td><td style=\"{}{}\" colspan=\"3\">{}</td></tr>\n",
+                LABEL_STYLE, label, CELL_STYLE, background, content
+            ));
+        }
+        for (label, value) in &section.extra_fields {
+            html.push_str(&format!(
+                "<tr><td style=\"{}\">{}</td><td style=\"{}\" colspan=\"3\">{}</td></tr>\n",
+                LABEL_STYLE,
+                Self::escape(label),
+                CELL_STYLE,
+                Self::escape_multiline(value)
+            ));
+        }
+
+        html.push_str("</table>\n");
+
+        if !section.record_details.is_empty() {
+            html.push_str(&format!(
+                "<p style=\"font-weight:bold;\">{}</p>\n<table style=\"{}\">\n<tr>",
+                labels.record_details, TABLE_STYLE
+            ));
+            for header in labels.record_detail_columns {
+                html.push_str(&format!(
+                    "<th style=\"{}background:#D9D9D9;\">{}</th>",
+                    CELL_STYLE, header
+                ));
+            }
+            html.push_str("</tr>\n");
+            for (i, detail) in section.record_details.iter().enumerate() {
+                html.push_str(&format!(
+                    "<tr><td style=\"{cell}text-align:center;\">{}</td><td style=\"{cell}\">{}</td>\
+                     <td style=\"{cell}\">{}</td></tr>\n",
+                    i + 1,
+                    Self::escape_multiline(&detail.path),
+                    Self::pre_block(&detail.code),
+                    cell = CELL_STYLE,
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</div>\n");
+        html
+    }
+
+    /// 风险等级背景色（与Word报告一致）
+    fn risk_background(risk_level: &RiskLevel) -> String {
+        RISK_LEVEL_FILLS
+            .iter()
+            .find(|(level, _)| level == risk_level)
+            .map(|(_, fill)| format!("background:#{};", fill))
+            .unwrap_or_default()
+    }
+
+    /// 保留换行的代码块
+    fn pre_block(text: &str) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+        format!(
+            "<pre style=\"{}\">{}</pre>",
+            PRE_STYLE,
+            Self::escape(&normalize_newlines(text))
+        )
+    }
+
+    /// 转义后将换行转换为 <br>
+    fn escape_multiline(text: &str) -> String {
+        Self::escape(&normalize_newlines(text)).replace('\n', "<br>")
+    }
+
+    /// HTML转义，防止单元格内容注入标签或脚本
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ReportError;
+    use crate::models::{GroupInfo, NoFindingsMode};
+    use crate::processors::test_support::record_of;
+
+    #[test]
+    fn test_render_escapes_finding_text() {
+        let result = ExcelProcessResult::from_groups(vec![(
+            "<script>alert(1)</script>|高危".to_string(),
+            GroupInfo {
+                b_column: "<script>alert(1)</script>".to_string(),
+                d_column: "高危".to_string(),
+                record_count: 1,
+                records: vec![record_of(&[
+                    ("B", "<script>alert(1)</script>"),
+                    ("D", "高危"),
+                    ("I", "/src/a.c"),
+                    ("J", "if (a < b && c > d) {}"),
+                ])],
+                original_severity: None,
+            },
+        )]);
+        let dir = std::env::temp_dir().join("report_forge_html_appendix_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let appendix = dir.join("appendix.md");
+        std::fs::write(&appendix, "# 术语表\n<b>XSS</b>：跨站脚本\n").unwrap();
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            appendix_file: Some(appendix.display().to_string()),
+            ..ReportConfig::default()
+        };
+
+        let html = HtmlGenerator::render(&config, &result, |_, _| {}).unwrap();
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("if (a &lt; b &amp;&amp; c &gt; d) {}</pre>"));
+        assert!(html.contains("background:#F4B6B6;"));
+        assert!(html.contains("<h2>术语表</h2>\n<p>&lt;b&gt;XSS&lt;/b&gt;：跨站脚本</p>\n</body>"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_html_record_details_raw_severity_and_no_findings() {
+        let result = ExcelProcessResult::from_groups(vec![(
+            "SQL注入|高危".to_string(),
+            GroupInfo {
+                b_column: "SQL注入".to_string(),
+                d_column: "高危".to_string(),
+                record_count: 1,
+                records: vec![record_of(&[
+                    ("B", "SQL注入"),
+                    ("D", "高危"),
+                    ("I", "/src/a.c"),
+                    ("J", "a < b"),
+                ])],
+                original_severity: None,
+            },
+        )]);
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            record_details_table: true,
+            show_raw_severity: true,
+            ..ReportConfig::default()
+        };
+
+        let html = HtmlGenerator::render(&config, &result, |_, _| {}).unwrap();
+        assert!(html.contains("<br>原始级别：高危"));
+        assert!(html.contains("<p style=\"font-weight:bold;\">缺陷明细</p>"));
+        assert!(html.contains(&format!(
+            "<td style=\"{cell}\">/src/a.c</td><td style=\"{cell}\"><pre style=\"{}\">a &lt; b</pre></td>",
+            PRE_STYLE,
+            cell = CELL_STYLE,
+        )));
+
+        // 没有问题时以说明代替空的统计表格，Error 模式返回错误
+        let empty = ExcelProcessResult::from_groups(Vec::new());
+        let html = HtmlGenerator::render(&config, &empty, |_, _| {}).unwrap();
+        assert!(html.contains("本次测试未发现问题"));
+        assert!(!html.contains("<table"));
+
+        config.no_findings_mode = NoFindingsMode::Error;
+        let error = HtmlGenerator::render(&config, &empty, |_, _| {}).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ReportError>(),
+            Some(ReportError::NoFindings)
+        ));
+    }
+}
@@ -0,0 +1,357 @@
+use crate::models::{ExcelProcessResult, Language, ReportConfig, StatisticItem, StatisticSummary};
+use crate::processors::word_generator::{AppendixLine, ReportSection};
+use crate::processors::{normalize_newlines, WordGenerator};
+use anyhow::Result;
+
+pub struct MarkdownGenerator;
+
+impl MarkdownGenerator {
+    /// 生成Markdown报告，内容与Word报告一致；每写完一个章节调用 on_progress(已完成章节数, 章节总数)
+    pub fn generate_report<F>(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+        on_progress: F,
+    ) -> Result<String>
+    where
+        F: FnMut(usize, usize),
+    {
+        log::info!("开始生成Markdown报告");
+
+        let markdown = Self::render(config, result_data, on_progress)?;
+
+        let timestamp = chrono::Local::now().timestamp_millis();
+        let file_stem = config.output_file_stem(timestamp)?;
+        let output_file = WordGenerator::write_output_file(
+            &config.output_dir,
+            &file_stem,
+            "md",
+            markdown.as_bytes(),
+        )?;
+
+        log::info!("报告生成完成！文件: {}", output_file);
+        Ok(output_file)
+    }
+
+    /// 渲染完整的Markdown文本
+    fn render<F>(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+        mut on_progress: F,
+    ) -> Result<String>
+    where
+        F: FnMut(usize, usize),
+    {
+        let result_data = WordGenerator::prepare_groups(config, result_data);
+        let no_findings = WordGenerator::no_findings(config, &result_data)?;
+
+        let mut markdown = format!("# {}\n\n", config.title());
+
+        let generated_date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let labels = config.language.labels();
+        let metadata = [
+            (labels.identifier, config.identifier_tag.as_str()),
+            (labels.software_version, config.code_version.as_str()),
+            (labels.tester, config.ceshi_user.as_str()),
+            (labels.test_time, config.ceshi_time.as_str()),
+            (labels.generated_date, generated_date.as_str()),
+        ];
+        for (label, value) in metadata {
+            markdown.push_str(&format!("- **{}**{}{}\n", label, labels.colon, value));
+        }
+        markdown.push('\n');
+
+        if no_findings {
+            // 没有问题时以说明代替统计表格
+            markdown.push_str(&format!("**{}**\n\n", labels.no_findings));
+        } else {
+            let statistics = WordGenerator::generate_statistics(&result_data, config)?;
+            let summary =
+                config.statistics_summary(result_data.total_records, result_data.total_groups)?;
+            markdown.push_str(&Self::statistics_table(
+                &statistics,
+                summary.as_deref(),
+                config.language,
+            ));
+        }
+
+        let report_numbers = config.report_numbers(&result_data.grouped_data)?;
+        for (title_num, ((group_key, group_info), report_number)) in
+            (1..).zip(result_data.grouped_data.iter().zip(report_numbers))
+        {
+            let section = WordGenerator::build_section(
+                config,
+                group_key,
+                group_info,
+                title_num,
+                report_number,
+            )?;
+            markdown.push_str(&Self::section(config, &section));
+            on_progress(title_num as usize, result_data.grouped_data.len());
+        }
+
+        if let Some(appendix) = WordGenerator::appendix_lines(config) {
+            markdown.push_str(&Self::appendix(&appendix));
+        }
+
+        if let Some(stamp) = config.generation_stamp_text() {
+            markdown.push_str(&format!("---\n\n*{}*\n", stamp));
+        }
+
+        Ok(markdown)
+    }
+
+    /// 统计表格（GFM表格语法），summary 为标题下方的汇总语句
+    fn statistics_table(
+        statistics: &[StatisticItem],
+        summary: Option<&str>,
+        language: Language,
+    ) -> String {
+        let labels = language.labels();
+        let mut table = format!("## {}\n\n", labels.statistics_title);
+        if let Some(summary) = summary {
+            table.push_str(&format!("{}\n\n", summary));
+        }
+        let columns = labels.statistics_columns;
+        table.push_str(&format!("| {} |\n", columns.join(" | ")));
+        table.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+
+        for stat in statistics {
+            table.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {:.1}% |\n",
+                stat.seq_num,
+                Self::table_cell(&stat.report_number),
+                Self::table_cell(&stat.problem_name),
+                stat.severity_level,
+                stat.problem_count,
+                stat.percentage
+            ));
+        }
+
+        let summary = StatisticSummary::from_items(statistics);
+        table.push_str(&format!(
+            "| **{}** | | | {} | {} | |\n\n",
+            labels.total,
+            summary.severity_text(language),
+            summary.total_count
+        ));
+
+        table
+    }
+
+    /// 附录：标题比附录文件中低一级（报告标题占用一级标题），正文每行一个段落
+    fn appendix(lines: &[AppendixLine]) -> String {
+        let mut markdown = String::new();
+        for line in lines {
+            match line {
+                AppendixLine::Heading(level, text) => {
+                    markdown.push_str(&format!("{} {}\n\n", "#".repeat(level + 1), text))
+                }
+                AppendixLine::Text(text) if text.trim().is_empty() => {}
+                AppendixLine::Text(text) => markdown.push_str(&format!("{}\n\n", text)),
+            }
+        }
+        markdown
+    }
+
+    /// 单个章节：标题、基本信息及各字段，代码与路径使用代码块
+    fn section(config: &ReportConfig, section: &ReportSection) -> String {
+        let labels = config.language.labels();
+        let mut text = format!("### {}\n\n", section.title);
+
+        let item = |label: &str, value: &str| format!("- **{}**{}{}\n", label, labels.colon, value);
+        text.push_str(&item(labels.report_number, &section.report_number));
+        text.push_str(&item(labels.software_version, &config.code_version));
+        text.push_str(&item(labels.tester, &config.ceshi_user));
+        text.push_str(&item(labels.test_time, &config.ceshi_time));
+        text.push_str(&item(
+            labels.severity,
+            &section.risk_level.text(config.language),
+        ));
+        if let Some(raw) = &section.raw_severity {
+            text.push_str(&item(labels.raw_severity, &Self::table_cell(raw)));
+        }
+        text.push('\n');
+
+        text.push_str(&format!("**{}**\n\n", labels.description));
+        text.push_str(&format!(
+            "{}{}{}\n\n",
+            labels.phenomenon,
+            labels.colon,
+            normalize_newlines(&section.phenomenon)
+        ));
+        text.push_str(&Self::code_block(&section.code));
+
+        text.push_str(&format!("**{}**\n\n", labels.file_path));
+        text.push_str(&Self::code_block(&section.code_path));
+
+        text.push_str(&format!("**{}**\n\n", labels.vulnerability));
+        text.push_str(&format!(
+            "{}\n\n",
+            normalize_newlines(&section.vulnerability)
+        ));
+
+        text.push_str(&format!("**{}**\n\n", labels.suggestion));
+        text.push_str(&format!("{}\n\n", normalize_newlines(&section.suggestion)));
+
+        for (label, value) in &section.extra_fields {
+            text.push_str(&format!("**{}**\n\n", label));
+            text.push_str(&format!("{}\n\n", normalize_newlines(value)));
+        }
+
+        if !section.record_details.is_empty() {
+            let columns = labels.record_detail_columns;
+            text.push_str(&format!("**{}**\n\n", labels.record_details));
+            text.push_str(&format!("| {} |\n", columns.join(" | ")));
+            text.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+            for (i, detail) in section.record_details.iter().enumerate() {
+                text.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    i + 1,
+                    Self::table_cell(&detail.path),
+                    Self::table_cell(&detail.code)
+                ));
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// 围栏代码块，内容中包含 ``` 时加长围栏
+    fn code_block(code: &str) -> String {
+        if code.is_empty() {
+            return String::new();
+        }
+
+        let code = normalize_newlines(code);
+        let mut fence = "```".to_string();
+        while code.contains(&fence) {
+            fence.push('`');
+        }
+
+        format!("{}\n{}\n{}\n\n", fence, code, fence)
+    }
+
+    /// 表格单元格转义：竖线转义，换行替换为 <br>
+    fn table_cell(text: &str) -> String {
+        normalize_newlines(text)
+            .replace('|', "\\|")
+            .replace('\n', "<br>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ReportError;
+    use crate::models::{GroupInfo, NoFindingsMode};
+    use crate::processors::test_support::record_of;
+
+    #[test]
+    fn test_render_markdown_report() {
+        let result = ExcelProcessResult::from_groups(vec![(
+            "SQL注入|高危".to_string(),
+            GroupInfo {
+                b_column: "SQL注入".to_string(),
+                d_column: "高危".to_string(),
+                record_count: 1,
+                records: vec![record_of(&[
+                    ("B", "SQL注入"),
+                    ("D", "高危"),
+                    ("I", "/src/db.c"),
+                    ("J", "query(\"a|b\");"),
+                    ("K", "拼接SQL语句"),
+                    ("N", "使用参数化查询"),
+                ])],
+                original_severity: None,
+            },
+        )]);
+        let dir = std::env::temp_dir().join("report_forge_markdown_appendix_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let appendix = dir.join("appendix.md");
+        std::fs::write(&appendix, "# 术语表\n\nXSS：跨站脚本\n## 测试方法\n").unwrap();
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            generation_stamp: true,
+            appendix_file: Some(appendix.display().to_string()),
+            ..ReportConfig::default()
+        };
+
+        let markdown = MarkdownGenerator::render(&config, &result, |_, _| {}).unwrap();
+
+        assert!(markdown.contains("## 问题统计表格\n\n共发现 1 条问题，归并为 1 类\n\n"));
+        assert!(markdown.contains("| 1 | TAG0001 | SQL注入 | 高 | 1 | 100.0% |"));
+        assert!(markdown.contains("### 1、SQL注入"));
+        assert!(markdown.contains("```\n缺陷1相关代码如下：\nquery(\"a|b\");\n```"));
+        assert!(markdown.contains("```\n缺陷1文件路径：\n/src/db.c\n```"));
+        assert!(markdown.contains("**整改建议**\n\n使用参数化查询"));
+        // 附录在章节之后、生成标记之前，标题降一级
+        assert!(markdown.contains("## 术语表\n\nXSS：跨站脚本\n\n### 测试方法\n\n---"));
+        let stamp = format!("---\n\n*由 report-forge v{} 于 ", env!("CARGO_PKG_VERSION"));
+        assert!(markdown.contains(&stamp));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_markdown_record_details_and_no_findings() {
+        let result = ExcelProcessResult::from_groups(vec![(
+            "SQL注入|高危".to_string(),
+            GroupInfo {
+                b_column: "SQL注入".to_string(),
+                d_column: "高危".to_string(),
+                record_count: 2,
+                records: vec![
+                    record_of(&[
+                        ("B", "SQL注入"),
+                        ("D", "高危"),
+                        ("I", "/src/a.c"),
+                        ("J", "a|b"),
+                    ]),
+                    record_of(&[
+                        ("B", "SQL注入"),
+                        ("D", "高危"),
+                        ("I", "/src/b.c"),
+                        ("J", "c\nd"),
+                    ]),
+                ],
+                original_severity: None,
+            },
+        )]);
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            record_details_table: true,
+            ..ReportConfig::default()
+        };
+
+        let markdown = MarkdownGenerator::render(&config, &result, |_, _| {}).unwrap();
+        assert!(markdown.contains(
+            "**缺陷明细**\n\n| 序号 | 文件路径 | 相关代码 |\n| --- | --- | --- |\n\
+             | 1 | /src/a.c | a\\|b |\n| 2 | /src/b.c | c<br>d |\n"
+        ));
+
+        // 没有问题时以说明代替空的统计表格，Error 模式返回错误
+        let empty = ExcelProcessResult::from_groups(Vec::new());
+        let markdown = MarkdownGenerator::render(&config, &empty, |_, _| {}).unwrap();
+        assert!(markdown.contains("**本次测试未发现问题**"));
+        assert!(!markdown.contains("问题统计表格"));
+
+        config.no_findings_mode = NoFindingsMode::Error;
+        let error = MarkdownGenerator::render(&config, &empty, |_, _| {}).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ReportError>(),
+            Some(ReportError::NoFindings)
+        ));
+    }
+
+    #[test]
+    fn test_code_block_fence_and_table_cell_escaping() {
+        assert_eq!(
+            MarkdownGenerator::code_block("a```b"),
+            "````\na```b\n````\n\n"
+        );
+        assert_eq!(MarkdownGenerator::code_block(""), "");
+        assert_eq!(MarkdownGenerator::table_cell("a|b\r\nc"), "a\\|b<br>c");
+    }
+}
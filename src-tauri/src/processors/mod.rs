@@ -1,5 +1,662 @@
 pub mod excel_processor;
+pub mod html_generator;
+pub mod markdown_generator;
+pub mod text_generator;
 pub mod word_generator;
 
-pub use excel_processor::ExcelProcessor;
+pub use excel_processor::{ExcelProcessor, ProgressHook};
+pub use html_generator::HtmlGenerator;
+pub use markdown_generator::MarkdownGenerator;
+pub use text_generator::TextGenerator;
 pub use word_generator::WordGenerator;
+
+use crate::models::{
+    ExcelProcessResult, GenerationCheckpoint, OutputFormat, ProcessingProgress, ReportConfig,
+    ReportEstimate, ReportMetrics, RiskLevel,
+};
+use anyhow::Result;
+use docx_rs::Docx;
+use std::path::Path;
+
+/// 统一换行符为 \n（Excel中的换行可能是 \r\n、\r 或 _x000D_，可能混用；
+/// _x000D_ 是转义后的 \r，其后紧跟 \n 时与 \r\n 一样只算一个换行）
+pub(crate) fn normalize_newlines(text: &str) -> String {
+    text.replace("_x000D_\n", "\n")
+        .replace("\r\n", "\n")
+        .replace("_x000D_", "\n")
+        .replace('\r', "\n")
+}
+
+/// 按配置的输出格式生成报告，返回输出文件路径
+///
+/// 每写完一个章节调用 on_progress(已完成章节数, 章节总数)，章节总数为追加基线、过滤分组后的章节数。
+/// 设置了 baseline_result 时在基线结果之后追加新的分组，已有章节编号不变；
+/// 设置了 delta_baseline 时生成只包含新增分组的变化报告（快照保存本次的完整结果，供下次对比）。
+pub fn generate_report<F>(
+    config: &ReportConfig,
+    result_data: &ExcelProcessResult,
+    on_progress: F,
+) -> Result<String>
+where
+    F: FnMut(usize, usize),
+{
+    if let Some(delta_baseline) = &config.delta_baseline {
+        if config.output_format != OutputFormat::Word {
+            anyhow::bail!("变化报告仅支持Word格式");
+        }
+        let doc = build_delta_report(config, delta_baseline, result_data, on_progress)?;
+        let output_file = WordGenerator::write_report(doc, config)?;
+        if config.save_result_snapshot {
+            save_result_snapshot(&Path::new(&output_file).with_extension("json"), result_data);
+        }
+        return Ok(output_file);
+    }
+
+    let appended = append_to_baseline(config, result_data)?;
+    let result_data = appended.as_ref().unwrap_or(result_data);
+
+    let output_file = write_report_file(config, result_data, on_progress)?;
+
+    if config.save_result_snapshot {
+        save_result_snapshot(&Path::new(&output_file).with_extension("json"), result_data);
+    }
+    Ok(output_file)
+}
+
+/// 按配置生成报告，设置了 split_by_severity 时按风险等级分别生成，返回各输出文件路径
+pub fn generate_reports<F>(
+    config: &ReportConfig,
+    result_data: &ExcelProcessResult,
+    on_progress: F,
+) -> Result<Vec<String>>
+where
+    F: FnMut(usize, usize),
+{
+    if config.split_by_severity {
+        generate_reports_by_severity(config, result_data, on_progress)
+    } else {
+        Ok(vec![generate_report(config, result_data, on_progress)?])
+    }
+}
+
+/// 按输出格式写入报告文件（不追加基线、不保存快照）
+fn write_report_file<F>(
+    config: &ReportConfig,
+    result_data: &ExcelProcessResult,
+    on_progress: F,
+) -> Result<String>
+where
+    F: FnMut(usize, usize),
+{
+    match config.output_format {
+        OutputFormat::Word => WordGenerator::generate_report(config, result_data, on_progress),
+        OutputFormat::Markdown => {
+            MarkdownGenerator::generate_report(config, result_data, on_progress)
+        }
+        OutputFormat::Html => HtmlGenerator::generate_report(config, result_data, on_progress),
+    }
+}
+
+/// 保存处理结果快照（通常在报告同一目录，与报告同名的 .json）；
+/// 此时报告已写入，保存失败只记录警告
+fn save_result_snapshot(snapshot: &Path, result_data: &ExcelProcessResult) {
+    match result_data.save(snapshot) {
+        Ok(()) => log::info!("处理结果已保存: {:?}", snapshot),
+        Err(e) => log::warn!("报告已生成，但处理结果保存失败: {:#}", e),
+    }
+}
+
+/// 与 delta_baseline 中保存的处理结果按分组键对比，构建变化报告
+fn build_delta_report<F>(
+    config: &ReportConfig,
+    delta_baseline: &str,
+    result_data: &ExcelProcessResult,
+    on_progress: F,
+) -> Result<Docx>
+where
+    F: FnMut(usize, usize),
+{
+    let baseline = ExcelProcessResult::load(delta_baseline)?;
+    let diff = ExcelProcessor::diff_results(&baseline, result_data);
+    WordGenerator::build_delta_report(config, &diff, on_progress)
+}
+
+/// 生成Word报告并返回docx文件内容（不写入文件），忽略 output_format 和 save_result_snapshot
+///
+/// 与 generate_report 相同，设置了 baseline_result 时在基线结果之后追加新的分组，
+/// 设置了 delta_baseline 时生成变化报告。
+pub fn generate_report_bytes<F>(
+    config: &ReportConfig,
+    result_data: &ExcelProcessResult,
+    on_progress: F,
+) -> Result<Vec<u8>>
+where
+    F: FnMut(usize, usize),
+{
+    if let Some(delta_baseline) = &config.delta_baseline {
+        let doc = build_delta_report(config, delta_baseline, result_data, on_progress)?;
+        return WordGenerator::pack_to_bytes(doc, config);
+    }
+
+    let appended = append_to_baseline(config, result_data)?;
+    let result_data = appended.as_ref().unwrap_or(result_data);
+
+    let doc = WordGenerator::build_report(config, result_data, on_progress)?;
+    WordGenerator::pack_to_bytes(doc, config)
+}
+
+/// 按风险等级分别生成报告（每个出现的等级一份，只包含该等级的分组），返回各输出文件路径
+///
+/// 文件名在 output_filename_template 之后加上等级名称（如 "_高危"），每份报告单独编号。
+/// 基线追加及分组准备（归类、过滤）只执行一次，再按等级拆分；
+/// 设置了 save_result_snapshot 时只保存一份完整结果快照（文件名不带等级后缀）。
+/// 各份报告的章节连续计数，on_progress 的章节总数为所有报告的章节数之和。
+/// 变化报告（delta_baseline）不支持按等级拆分。
+pub fn generate_reports_by_severity<F>(
+    config: &ReportConfig,
+    result_data: &ExcelProcessResult,
+    mut on_progress: F,
+) -> Result<Vec<String>>
+where
+    F: FnMut(usize, usize),
+{
+    if config.delta_baseline.is_some() {
+        anyhow::bail!("变化报告不支持按风险等级分别生成");
+    }
+    let appended = append_to_baseline(config, result_data)?;
+    let result_data = appended.as_ref().unwrap_or(result_data);
+    let prepared = WordGenerator::prepare_groups(config, result_data);
+
+    let mut levels: Vec<RiskLevel> = Vec::new();
+    for (_, info) in &prepared.grouped_data {
+        let level = RiskLevel::from_severity(&info.d_column);
+        if !levels.contains(&level) {
+            levels.push(level);
+        }
+    }
+    levels.sort_by_key(RiskLevel::priority);
+
+    let output_files = if levels.is_empty() {
+        // 没有任何问题时按 no_findings_mode 生成一份报告或返回错误
+        vec![write_report_file(config, &prepared, on_progress)?]
+    } else {
+        // 分组已准备好，各等级的报告不再重复过滤
+        let prepared_config = ReportConfig {
+            severity_filter: None,
+            min_record_count: 1,
+            ..config.clone()
+        };
+        let total_sections = prepared.grouped_data.len();
+        let mut sections_done = 0;
+        let mut output_files = Vec::with_capacity(levels.len());
+        for level in levels {
+            log::info!("生成{}报告", level.severity_name());
+            let level_config = ReportConfig {
+                output_filename_template: format!(
+                    "{}_{}",
+                    config.output_filename_template,
+                    level.severity_name()
+                ),
+                ..prepared_config.clone()
+            };
+            let level_result = ExcelProcessor::filter_by_severity(&prepared, &[level]);
+            let level_progress = |_: usize, _: usize| {
+                sections_done += 1;
+                on_progress(sections_done, total_sections);
+            };
+            output_files.push(write_report_file(
+                &level_config,
+                &level_result,
+                level_progress,
+            )?);
+        }
+        output_files
+    };
+
+    if config.save_result_snapshot {
+        let timestamp = chrono::Local::now().timestamp_millis();
+        match config.output_file_stem(timestamp) {
+            Ok(file_stem) => {
+                let snapshot = Path::new(&config.output_dir).join(format!("{}.json", file_stem));
+                save_result_snapshot(&snapshot, result_data);
+            }
+            Err(e) => log::warn!("报告已生成，但处理结果保存失败: {:#}", e),
+        }
+    }
+    Ok(output_files)
+}
+
+/// 按配置读取输入文件并去重、分组：设置了 streaming_read 时流式处理，否则合并全部数据后处理；
+/// 不读写断点文件，用于统计指标、预估规模等不生成报告的场景
+pub fn load_result(config: &ReportConfig) -> Result<ExcelProcessResult> {
+    read_input(config, &mut |_, _| {})
+}
+
+/// 生成报告的各入口（界面命令、命令行、从断点继续）共用的读取流程：
+/// 设置了 checkpoint_file 时断点续读（checkpoint 为已读取的断点），否则同 load_result
+///
+/// 每读取完一个文件调用 on_file_done(已处理文件数, 文件总数)；合并读取时在全部文件合并后调用一次。
+pub fn read_and_process(
+    config: &ReportConfig,
+    checkpoint: Option<GenerationCheckpoint>,
+    on_file_done: &mut dyn FnMut(usize, usize),
+) -> Result<ExcelProcessResult> {
+    match &config.checkpoint_file {
+        Some(checkpoint_file) => {
+            process_with_checkpoint(config, checkpoint_file, checkpoint, on_file_done)
+        }
+        None => read_input(config, on_file_done),
+    }
+}
+
+/// 流式处理或合并全部数据后处理（不使用断点）
+fn read_input(
+    config: &ReportConfig,
+    on_file_done: &mut dyn FnMut(usize, usize),
+) -> Result<ExcelProcessResult> {
+    if config.streaming_read {
+        return ExcelProcessor::process_excel_files_resumable(
+            &config.excel_files,
+            &config.process,
+            None,
+            None,
+            on_file_done,
+        );
+    }
+
+    let (merged_data, skipped_files) =
+        ExcelProcessor::merge_excel_files_with_policy(&config.excel_files, &config.process)?;
+    on_file_done(config.excel_files.len(), config.excel_files.len());
+    let mut result = ExcelProcessor::process_raw_data(merged_data, &config.process)?;
+    result.skipped_files = skipped_files;
+    Ok(result)
+}
+
+/// 按与生成报告相同的流程（读取、去重、分组、追加基线、过滤）统计处理指标，不生成报告
+pub fn compute_metrics(config: &ReportConfig) -> Result<ReportMetrics> {
+    let result = load_result(config)?;
+    let appended = append_to_baseline(config, &result)?;
+
+    let selected = WordGenerator::select_groups(config, appended.as_ref().unwrap_or(&result));
+    let (excluded_groups, excluded_records) = selected
+        .grouped_data
+        .iter()
+        .filter(|(_, info)| info.record_count < config.min_record_count)
+        .fold((0, 0), |(groups, records), (_, info)| {
+            (groups + 1, records + info.record_count)
+        });
+    let prepared = ExcelProcessor::filter_by_min_records(&selected, config.min_record_count);
+
+    Ok(ReportMetrics {
+        total_files: config.excel_files.len() - result.skipped_files.len(),
+        skipped_files: result.skipped_files.len(),
+        total_raw_rows: result.total_records + result.removed_duplicates.len(),
+        duplicates_removed: result.removed_duplicates.len(),
+        total_records: prepared.total_records,
+        total_groups: prepared.total_groups,
+        severity_counts: prepared.severity_counts,
+        excluded_groups,
+        excluded_records,
+    })
+}
+
+/// 按与生成报告相同的流程读取、处理数据后预估报告规模（不生成报告），章节过多或文件过大时附带提示
+pub fn estimate_report(config: &ReportConfig) -> Result<ReportEstimate> {
+    let result = load_result(config)?;
+    let appended = append_to_baseline(config, &result)?;
+    WordGenerator::estimate_report(config, appended.as_ref().unwrap_or(&result))
+}
+
+/// 流式处理输入文件，每处理完 checkpoint_interval 个文件将去重状态连同配置写入断点文件 checkpoint_file
+///
+/// checkpoint 为之前保存的断点且输入（文件列表、文件大小及修改时间、处理选项）未变化时从断点继续；
+/// 输入已变化时忽略断点，重新处理（是否沿用断点由调用方按 GenerationCheckpoint::matches_inputs 提示用户）；
+/// 每处理完一个文件调用 on_file_done(已处理文件数, 文件总数)
+pub fn process_with_checkpoint(
+    config: &ReportConfig,
+    checkpoint_file: &str,
+    checkpoint: Option<GenerationCheckpoint>,
+    on_file_done: &mut dyn FnMut(usize, usize),
+) -> Result<ExcelProcessResult> {
+    let input_fingerprint = GenerationCheckpoint::input_fingerprint(config);
+    let resume = checkpoint
+        .filter(|checkpoint| checkpoint.input_fingerprint == input_fingerprint)
+        .map(|checkpoint| checkpoint.progress);
+
+    let mut save = |progress: ProcessingProgress| -> Result<()> {
+        GenerationCheckpoint::write(checkpoint_file, &input_fingerprint, config, &progress)?;
+        log::info!(
+            "断点已保存: {}（已处理 {}/{} 个文件）",
+            checkpoint_file,
+            progress.files_done,
+            config.excel_files.len()
+        );
+        Ok(())
+    };
+    let hook = ProgressHook {
+        every_files: config.checkpoint_interval,
+        on_progress: &mut save,
+    };
+    ExcelProcessor::process_excel_files_resumable(
+        &config.excel_files,
+        &config.process,
+        resume,
+        Some(hook),
+        on_file_done,
+    )
+}
+
+/// 读取已有的断点文件；文件不存在时返回 None，无法读取或格式错误时记录警告并返回 None（重新处理）
+pub fn load_checkpoint(checkpoint_file: &str) -> Option<GenerationCheckpoint> {
+    if !Path::new(checkpoint_file).exists() {
+        return None;
+    }
+    match GenerationCheckpoint::load(checkpoint_file) {
+        Ok(checkpoint) => Some(checkpoint),
+        Err(e) => {
+            log::warn!("忽略无法读取的断点文件: {:#}", e);
+            None
+        }
+    }
+}
+
+/// 报告生成成功后删除断点文件，删除失败只记录警告
+pub fn remove_checkpoint(checkpoint_file: &str) {
+    match std::fs::remove_file(checkpoint_file) {
+        Ok(()) => log::info!("已删除断点文件: {}", checkpoint_file),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("无法删除断点文件 {}: {}", checkpoint_file, e),
+    }
+}
+
+/// 从断点文件继续生成报告，返回各输出文件路径（设置了 split_by_severity 时每个风险等级一份）
+///
+/// 使用断点中保存的配置；输入未变化时跳过已处理的文件，已变化时忽略断点中的进度并重新处理。
+/// 报告生成成功后删除断点文件。
+pub fn resume_generation<F>(checkpoint_path: &str, on_progress: F) -> Result<Vec<String>>
+where
+    F: FnMut(usize, usize),
+{
+    let checkpoint = GenerationCheckpoint::load(checkpoint_path)?;
+    let config = ReportConfig {
+        checkpoint_file: Some(checkpoint_path.to_string()),
+        ..checkpoint.config.clone()
+    };
+    config.validate()?;
+    if !checkpoint.matches_inputs(&config) {
+        log::warn!(
+            "断点 {} 保存后输入文件或处理选项已变化，忽略断点并重新处理",
+            checkpoint_path
+        );
+    }
+
+    let result_data = read_and_process(&config, Some(checkpoint), &mut |_, _| {})?;
+    let output_files = generate_reports(&config, &result_data, on_progress)?;
+    remove_checkpoint(checkpoint_path);
+    Ok(output_files)
+}
+
+/// 设置了 baseline_result 时返回基线结果追加新分组后的结果
+fn append_to_baseline(
+    config: &ReportConfig,
+    result_data: &ExcelProcessResult,
+) -> Result<Option<ExcelProcessResult>> {
+    match &config.baseline_result {
+        Some(baseline) => {
+            let baseline = ExcelProcessResult::load(baseline)?;
+            let appended = ExcelProcessor::append_new_groups(&baseline, result_data)?;
+            Ok(Some(appended))
+        }
+        None => Ok(None),
+    }
+}
+
+/// 各生成器测试共用的辅助函数
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::models::ExcelRecord;
+    use std::io::Read;
+
+    /// 由（列, 取值）创建记录
+    pub(crate) fn record_of(cells: &[(&str, &str)]) -> ExcelRecord {
+        ExcelRecord {
+            data: cells
+                .iter()
+                .map(|(column, value)| (column.to_string(), Some(value.to_string())))
+                .collect(),
+            dedup_key: None,
+        }
+    }
+
+    /// 读取docx文件内容中的 word/document.xml
+    pub(crate) fn document_xml(docx: Vec<u8>) -> String {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx)).unwrap();
+        let mut document = String::new();
+        archive
+            .by_name("word/document.xml")
+            .unwrap()
+            .read_to_string(&mut document)
+            .unwrap();
+        document
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GroupInfo;
+    use test_support::record_of;
+
+    fn group(name: &str, severity: &str) -> (String, GroupInfo) {
+        (
+            format!("{}|{}", name, severity),
+            GroupInfo {
+                b_column: name.to_string(),
+                d_column: severity.to_string(),
+                record_count: 1,
+                records: vec![record_of(&[("B", name), ("D", severity)])],
+                original_severity: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_generate_reports_by_severity() {
+        let grouped_data = vec![
+            group("XSS", "中危"),
+            group("SQL注入", "高危"),
+            group("CSRF", "中危"),
+        ];
+        let result = ExcelProcessResult::from_groups(grouped_data);
+        let output_dir = std::env::temp_dir().join("report_forge_split_by_severity_test");
+        let _ = std::fs::remove_dir_all(&output_dir);
+        let config = ReportConfig {
+            output_dir: output_dir.display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            output_filename_template: "{tag}".to_string(),
+            output_format: OutputFormat::Markdown,
+            split_by_severity: true,
+            save_result_snapshot: true,
+            ..ReportConfig::default()
+        };
+
+        let mut progress = Vec::new();
+        let files =
+            generate_reports(&config, &result, |done, total| progress.push((done, total))).unwrap();
+
+        assert_eq!(files.len(), 2);
+        // 各份报告的章节连续计数，进度不回退
+        assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+        assert!(files[0].ends_with("TAG_高危.md"));
+        assert!(files[1].ends_with("TAG_中危.md"));
+
+        let high = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(high.contains("### 1、SQL注入"));
+        assert!(!high.contains("XSS"));
+        // 每份报告单独编号
+        let medium = std::fs::read_to_string(&files[1]).unwrap();
+        assert!(medium.contains("| 1 | TAG0001 | XSS |"));
+        assert!(medium.contains("| 2 | TAG0002 | CSRF |"));
+        assert!(!medium.contains("SQL注入"));
+
+        // 只保存一份完整结果快照，文件名不带等级后缀
+        let snapshots: Vec<_> = std::fs::read_dir(&output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        assert_eq!(snapshots, vec![output_dir.join("TAG.json")]);
+        let snapshot = ExcelProcessResult::load(&snapshots[0]).unwrap();
+        assert_eq!(snapshot.total_groups, 3);
+    }
+
+    #[test]
+    fn test_delta_report_saves_full_snapshot() {
+        let result = ExcelProcessResult::from_groups;
+        let output_dir = std::env::temp_dir().join("report_forge_delta_report_test");
+        let _ = std::fs::remove_dir_all(&output_dir);
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let baseline = output_dir.join("last.json");
+        result(vec![group("XSS", "中危"), group("SQL注入", "高危")])
+            .save(&baseline)
+            .unwrap();
+        let current = result(vec![group("SQL注入", "高危"), group("CSRF", "中危")]);
+        let config = ReportConfig {
+            output_dir: output_dir.display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            delta_baseline: Some(baseline.display().to_string()),
+            save_result_snapshot: true,
+            ..ReportConfig::default()
+        };
+
+        let output_file = generate_report(&config, &current, |_, _| {}).unwrap();
+
+        assert!(output_file.ends_with(".docx"));
+        // 快照保存本次的完整结果，而不仅是新增的分组
+        let snapshot = ExcelProcessResult::load(Path::new(&output_file).with_extension("json"));
+        assert_eq!(snapshot.unwrap().total_groups, 2);
+
+        // 快照保存失败时报告仍然生成成功
+        let blocked = output_dir.join("blocked");
+        std::fs::create_dir_all(&blocked).unwrap();
+        std::fs::create_dir_all(blocked.join("TAG.json")).unwrap();
+        let blocked_config = ReportConfig {
+            output_dir: blocked.display().to_string(),
+            output_filename_template: "{tag}".to_string(),
+            ..config.clone()
+        };
+        let output_file = generate_report(&blocked_config, &current, |_, _| {}).unwrap();
+        assert!(Path::new(&output_file).is_file());
+
+        // 不按风险等级拆分变化报告
+        assert!(generate_reports_by_severity(&config, &current, |_, _| {}).is_err());
+
+        let markdown = ReportConfig {
+            output_format: OutputFormat::Markdown,
+            ..config
+        };
+        assert!(generate_report(&markdown, &current, |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn test_compute_metrics() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.xlsx");
+        let config = ReportConfig {
+            excel_files: vec![fixture.display().to_string()],
+            ..ReportConfig::default()
+        };
+
+        let metrics = compute_metrics(&config).unwrap();
+
+        assert_eq!(metrics.total_files, 1);
+        assert_eq!(metrics.skipped_files, 0);
+        assert_eq!(metrics.total_raw_rows, 7);
+        assert_eq!(metrics.duplicates_removed, 1);
+        assert_eq!(metrics.total_records, 6);
+        assert_eq!(metrics.total_groups, 3);
+        let counted: usize = metrics.severity_counts.values().sum();
+        assert_eq!(counted, 6);
+
+        // 与生成报告相同：流式读取，并按严重性及最少记录数过滤
+        let filtered = ReportConfig {
+            streaming_read: true,
+            severity_filter: Some(vec![RiskLevel::High, RiskLevel::Medium]),
+            min_record_count: 3,
+            ..config
+        };
+        let metrics = compute_metrics(&filtered).unwrap();
+        assert_eq!(metrics.total_raw_rows, 7);
+        assert_eq!(metrics.duplicates_removed, 1);
+        assert_eq!(metrics.total_records, 3);
+        assert_eq!(metrics.total_groups, 1);
+        assert_eq!(metrics.severity_counts[&RiskLevel::High], 3);
+        assert_eq!(metrics.severity_counts[&RiskLevel::Low], 0);
+        assert_eq!(metrics.excluded_groups, 1);
+        assert_eq!(metrics.excluded_records, 2);
+
+        let estimate = estimate_report(&filtered).unwrap();
+        assert_eq!(estimate.section_count, 1);
+        assert_eq!(estimate.total_records, 3);
+    }
+
+    #[test]
+    fn test_checkpoint_resume_and_stale_detection() {
+        let dir = std::env::temp_dir().join("report_forge_checkpoint_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.csv");
+        let input = dir.join("findings.csv");
+        std::fs::copy(fixture, &input).unwrap();
+        let checkpoint_file = dir.join("checkpoint.json").display().to_string();
+        let config = ReportConfig {
+            excel_files: vec![input.display().to_string()],
+            output_dir: dir.display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            checkpoint_file: Some(checkpoint_file.clone()),
+            ..ReportConfig::default()
+        };
+
+        let result =
+            process_with_checkpoint(&config, &checkpoint_file, None, &mut |_, _| {}).unwrap();
+        assert_eq!(result.total_records, 6);
+        let mut checkpoint = load_checkpoint(&checkpoint_file).unwrap();
+        assert_eq!(checkpoint.progress.files_done, 1);
+        assert!(checkpoint.matches_inputs(&config));
+
+        // 输入未变化：沿用断点中的进度（此处清空已保留的记录以便区分），不再读取已处理的文件
+        checkpoint.progress.records.clear();
+        let resumed = process_with_checkpoint(
+            &config,
+            &checkpoint_file,
+            Some(checkpoint.clone()),
+            &mut |_, _| {},
+        );
+        assert_eq!(resumed.unwrap().total_records, 0);
+
+        // 输入已变化：忽略断点，重新处理
+        checkpoint.input_fingerprint = "stale".to_string();
+        let reprocessed =
+            process_with_checkpoint(&config, &checkpoint_file, Some(checkpoint), &mut |_, _| {});
+        assert_eq!(reprocessed.unwrap().total_records, 6);
+
+        let output_files = resume_generation(&checkpoint_file, |_, _| {}).unwrap();
+        assert!(Path::new(&output_files[0]).is_file());
+        assert!(!Path::new(&checkpoint_file).exists());
+        assert!(resume_generation(&checkpoint_file, |_, _| {}).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_normalize_newlines() {
+        assert_eq!(normalize_newlines("a\r\nb"), "a\nb");
+        assert_eq!(normalize_newlines("a\rb"), "a\nb");
+        assert_eq!(normalize_newlines("a_x000D_b"), "a\nb");
+        assert_eq!(normalize_newlines("a_x000D_\nb"), "a\nb");
+        assert_eq!(normalize_newlines("a\nb"), "a\nb");
+        assert_eq!(
+            normalize_newlines("a\r\nb\rc_x000D_d\ne\r\n\r\nf"),
+            "a\nb\nc\nd\ne\n\nf"
+        );
+    }
+}
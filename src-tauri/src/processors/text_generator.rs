@@ -0,0 +1,277 @@
+use crate::models::{
+    ExcelProcessResult, Labels, Language, ReportConfig, StatisticItem, StatisticSummary,
+};
+use crate::processors::word_generator::{AppendixLine, ReportSection};
+use crate::processors::{normalize_newlines, WordGenerator};
+use anyhow::Result;
+
+pub struct TextGenerator;
+
+impl TextGenerator {
+    /// 纯文本渲染报告内容（标题、标签、字段值），字段顺序与Word报告一致，不生成文件；
+    /// 不含生成日期，便于快照比对
+    pub fn report_to_text(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+    ) -> Result<String> {
+        let result_data = WordGenerator::prepare_groups(config, result_data);
+        let no_findings = WordGenerator::no_findings(config, &result_data)?;
+
+        let mut text = format!("{}\n\n", config.title());
+
+        let labels = config.language.labels();
+        let metadata = [
+            (labels.identifier, config.identifier_tag.as_str()),
+            (labels.software_version, config.code_version.as_str()),
+            (labels.tester, config.ceshi_user.as_str()),
+            (labels.test_time, config.ceshi_time.as_str()),
+        ];
+        for (label, value) in metadata {
+            text.push_str(&format!("{}{}{}\n", label, labels.colon, value));
+        }
+        text.push('\n');
+
+        if no_findings {
+            // 没有问题时以说明代替统计表格
+            text.push_str(&format!("{}\n\n", labels.no_findings));
+        } else {
+            let statistics = WordGenerator::generate_statistics(&result_data, config)?;
+            let summary =
+                config.statistics_summary(result_data.total_records, result_data.total_groups)?;
+            text.push_str(&Self::statistics_table(
+                &statistics,
+                summary.as_deref(),
+                config.language,
+            ));
+        }
+
+        let report_numbers = config.report_numbers(&result_data.grouped_data)?;
+        for (title_num, ((group_key, group_info), report_number)) in
+            (1..).zip(result_data.grouped_data.iter().zip(report_numbers))
+        {
+            let section = WordGenerator::build_section(
+                config,
+                group_key,
+                group_info,
+                title_num,
+                report_number,
+            )?;
+            text.push_str(&Self::section(config, &section));
+        }
+
+        if let Some(appendix) = WordGenerator::appendix_lines(config) {
+            for line in appendix {
+                match line {
+                    AppendixLine::Heading(_, line) | AppendixLine::Text(line) => {
+                        text.push_str(&format!("{}\n", line))
+                    }
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// 统计表格：每行各列以制表符分隔，summary 为标题下方的汇总语句
+    fn statistics_table(
+        statistics: &[StatisticItem],
+        summary: Option<&str>,
+        language: Language,
+    ) -> String {
+        let labels = language.labels();
+        let mut table = format!("{}\n", labels.statistics_title);
+        if let Some(summary) = summary {
+            table.push_str(&format!("{}\n", summary));
+        }
+        table.push_str(&format!("{}\n", labels.statistics_columns.join("\t")));
+
+        for stat in statistics {
+            table.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{:.1}%\n",
+                stat.seq_num,
+                stat.report_number,
+                stat.problem_name,
+                stat.severity_level,
+                stat.problem_count,
+                stat.percentage
+            ));
+        }
+
+        let summary = StatisticSummary::from_items(statistics);
+        table.push_str(&format!(
+            "{}\t\t\t{}\t{}\t\n\n",
+            labels.total,
+            summary.severity_text(language),
+            summary.total_count
+        ));
+
+        table
+    }
+
+    /// 单个章节：标题及各字段，多行内容缩进两格
+    fn section(config: &ReportConfig, section: &ReportSection) -> String {
+        let labels = config.language.labels();
+        let mut text = format!("{}\n", section.title);
+
+        let item = |label: &str, value: &str| format!("{}{}{}\n", label, labels.colon, value);
+        text.push_str(&item(labels.report_number, &section.report_number));
+        text.push_str(&item(labels.software_version, &config.code_version));
+        text.push_str(&item(labels.tester, &config.ceshi_user));
+        text.push_str(&item(labels.test_time, &config.ceshi_time));
+
+        let mut description = format!(
+            "{}\n{}",
+            labels.line_label(labels.phenomenon),
+            section.phenomenon
+        );
+        if !section.code.is_empty() {
+            description.push_str(&format!("\n{}", section.code));
+        }
+        text.push_str(&Self::field(labels, labels.description, &description));
+
+        let mut risk_text = section.risk_level.text(config.language);
+        if let Some(raw) = &section.raw_severity {
+            risk_text.push_str(&format!("\n{}{}{}", labels.raw_severity, labels.colon, raw));
+        }
+        text.push_str(&Self::field(labels, labels.severity, &risk_text));
+        text.push_str(&Self::field(labels, labels.file_path, &section.code_path));
+        text.push_str(&Self::field(
+            labels,
+            labels.vulnerability,
+            &section.vulnerability,
+        ));
+        text.push_str(&Self::field(labels, labels.suggestion, &section.suggestion));
+        for (label, value) in &section.extra_fields {
+            text.push_str(&Self::field(labels, label, value));
+        }
+
+        if !section.record_details.is_empty() {
+            text.push_str(&format!("{}\n", labels.line_label(labels.record_details)));
+            for (i, detail) in section.record_details.iter().enumerate() {
+                text.push_str(&format!("  {}. {}\n", i + 1, detail.path));
+                text.push_str(&Self::indent(&detail.code, 4));
+            }
+        }
+
+        text.push('\n');
+        text
+    }
+
+    /// 标签行及缩进后的多行内容
+    fn field(labels: &Labels, label: &str, value: &str) -> String {
+        format!("{}\n{}", labels.line_label(label), Self::indent(value, 2))
+    }
+
+    /// 统一换行符后每行缩进 width 个空格，空内容返回空字符串
+    fn indent(value: &str, width: usize) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+
+        let padding = " ".repeat(width);
+        normalize_newlines(value)
+            .lines()
+            .map(|line| format!("{}{}\n", padding, line))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GroupInfo;
+    use crate::processors::test_support::record_of;
+
+    fn sample_result() -> ExcelProcessResult {
+        ExcelProcessResult::from_groups(vec![(
+            "SQL注入|高危".to_string(),
+            GroupInfo {
+                b_column: "SQL注入".to_string(),
+                d_column: "高危".to_string(),
+                record_count: 1,
+                records: vec![record_of(&[
+                    ("B", "SQL注入"),
+                    ("D", "高危"),
+                    ("I", "/src/db.c"),
+                    ("J", "query(sql);"),
+                    ("K", "拼接SQL语句"),
+                    ("N", "使用参数化查询\r\n校验输入"),
+                ])],
+                original_severity: None,
+            },
+        )])
+    }
+
+    #[test]
+    fn test_report_to_text() {
+        let result = sample_result();
+        let dir = std::env::temp_dir().join("report_forge_text_appendix_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let appendix = dir.join("appendix.md");
+        std::fs::write(&appendix, "# 术语表\r\n\r\nXSS：跨站脚本\n").unwrap();
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            appendix_file: Some(appendix.display().to_string()),
+            ..ReportConfig::default()
+        };
+
+        let text = TextGenerator::report_to_text(&config, &result).unwrap();
+
+        assert!(text.contains("问题标识：TAG\n"));
+        assert!(text.contains("问题统计表格\n共发现 1 条问题，归并为 1 类\n"));
+        assert!(text.contains("1\tTAG0001\tSQL注入\t高\t1\t100.0%\n"));
+        assert!(text.contains("1、SQL注入\n问题报告编号：TAG0001\n"));
+        assert!(text.contains("缺陷1相关代码如下：\n  query(sql);\n"));
+        assert!(
+            text.contains("漏洞说明：\n  拼接SQL语句\n整改建议：\n  使用参数化查询\n  校验输入\n")
+        );
+        assert!(text.ends_with("\n\n术语表\n\nXSS：跨站脚本\n"));
+        // 与渲染日期无关，可直接用于快照比对
+        assert_eq!(
+            text,
+            TextGenerator::report_to_text(&config, &result).unwrap()
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_report_to_text_in_english() {
+        let result = sample_result();
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            ceshi_user: "Alice".to_string(),
+            language: Language::En,
+            show_raw_severity: true,
+            ..ReportConfig::default()
+        };
+
+        let text = TextGenerator::report_to_text(&config, &result).unwrap();
+
+        assert!(text.contains("Identifier: TAG\nSoftware Version: \nTester: Alice\n"));
+        assert!(text.contains("Summary of Findings\n"));
+        assert!(text.contains("No.\tReport No.\tIssue\tSeverity\tCount\tPercentage\n"));
+        assert!(text.contains("1\tTAG0001\tSQL注入\tHigh\t1\t100.0%\n"));
+        assert!(text.contains("Total\t\t\tHigh 1 / Medium 0 / Low 0\t1\t\n"));
+        assert!(text.contains("1. SQL注入\nReport No.: TAG0001\n"));
+        assert!(text.contains("Description:\n  Defect:\n  SQL注入\n"));
+        assert!(text.contains(
+            "Severity:\n  ☑ High Risk  ☐ Medium Risk  ☐ Low Risk\n  Original Severity: 高危\n"
+        ));
+        assert!(text.contains("Recommendation:\n  使用参数化查询\n"));
+    }
+
+    #[test]
+    fn test_report_to_text_without_findings() {
+        let empty = ExcelProcessResult::from_groups(Vec::new());
+        let mut config = ReportConfig::default();
+
+        let text = TextGenerator::report_to_text(&config, &empty).unwrap();
+        assert!(text.contains("本次测试未发现问题\n"));
+        assert!(!text.contains("问题统计表格"));
+
+        config.no_findings_mode = crate::models::NoFindingsMode::Error;
+        assert!(TextGenerator::report_to_text(&config, &empty).is_err());
+    }
+}
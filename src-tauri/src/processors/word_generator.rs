@@ -1,423 +1,3575 @@
-use crate::models::{ExcelProcessResult, ReportConfig, RiskInfo, StatisticItem};
+use crate::error::ReportError;
+use crate::models::{
+    DocumentStyle, ExcelProcessResult, ExcelRecord, GroupCountChange, GroupInfo, Labels, Language,
+    NoFindingsMode, PathListStyle, ReportConfig, ReportEstimate, RepresentativeSelector,
+    ResultDiff, RiskLevel, SectionPreview, StatisticItem, StatisticSummary, STATISTICS_COLUMNS,
+};
+use crate::processors::{normalize_newlines, ExcelProcessor};
 use anyhow::{Context, Result};
 use docx_rs::*;
+use indexmap::IndexMap;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
 use std::path::Path;
 
 pub struct WordGenerator;
 
+/// 严重性级别单元格背景色（高危红、中危橙、低危绿，未知不着色）
+pub(crate) const RISK_LEVEL_FILLS: [(RiskLevel, &str); 3] = [
+    (RiskLevel::High, "F4B6B6"),
+    (RiskLevel::Medium, "FBD4A8"),
+    (RiskLevel::Low, "C6E0B4"),
+];
+
+/// 图片最大显示宽度（EMU，约12厘米）
+const MAX_IMAGE_WIDTH_EMU: u32 = 4_320_000;
+
+/// 每像素对应的EMU（按96 DPI计算）
+const EMU_PER_PIXEL: u32 = 9525;
+
+/// 代码块等宽字体（西文 / 中文）
+const CODE_ASCII_FONT: &str = "Consolas";
+const CODE_EAST_ASIA_FONT: &str = "等线";
+
+/// 代码块背景色（浅灰）
+const CODE_FILL: &str = "F5F5F5";
+
+/// 路径列表的列表定义编号（docx-rs 固定输出编号为1的默认列表，自定义列表从2开始）
+const NUMBERED_LIST_ABSTRACT_ID: usize = 2;
+const BULLETED_LIST_ABSTRACT_ID: usize = 3;
+const FIRST_LIST_NUMBERING_ID: usize = 2;
+
+/// 路径列表的缩进（悬挂缩进，单位：twip）
+const LIST_INDENT: i32 = 420;
+
+/// 水印文字高度（磅）、最大宽度（磅，约为A4页面正文宽度）、颜色及旋转角度（顺时针，315即左下到右上斜置）
+const WATERMARK_HEIGHT_PT: f32 = 72.0;
+const WATERMARK_MAX_WIDTH_PT: f32 = 415.0;
+const WATERMARK_COLOR: &str = "D9D9D9";
+const WATERMARK_ROTATION: u32 = 315;
+
+/// 统计表格默认列宽（twip）：序号窄，问题名称宽
+const DEFAULT_STATISTICS_COLUMN_WIDTHS: [usize; STATISTICS_COLUMNS.len()] =
+    [800, 2200, 2900, 1300, 1000, 1000];
+
+/// 章节表格列宽（twip）：标签-内容-标签-内容
+const SECTION_TABLE_GRID: [usize; 4] = [1800, 2800, 1800, 2800];
+
+/// 文末生成说明的颜色（灰色）
+const STAMP_COLOR: &str = "808080";
+
+/// 声明的标题样式级数（Heading1~Heading3），与目录收录的级别一致
+const MAX_HEADING_LEVEL: usize = 3;
+
+/// Word书签名称的最大长度
+const MAX_BOOKMARK_NAME_LEN: usize = 40;
+
+/// 文档内部链接的颜色（Word默认超链接蓝色）
+const LINK_COLOR: &str = "0563C1";
+
+/// 文档核心属性在包中的路径
+const CORE_PROPERTIES_PATH: &str = "docProps/core.xml";
+
+/// 规模预估：文档基础大小（封面、统计表格、样式等）及每个章节表格的大小（字节，压缩后）
+const ESTIMATE_BASE_BYTES: u64 = 12_000;
+const ESTIMATE_SECTION_BYTES: u64 = 2_500;
+
+/// 规模预估：每个字符及每行（单独成段）文本增加的大小（字节，压缩后）
+const ESTIMATE_BYTES_PER_CHAR: u64 = 1;
+const ESTIMATE_BYTES_PER_LINE: u64 = 60;
+
+/// 规模预估：每个章节的生成耗时及每MB文档的写入耗时（秒）
+const ESTIMATE_SECONDS_PER_SECTION: f64 = 0.02;
+const ESTIMATE_SECONDS_PER_MB: f64 = 0.5;
+
+/// 章节数或预计文件大小达到阈值时提示报告可能过大
+const LARGE_REPORT_SECTIONS: usize = 500;
+const LARGE_REPORT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 章节中嵌入的截图
+struct SectionImage {
+    data: Vec<u8>,
+    width_px: u32,
+    height_px: u32,
+}
+
+/// 缺陷明细表格中的一行（对应分组内的一条记录）
+pub(crate) struct RecordDetail {
+    pub(crate) path: String,
+    pub(crate) code: String,
+}
+
+/// 相关文件路径中的一行
+#[derive(Debug, PartialEq)]
+pub(crate) enum PathLine {
+    /// 标签行（如“缺陷1文件路径：”）或省略说明
+    Text(String),
+    /// 路径列表项及其在所属列表中的序号（每个列表从1开始）
+    Item(usize, String),
+}
+
+/// 一个章节渲染所需的内容（各输出格式共用）
+pub(crate) struct ReportSection {
+    pub(crate) report_number: String,
+    pub(crate) title: String,
+    pub(crate) risk_level: RiskLevel,
+    pub(crate) raw_severity: Option<String>, // 未启用 show_raw_severity 或原文为空时为 None
+    pub(crate) phenomenon: String,
+    pub(crate) code_path: String,
+    pub(crate) path_lines: Vec<PathLine>, // Word报告按 path_list_style 渲染为列表，其他格式使用 code_path
+    pub(crate) code: String,
+    pub(crate) vulnerability: String,
+    pub(crate) suggestion: String,
+    pub(crate) record_details: Vec<RecordDetail>, // 未启用缺陷明细表格时为空
+    pub(crate) extra_fields: Vec<(String, String)>, // 额外字段（标签、内容），来源列不存在时不包含
+}
+
+impl ReportSection {
+    /// 转换为前端预览结构
+    fn into_preview(self, language: Language) -> SectionPreview {
+        SectionPreview {
+            report_number: self.report_number,
+            title: self.title,
+            risk_text: self.risk_level.text(language),
+            phenomenon: self.phenomenon,
+            code_path: self.code_path,
+            code: self.code,
+            vulnerability: self.vulnerability,
+            suggestion: self.suggestion,
+            extra_fields: self.extra_fields,
+        }
+    }
+}
+
+/// 附录中的一行（各输出格式共用）
+#[derive(Debug, PartialEq)]
+pub(crate) enum AppendixLine {
+    /// Markdown标题行（# 开头），级别为 # 的个数（最多 MAX_HEADING_LEVEL 级）
+    Heading(usize, String),
+    /// 正文行，空行为空字符串
+    Text(String),
+}
+
+/// 页脚模板片段
+#[derive(Debug, PartialEq)]
+enum FooterPart {
+    Text(String),
+    Page,
+    TotalPages,
+}
+
 impl WordGenerator {
-    /// 生成完整报告
-    pub fn generate_report(
+    /// 生成完整报告，每写完一个章节调用 on_progress(已完成章节数, 章节总数)
+    pub fn generate_report<F>(
         config: &ReportConfig,
         result_data: &ExcelProcessResult,
-    ) -> Result<String> {
+        on_progress: F,
+    ) -> Result<String>
+    where
+        F: FnMut(usize, usize),
+    {
+        let doc = Self::build_report(config, result_data, on_progress)?;
+        Self::write_report(doc, config)
+    }
+
+    /// 构建完整的Word文档（不写入文件），每完成一个章节调用 on_progress
+    pub fn build_report<F>(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+        on_progress: F,
+    ) -> Result<Docx>
+    where
+        F: FnMut(usize, usize),
+    {
         log::info!("开始生成Word报告");
+        Self::build_document(config, result_data, None, on_progress)
+    }
+
+    /// 构建变化报告（不写入文件）：章节只包含 diff 中新增的分组，封面注明新增及已修复的问题数；
+    /// 设置 delta_include_resolved 时在统计表格之后列出已修复的分组
+    pub fn build_delta_report<F>(
+        config: &ReportConfig,
+        diff: &ResultDiff,
+        on_progress: F,
+    ) -> Result<Docx>
+    where
+        F: FnMut(usize, usize),
+    {
+        log::info!(
+            "开始生成变化报告：新增 {} 个分组，已修复 {} 个分组",
+            diff.added_groups.len(),
+            diff.removed_groups.len()
+        );
+
+        let added = ExcelProcessResult::from_groups(diff.added_groups.clone());
+        Self::build_document(config, &added, Some(diff), on_progress)
+    }
+
+    /// 构建Word文档，delta 为变化报告的对比结果（普通报告为 None）
+    fn build_document<F>(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+        delta: Option<&ResultDiff>,
+        mut on_progress: F,
+    ) -> Result<Docx>
+    where
+        F: FnMut(usize, usize),
+    {
+        // 处理未分类分组并按严重性过滤
+        let result_data = &Self::prepare_groups(config, result_data);
+
+        let no_findings = Self::no_findings(config, result_data)?;
 
         // 创建文档
-        let mut doc = Docx::new();
+        let style = &config.document_style;
+        let mut doc = Self::add_heading_styles(Docx::new(), style);
+        if config.path_list_style != PathListStyle::Plain {
+            doc = Self::add_list_numberings(doc);
+        }
+
+        // 生成封面
+        doc = Self::add_cover_page(doc, config, style, delta)?;
+
+        if no_findings {
+            // 没有问题时以说明代替统计表格和目录
+            doc = Self::add_no_findings_page(doc, config);
+        } else {
+            // 生成统计表格
+            let statistics = Self::generate_statistics(result_data, config)?;
+            let summary =
+                config.statistics_summary(result_data.total_records, result_data.total_groups)?;
+            let widths = Self::statistics_column_widths(config);
+            doc = Self::add_statistics_table(doc, &statistics, summary, widths, config)?;
+
+            // 生成目录
+            if config.include_toc {
+                doc = Self::add_table_of_contents(doc, config)?;
+            }
+        }
 
-        // 生成统计表格
-        let statistics = Self::generate_statistics(result_data);
-        doc = Self::add_statistics_table(doc, &statistics)?;
+        // 变化报告列出数量增加的问题，并按配置附带已修复问题（没有新增问题时也列出）
+        if let Some(diff) = delta {
+            doc = Self::add_increased_table(doc, &diff.count_changes, config);
+        }
+        if let Some(diff) = delta.filter(|_| config.delta_include_resolved) {
+            doc = Self::add_resolved_table(doc, &diff.removed_groups, config);
+        }
 
         // 为每个分组生成报告内容
-        let mut title_num = 1;
-        for (group_key, group_info) in &result_data.grouped_data {
-            // 生成报告编号
-            let report_number = format!(
-                "{}{}",
-                config.identifier_tag,
-                format!("{:04}", title_num + config.wt_add)
-            );
+        let report_numbers = config.report_numbers(&result_data.grouped_data)?;
+        for (((group_key, group_info), report_number), title_num) in
+            result_data.grouped_data.iter().zip(report_numbers).zip(1..)
+        {
+            let section =
+                Self::build_section(config, group_key, group_info, title_num, report_number)?;
 
-            let parts: Vec<&str> = group_key.split('|').collect();
-            let problem_name = parts.get(0).unwrap_or(&"");
-            let severity = parts.get(1).unwrap_or(&"");
-
-            let risk_info = RiskInfo::from_severity(severity);
-            let title = format!("{}、{}", title_num, problem_name);
-
-            // 生成相关代码文本
-            let code_text = Self::generate_code_text(&group_info.records);
-            let code_path_text = Self::generate_path_text(&group_info.records);
-
-            // 获取第一条记录的详细信息
-            let first_record = group_info.records.first();
-            let phenomenon = first_record
-                .and_then(|r| r.data.get("B"))
-                .and_then(|v| v.as_ref())
-                .map(|s| s.as_str())
-                .unwrap_or("");
-            let vulnerability = first_record
-                .and_then(|r| r.data.get("K"))
-                .and_then(|v| v.as_ref())
-                .map(|s| s.as_str())
-                .unwrap_or("");
-            let suggestion = first_record
-                .and_then(|r| r.data.get("N"))
-                .and_then(|v| v.as_ref())
-                .map(|s| s.as_str())
-                .unwrap_or("");
+            // 加载截图
+            let images = match &config.image_column {
+                Some(column) => Self::load_section_images(&group_info.records, column),
+                None => Vec::new(),
+            };
 
             // 添加报告内容
-            doc = Self::add_report_section(
-                doc,
-                &report_number,
-                &title,
-                &config.code_version,
-                &config.ceshi_user,
-                &config.ceshi_time,
-                &risk_info.text,
-                phenomenon,
-                &Self::clean_text(&code_path_text),
-                &Self::clean_text(&code_text),
-                vulnerability,
-                suggestion,
-            )?;
+            doc = Self::add_report_section(doc, config, &section, title_num as usize, &images)?;
+
+            // 章节之间插入分页符，最后一个章节后不插入以免产生空白页
+            if config.section_page_break && (title_num as usize) < result_data.grouped_data.len() {
+                doc = doc
+                    .add_paragraph(Paragraph::new().add_run(Run::new().add_break(BreakType::Page)));
+            }
 
             log::info!(
                 "已处理第 {}/{} 条记录",
                 title_num,
                 result_data.total_groups
             );
-            title_num += 1;
+            on_progress(title_num as usize, result_data.grouped_data.len());
         }
 
-        // 生成输出文件路径
-        let timestamp = chrono::Local::now().timestamp();
-        let output_file = format!(
-            "{}/{}_{}_{}.docx",
-            config.output_dir,
-            config.identifier_tag,
-            config.code_version,
-            timestamp
-        );
+        // 附录（术语表、测试方法等固定内容）
+        if let Some(appendix) = Self::appendix_lines(config) {
+            if !no_findings {
+                doc = doc
+                    .add_paragraph(Paragraph::new().add_run(Run::new().add_break(BreakType::Page)));
+            }
+            doc = Self::add_appendix(doc, &appendix, style);
+        }
 
-        // 保存文档
-        let path = Path::new(&output_file);
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("无法创建输出目录: {:?}", parent))?;
+        // 文末注明生成工具版本及时间
+        if let Some(stamp) = config.generation_stamp_text() {
+            doc = doc.add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(stamp)
+                            .size(style.body_size)
+                            .color(STAMP_COLOR)
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Right),
+            );
         }
 
-        let file = std::fs::File::create(&output_file)
-            .with_context(|| format!("无法创建输出文件: {}", output_file))?;
+        // 添加页脚页码
+        doc = doc.footer(Self::create_footer(config.footer(), style));
 
-        doc.build()
-            .pack(file)
-            .with_context(|| "无法写入Word文档")?;
+        // 添加水印（页眉中衬于文字下方的斜置浅灰色文字，每页显示）
+        if let Some(header) = Self::create_watermark_header(config.watermark.as_deref()) {
+            doc = doc.header(header);
+        }
+
+        Ok(doc)
+    }
+
+    /// 将文档写入输出目录，返回输出文件路径
+    pub fn write_report(doc: Docx, config: &ReportConfig) -> Result<String> {
+        // 生成输出文件路径（毫秒时间戳，同名文件已存在时追加序号）
+        let timestamp = chrono::Local::now().timestamp_millis();
+        let file_stem = config.output_file_stem(timestamp)?;
+
+        // 保存文档
+        let packed = Self::pack_to_bytes(doc, config)?;
+        let output_file = Self::write_output_file(&config.output_dir, &file_stem, "docx", &packed)?;
 
         log::info!("报告生成完成！文件: {}", output_file);
         Ok(output_file)
     }
 
-    /// 生成统计信息
-    fn generate_statistics(result_data: &ExcelProcessResult) -> Vec<StatisticItem> {
-        let mut statistics = Vec::new();
-        let mut seq_num = 1;
-
-        for (_, group_info) in &result_data.grouped_data {
-            let severity = if group_info.d_column.contains("高危") || group_info.d_column.contains("高") {
-                "高"
-            } else if group_info.d_column.contains("中危") || group_info.d_column.contains("中") {
-                "中"
-            } else if group_info.d_column.contains("低危") || group_info.d_column.contains("低") {
-                "低"
-            } else {
-                "未知"
-            };
-
-            statistics.push(StatisticItem {
-                seq_num,
-                problem_name: group_info.b_column.clone(),
-                severity_level: severity.to_string(),
-                problem_count: group_info.record_count,
-            });
+    /// 将文档打包为docx文件内容（不写入文件），供直接返回给调用方
+    pub fn pack_to_bytes(doc: Docx, config: &ReportConfig) -> Result<Vec<u8>> {
+        // 写入文档属性（标题、作者、主题、关键字、创建时间）
+        let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let mut packed = std::io::Cursor::new(Vec::new());
+        doc.created_at(&now)
+            .updated_at(&now)
+            .build()
+            .pack(&mut packed)
+            .with_context(|| "无法写入Word文档")?;
+        Self::rewrite_package(packed.into_inner(), config, &now)
+    }
 
-            seq_num += 1;
-        }
+    /// 预览各章节内容，不写入文件
+    pub fn preview_sections(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+    ) -> Result<Vec<SectionPreview>> {
+        let result_data = Self::prepare_groups(config, result_data);
+        let report_numbers = config.report_numbers(&result_data.grouped_data)?;
 
-        statistics
+        result_data
+            .grouped_data
+            .iter()
+            .zip(report_numbers)
+            .zip(1..)
+            .map(|(((group_key, group_info), report_number), title_num)| {
+                Self::build_section(config, group_key, group_info, title_num, report_number)
+                    .map(|section| section.into_preview(config.language))
+            })
+            .collect()
     }
 
-    /// 添加统计表格到文档
-    fn add_statistics_table(mut doc: Docx, statistics: &[StatisticItem]) -> Result<Docx> {
-        // 添加标题
-        doc = doc.add_paragraph(
-            Paragraph::new()
-                .add_run(
-                    Run::new()
-                        .add_text("问题统计表格")
-                        .size(32) // 小四 = 24, 这里用32表示16磅
-                        .bold()
-                        .fonts(RunFonts::new().east_asia("宋体").ascii("Times New Roman")),
-                )
-                .align(AlignmentType::Center),
-        );
+    /// 按处理结果预估Word报告的规模（章节数、代码及路径字符数、文件大小、生成耗时），不构建文档
+    pub fn estimate_report(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+    ) -> Result<ReportEstimate> {
+        let result_data = Self::prepare_groups(config, result_data);
+        let report_numbers = config.report_numbers(&result_data.grouped_data)?;
 
-        // 创建表头行 - 带样式
-        let header_cells = vec![
-            Self::create_header_cell("序号"),
-            Self::create_header_cell("问题名称"),
-            Self::create_header_cell("严重性级别"),
-            Self::create_header_cell("问题个数"),
-        ];
+        let mut code_chars = 0;
+        let mut path_chars = 0;
+        let mut text_chars = 0;
+        let mut text_lines = 0;
+        let mut image_bytes = 0;
+        for (((group_key, group_info), report_number), title_num) in
+            result_data.grouped_data.iter().zip(report_numbers).zip(1..)
+        {
+            let section =
+                Self::build_section(config, group_key, group_info, title_num, report_number)?;
+            code_chars += section.code.chars().count();
+            path_chars += section.code_path.chars().count();
 
-        // 创建表格，设置边框
-        let mut table = Table::new(vec![TableRow::new(header_cells)])
-            .set_grid(vec![1200, 4500, 1800, 1500]) // 调整列宽：序号窄，问题名称宽
-            .align(TableAlignmentType::Center);
+            let details = section
+                .record_details
+                .iter()
+                .flat_map(|detail| [&detail.path, &detail.code]);
+            for text in [
+                &section.phenomenon,
+                &section.code,
+                &section.code_path,
+                &section.vulnerability,
+                &section.suggestion,
+            ]
+            .into_iter()
+            .chain(details)
+            {
+                text_chars += text.chars().count();
+                text_lines += text.lines().count();
+            }
 
-        // 添加数据行
-        for stat in statistics {
-            let row = TableRow::new(vec![
-                Self::create_data_cell(&stat.seq_num.to_string()),
-                Self::create_data_cell(&stat.problem_name),
-                Self::create_data_cell(&stat.severity_level),
-                Self::create_data_cell(&stat.problem_count.to_string()),
-            ]);
-            table = table.add_row(row);
+            if let Some(column) = &config.image_column {
+                image_bytes += Self::image_file_bytes(&group_info.records, column);
+            }
         }
 
-        doc = doc.add_table(table);
-        doc = doc.add_paragraph(Paragraph::new()); // 空行
+        let section_count = result_data.grouped_data.len();
+        let estimated_bytes = ESTIMATE_BASE_BYTES
+            + section_count as u64 * ESTIMATE_SECTION_BYTES
+            + text_chars as u64 * ESTIMATE_BYTES_PER_CHAR
+            + text_lines as u64 * ESTIMATE_BYTES_PER_LINE
+            + image_bytes;
+        let estimated_mb = estimated_bytes as f64 / (1024.0 * 1024.0);
+        let estimated_seconds = section_count as f64 * ESTIMATE_SECONDS_PER_SECTION
+            + estimated_mb * ESTIMATE_SECONDS_PER_MB;
 
-        Ok(doc)
-    }
+        let warning = (section_count >= LARGE_REPORT_SECTIONS
+            || estimated_bytes >= LARGE_REPORT_BYTES)
+            .then(|| {
+                format!(
+                    "报告包含 {} 个章节，预计约 {:.1} MB，生成可能需要较长时间",
+                    section_count, estimated_mb
+                )
+            });
 
-    /// 创建表头单元格 - 小四字体，宋体，加粗，居中
-    fn create_header_cell(text: &str) -> TableCell {
-        TableCell::new()
-            .add_paragraph(
-                Paragraph::new()
-                    .add_run(
-                        Run::new()
-                            .add_text(text)
-                            .size(24) // 小四 = 12磅 = 24半磅
-                            .bold()
-                            .fonts(RunFonts::new().east_asia("宋体").ascii("Times New Roman")),
-                    )
-                    .align(AlignmentType::Center),
-            )
-            .vertical_align(VAlignType::Center)
-            .shading(Shading::new().fill("D9E2F3")) // 浅蓝色背景
+        Ok(ReportEstimate {
+            section_count,
+            total_records: result_data.total_records,
+            code_chars,
+            path_chars,
+            image_bytes,
+            estimated_bytes,
+            estimated_seconds,
+            warning,
+        })
     }
 
-    /// 创建数据单元格 - 小四字体，宋体，居中
-    fn create_data_cell(text: &str) -> TableCell {
-        TableCell::new()
-            .add_paragraph(
-                Paragraph::new()
-                    .add_run(
-                        Run::new()
-                            .add_text(text)
-                            .size(24) // 小四 = 12磅 = 24半磅
-                            .fonts(RunFonts::new().east_asia("宋体").ascii("Times New Roman")),
-                    )
-                    .align(AlignmentType::Center),
-            )
-            .vertical_align(VAlignType::Center)
+    /// 处理问题名称为空的分组、按严重性过滤并排除记录数低于 min_record_count 的分组，
+    /// 统计表格与章节使用同一结果（严重性级别已在处理Excel时按配置识别）
+    pub(crate) fn prepare_groups(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+    ) -> ExcelProcessResult {
+        let result = Self::select_groups(config, result_data);
+
+        // 按识别后的分组计数，排除记录数不足的分组
+        if config.min_record_count > 1 {
+            ExcelProcessor::filter_by_min_records(&result, config.min_record_count)
+        } else {
+            result
+        }
     }
 
-    /// 添加报告章节 - 使用指定的表格格式
-    #[allow(clippy::too_many_arguments)]
-    fn add_report_section(
-        mut doc: Docx,
-        report_number: &str,
-        title: &str,
-        code_version: &str,
-        ceshi_user: &str,
-        ceshi_time: &str,
-        risk_text: &str,
-        phenomenon: &str,
-        code_path: &str,
-        code: &str,
-        vulnerability: &str,
-        suggestion: &str,
-    ) -> Result<Docx> {
-        // 添加标题
-        doc = doc.add_paragraph(
-            Paragraph::new()
-                .add_run(
-                    Run::new()
-                        .add_text(title)
-                        .size(28) // 标题字号稍大
-                        .bold()
-                        .fonts(RunFonts::new().east_asia("宋体").ascii("Times New Roman")),
-                )
-                .style("Heading3"),
+    /// prepare_groups 中排除记录数不足的分组之前的步骤：处理问题名称为空的分组并按严重性过滤
+    pub(crate) fn select_groups(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+    ) -> ExcelProcessResult {
+        let result = ExcelProcessor::handle_uncategorized(
+            result_data,
+            config.drop_uncategorized,
+            config.uncategorized(),
         );
 
-        // 创建报告信息表格 (8行4列)
-        let mut table = Table::new(vec![
-            // 第1行：问题报告编号 | [编号] | 软件版本 | [版本]
-            TableRow::new(vec![
-                Self::create_label_cell("问题报告编号"),
-                Self::create_content_cell(report_number),
-                Self::create_label_cell("软件版本"),
-                Self::create_content_cell(code_version),
-            ]),
-            // 第2行：测试人 | [测试人] | 测试时间 | [时间]
-            TableRow::new(vec![
-                Self::create_label_cell("测试人"),
-                Self::create_content_cell(ceshi_user),
-                Self::create_label_cell("测试时间"),
-                Self::create_content_cell(ceshi_time),
-            ]),
-            // 第3行：问题描述 (跨3列)
-            TableRow::new(vec![
-                Self::create_label_cell("问题描述"),
-                Self::create_multiline_cell(&format!(
-                    "缺陷描述：\n{}\n\n{}",
-                    phenomenon, code
-                ))
-                .grid_span(3),
-            ]),
-            // 第4行：问题严重性级别 (跨3列)
-            TableRow::new(vec![
-                Self::create_label_cell("问题严重性级别"),
-                Self::create_content_cell(risk_text).grid_span(3),
-            ]),
-            // 第5行：相关文件路径 (跨3列)
-            TableRow::new(vec![
-                Self::create_label_cell("相关文件路径"),
-                Self::create_multiline_cell(code_path).grid_span(3),
-            ]),
-            // 第6行：漏洞说明 (跨3列)
-            TableRow::new(vec![
-                Self::create_label_cell("漏洞说明"),
-                Self::create_multiline_cell(vulnerability).grid_span(3),
-            ]),
-            // 第7行：整改建议 (跨3列)
-            TableRow::new(vec![
-                Self::create_label_cell("整改建议"),
-                Self::create_multiline_cell(suggestion).grid_span(3),
-            ]),
-        ]);
+        match &config.severity_filter {
+            Some(levels) => ExcelProcessor::filter_by_severity(&result, levels),
+            None => result,
+        }
+    }
 
-        // 设置表格样式和列宽
-        table = table
-            .set_grid(vec![1800, 2800, 1800, 2800]) // 4列：标签-内容-标签-内容
-            .align(TableAlignmentType::Center);
+    /// 提取一个分组对应章节的内容（编号、标题、风险等级及各字段文本），
+    /// 问题报告编号由 ReportConfig::report_numbers 统一生成
+    pub(crate) fn build_section(
+        config: &ReportConfig,
+        group_key: &str,
+        group_info: &GroupInfo,
+        title_num: i32,
+        report_number: String,
+    ) -> Result<ReportSection> {
+        let parts: Vec<&str> = group_key.split('|').collect();
+        let problem_name = parts.first().unwrap_or(&"");
 
-        doc = doc.add_table(table);
+        // 不合并字段时只取代表记录的取值
+        let field_records = match Self::representative_record(&group_info.records, config) {
+            Some(record) if !config.merge_record_fields => std::slice::from_ref(record),
+            _ => group_info.records.as_slice(),
+        };
+        let placeholder = config.empty_field_placeholder.as_deref();
+        let field_value = |column: &str| {
+            Self::or_placeholder(
+                Self::record_field_text(field_records, column, config.merge_record_fields),
+                placeholder,
+            )
+        };
 
-        // 添加空行作为分隔
-        doc = doc.add_paragraph(Paragraph::new());
+        let columns = &config.column_mapping;
+        let (path_lines, code) = if config.collapse_identical_code {
+            Self::generate_collapsed_code_texts(&group_info.records, config)?
+        } else {
+            (
+                Self::generate_path_lines(&group_info.records, config)?,
+                Self::generate_code_text(
+                    &group_info.records,
+                    &columns.code_column,
+                    config.max_snippets_per_group,
+                    placeholder,
+                    config.language.labels(),
+                ),
+            )
+        };
+        let code_path = Self::path_lines_text(&path_lines, config.path_list_style);
 
-        Ok(doc)
+        Ok(ReportSection {
+            report_number,
+            title: config.section_title(
+                title_num,
+                problem_name,
+                &group_info.d_column,
+                group_info.record_count,
+            )?,
+            risk_level: RiskLevel::from_severity(&group_info.d_column),
+            raw_severity: if config.show_raw_severity {
+                Self::raw_severity(group_info)
+            } else {
+                None
+            },
+            phenomenon: field_value("B"),
+            code_path: Self::or_placeholder(Self::clean_text(&code_path), placeholder),
+            path_lines,
+            code: Self::or_placeholder(Self::clean_text(&code), placeholder),
+            vulnerability: field_value("K"),
+            suggestion: field_value("N"),
+            record_details: if config.record_details_table {
+                Self::record_details(&group_info.records, config)
+            } else {
+                Vec::new()
+            },
+            extra_fields: config
+                .extra_fields
+                .iter()
+                .filter(|(_, column)| {
+                    group_info
+                        .records
+                        .iter()
+                        .any(|record| record.data.contains_key(column))
+                })
+                .map(|(label, column)| (label.clone(), field_value(column)))
+                .collect(),
+        })
     }
 
-    /// 创建标签单元格 - 浅灰背景，加粗，居中
-    fn create_label_cell(text: &str) -> TableCell {
-        TableCell::new()
-            .add_paragraph(
-                Paragraph::new()
-                    .add_run(
-                        Run::new()
-                            .add_text(text)
-                            .size(24) // 小四
-                            .bold()
-                            .fonts(RunFonts::new().east_asia("宋体").ascii("Times New Roman")),
-                    )
-                    .align(AlignmentType::Center),
-            )
-            .vertical_align(VAlignType::Center)
-            .shading(Shading::new().fill("F2F2F2")) // 浅灰色背景
+    /// 字段为空或仅含空白时替换为占位文本（未配置占位文本时原样返回）
+    fn or_placeholder(value: String, placeholder: Option<&str>) -> String {
+        match placeholder {
+            Some(placeholder) if value.trim().is_empty() => placeholder.to_string(),
+            _ => value,
+        }
     }
 
-    /// 创建内容单元格 - 普通文本，左对齐，垂直居中
-    fn create_content_cell(text: &str) -> TableCell {
-        TableCell::new()
-            .add_paragraph(
-                Paragraph::new()
-                    .add_run(
-                        Run::new()
-                            .add_text(text)
-                            .size(24) // 小四
-                            .fonts(RunFonts::new().east_asia("宋体").ascii("Times New Roman")),
-                    )
-                    .align(AlignmentType::Left),
-            )
-            .vertical_align(VAlignType::Center)
-    }
+    /// Excel中的原始严重性级别文本（按配置识别及风险升级前的值），分组内的不同取值以 " / " 连接；
+    /// 记录中没有严重性级别列时取风险升级前的级别
+    fn raw_severity(group_info: &GroupInfo) -> Option<String> {
+        let mut values: Vec<&str> = Vec::new();
+        for record in &group_info.records {
+            let value = record
+                .data
+                .get("D")
+                .and_then(|v| v.as_deref())
+                .map(str::trim);
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+        }
+        if !values.is_empty() {
+            return Some(values.join(" / "));
+        }
 
-    /// 创建多行内容单元格 - 支持换行，左对齐，顶部对齐
-    fn create_multiline_cell(text: &str) -> TableCell {
-        let mut cell = TableCell::new();
+        let raw = group_info
+            .original_severity
+            .as_deref()
+            .unwrap_or(&group_info.d_column)
+            .trim();
+        (!raw.is_empty()).then(|| raw.to_string())
+    }
 
-        // 处理换行符：Excel中的换行可能是\n, \r\n, 或 _x000D_
-        let cleaned_text = text
-            .replace("_x000D_", "\n")  // Excel特殊换行符
-            .replace("\r\n", "\n")      // Windows换行符
-            .replace('\r', "\n");       // Mac换行符
+    /// 逐条提取记录的文件路径和相关代码，用于缺陷明细表格
+    fn record_details(records: &[ExcelRecord], config: &ReportConfig) -> Vec<RecordDetail> {
+        let value = |record: &ExcelRecord, column: &str| {
+            record
+                .data
+                .get(column)
+                .and_then(|v| v.as_deref())
+                .unwrap_or("")
+                .to_string()
+        };
 
-        // 按行分割文本
-        let lines: Vec<&str> = cleaned_text.split('\n').collect();
+        records
+            .iter()
+            .map(|record| {
+                let path = value(record, &config.column_mapping.path_column);
+                let path = match config.strip_path_prefix.as_deref() {
+                    Some(prefix) => Self::strip_path_prefix(&path, prefix),
+                    None => &path,
+                };
+                RecordDetail {
+                    path: Self::clean_text(path),
+                    code: Self::clean_text(&value(record, &config.column_mapping.code_column)),
+                }
+            })
+            .collect()
+    }
 
-        for (i, line) in lines.iter().enumerate() {
-            // 跳过空行，但保留一些间距
-            if line.trim().is_empty() && i > 0 {
-                cell = cell.add_paragraph(Paragraph::new().add_run(Run::new().add_text(" ")));
-                continue;
+    /// 按 representative_selector 选出分组的代表记录，比较结果相同时取最先出现的记录
+    fn representative_record<'a>(
+        records: &'a [ExcelRecord],
+        config: &ReportConfig,
+    ) -> Option<&'a ExcelRecord> {
+        // 只有严格大于时才替换，相同时保留先出现的记录
+        records.iter().reduce(|best, record| {
+            if Self::compare_representatives(record, best, config) == Ordering::Greater {
+                record
+            } else {
+                best
             }
+        })
+    }
 
-            let mut para = Paragraph::new()
-                .add_run(
-                    Run::new()
-                        .add_text(*line) // 解引用 &&str 为 &str
-                        .size(24) // 小四
-                        .fonts(RunFonts::new().east_asia("宋体").ascii("Times New Roman")),
-                )
-                .align(AlignmentType::Left);
-
-            // 为段落间添加适当间距
-            if i > 0 {
-                para = para.line_spacing(LineSpacing::new().before(80).after(0));
+    /// 按 representative_selector 比较两条记录，Greater 表示 a 更适合作为代表记录
+    fn compare_representatives(
+        a: &ExcelRecord,
+        b: &ExcelRecord,
+        config: &ReportConfig,
+    ) -> Ordering {
+        match &config.representative_selector {
+            RepresentativeSelector::FirstSeen => Ordering::Equal,
+            RepresentativeSelector::LongestDescription => {
+                let length =
+                    |record: &ExcelRecord| Self::cell_text(record, "K").trim().chars().count();
+                length(a).cmp(&length(b))
+            }
+            RepresentativeSelector::HighestSeverity => {
+                let (a, b) = (Self::cell_text(a, "D"), Self::cell_text(b, "D"));
+                // 优先级数值越小等级越高
+                let priority = |severity: &str| config.risk_level(severity).priority();
+                priority(b)
+                    .cmp(&priority(a))
+                    .then_with(|| Self::value_order(a, b))
+            }
+            RepresentativeSelector::MaxColumn(column) => {
+                Self::value_order(Self::cell_text(a, column), Self::cell_text(b, column))
             }
-
-            cell = cell.add_paragraph(para);
         }
-
-        cell.vertical_align(VAlignType::Top)
     }
 
-    /// 生成相关代码文本
-    fn generate_code_text(records: &[crate::models::ExcelRecord]) -> String {
-        let mut code_text = String::new();
-        for (i, record) in records.iter().enumerate() {
-            let code = record
-                .data
-                .get("J")
-                .and_then(|v| v.as_ref())
-                .map(|s| s.as_str())
-                .unwrap_or("");
-            code_text.push_str(&format!("缺陷{}相关代码如下：\r{}\r\n", i + 1, code));
+    /// 代表记录取值的比较顺序（全序）：数字高于文本，数字之间按数值比较，文本之间按文本比较
+    fn value_order(a: &str, b: &str) -> Ordering {
+        let number = |value: &str| value.parse::<f64>().ok().filter(|n| !n.is_nan());
+        let (a, b) = (a.trim(), b.trim());
+        match (number(a), number(b)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => a.cmp(b),
         }
-        code_text.trim().to_string()
     }
 
-    /// 生成文件路径文本
-    fn generate_path_text(records: &[crate::models::ExcelRecord]) -> String {
-        let mut path_text = String::new();
-        for (i, record) in records.iter().enumerate() {
-            let path = record
-                .data
-                .get("I")
-                .and_then(|v| v.as_ref())
-                .map(|s| s.as_str())
-                .unwrap_or("")
-                .trim_start_matches("root");
-            path_text.push_str(&format!("缺陷{}文件路径：\r{}\r\n", i + 1, path));
+    /// 获取分组的字段文本：默认取第一条记录，合并时按出现顺序拼接不重复的取值
+    fn record_field_text(records: &[ExcelRecord], column: &str, merge: bool) -> String {
+        if !merge {
+            return records
+                .first()
+                .and_then(|r| r.data.get(column))
+                .and_then(|v| v.clone())
+                .unwrap_or_default();
+        }
+
+        let mut distinct: Vec<&str> = Vec::new();
+        for value in records
+            .iter()
+            .filter_map(|r| r.data.get(column).and_then(|v| v.as_deref()))
+        {
+            if !distinct.contains(&value) {
+                distinct.push(value);
+            }
+        }
+        distinct.join("\n")
+    }
+
+    /// 先写入同目录下的临时文件，写入成功后再重命名为不覆盖已有文件的输出文件，
+    /// 失败时不留下不完整的报告
+    pub(crate) fn write_output_file(
+        output_dir: &str,
+        file_stem: &str,
+        extension: &str,
+        contents: &[u8],
+    ) -> Result<String> {
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| ReportError::write_failed(output_dir, e))?;
+
+        let (temp_file, mut file) =
+            Self::create_output_file(output_dir, &format!(".{}", file_stem), "tmp")?;
+        if let Err(e) = file.write_all(contents).and_then(|_| file.sync_all()) {
+            drop(file);
+            let _ = std::fs::remove_file(&temp_file);
+            return Err(ReportError::write_failed(&temp_file, e).into());
+        }
+        drop(file);
+
+        // 先占用目标文件名，再用临时文件替换它
+        let output_file = match Self::create_output_file(output_dir, file_stem, extension) {
+            Ok((output_file, _)) => output_file,
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp_file);
+                return Err(e);
+            }
+        };
+        if let Err(e) = std::fs::rename(&temp_file, &output_file) {
+            let _ = std::fs::remove_file(&temp_file);
+            let _ = std::fs::remove_file(&output_file);
+            return Err(ReportError::write_failed(&output_file, e).into());
+        }
+
+        Ok(output_file)
+    }
+
+    /// 创建不覆盖已有文件的输出文件，重名时依次尝试 `_1`、`_2` 等后缀
+    pub(crate) fn create_output_file(
+        output_dir: &str,
+        file_stem: &str,
+        extension: &str,
+    ) -> Result<(String, std::fs::File)> {
+        let mut counter = 0;
+        loop {
+            let file_name = if counter == 0 {
+                format!("{}.{}", file_stem, extension)
+            } else {
+                format!("{}_{}.{}", file_stem, counter, extension)
+            };
+            let output_file = Path::new(output_dir).join(file_name).display().to_string();
+
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&output_file)
+            {
+                Ok(file) => return Ok((output_file, file)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => counter += 1,
+                Err(e) => return Err(ReportError::write_failed(&output_file, e).into()),
+            }
+        }
+    }
+
+    /// 改写打包后的文档包：替换 docProps/core.xml，写入标题、作者、主题和关键字；
+    /// 设置了水印时在页眉中插入水印形状
+    ///
+    /// docx-rs 只能设置创建/修改时间，也不支持VML形状，这些内容在打包后直接改写。
+    fn rewrite_package(docx: Vec<u8>, config: &ReportConfig, created: &str) -> Result<Vec<u8>> {
+        let mut archive =
+            zip::ZipArchive::new(std::io::Cursor::new(docx)).context("无法读取Word文档包")?;
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let watermark = config
+            .watermark
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(|text| Self::watermark_shape(text, &config.document_style));
+
+        for index in 0..archive.len() {
+            let entry = archive.by_index_raw(index)?;
+            let name = entry.name().to_string();
+            if name == CORE_PROPERTIES_PATH {
+                continue;
+            }
+            match &watermark {
+                Some(shape) if name.starts_with("word/header") && name.ends_with(".xml") => {
+                    drop(entry);
+                    let mut header_xml = String::new();
+                    archive.by_index(index)?.read_to_string(&mut header_xml)?;
+                    writer.start_file(name, zip::write::SimpleFileOptions::default())?;
+                    writer.write_all(Self::insert_watermark(&header_xml, shape).as_bytes())?;
+                }
+                _ => writer.raw_copy_file(entry)?,
+            }
+        }
+
+        let subject = config.language.labels().report_title;
+        let keywords = format!(
+            "{},{},{}",
+            subject, config.identifier_tag, config.code_version
+        );
+        let core_xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:dcmitype="http://purl.org/dc/dcmitype/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>{}</dc:title><dc:subject>{}</dc:subject><dc:creator>{}</dc:creator><cp:keywords>{}</cp:keywords><cp:lastModifiedBy>{}</cp:lastModifiedBy><cp:revision>1</cp:revision><dcterms:created xsi:type="dcterms:W3CDTF">{}</dcterms:created><dcterms:modified xsi:type="dcterms:W3CDTF">{}</dcterms:modified></cp:coreProperties>"#,
+            Self::xml_escape(config.title()),
+            Self::xml_escape(subject),
+            Self::xml_escape(&config.ceshi_user),
+            Self::xml_escape(&keywords),
+            Self::xml_escape(&config.ceshi_user),
+            Self::xml_escape(created),
+            Self::xml_escape(created)
+        );
+        writer.start_file(
+            CORE_PROPERTIES_PATH,
+            zip::write::SimpleFileOptions::default(),
+        )?;
+        writer.write_all(core_xml.as_bytes())?;
+
+        Ok(writer.finish()?.into_inner())
+    }
+
+    /// XML文本转义，可用于元素内容和属性值；XML 1.0 不允许的字符（制表符、换行以外的控制字符，
+    /// U+FFFE、U+FFFF）直接去掉，否则 Word 无法打开文档
+    fn xml_escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                '\t' | '\n' | '\r' => escaped.push(c),
+                '\u{0}'..='\u{1F}' | '\u{FFFE}' | '\u{FFFF}' => {}
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    /// 生成统计信息，报告编号与章节编号使用同一模板
+    pub(crate) fn generate_statistics(
+        result_data: &ExcelProcessResult,
+        config: &ReportConfig,
+    ) -> Result<Vec<StatisticItem>> {
+        let mut statistics = Vec::new();
+        let report_numbers = config.report_numbers(&result_data.grouped_data)?;
+
+        for (((_, group_info), report_number), seq_num) in
+            result_data.grouped_data.iter().zip(report_numbers).zip(1..)
+        {
+            let risk_level = RiskLevel::from_severity(&group_info.d_column);
+
+            statistics.push(StatisticItem {
+                seq_num,
+                report_number,
+                problem_name: group_info.b_column.clone(),
+                severity_level: config.language.severity_label(&risk_level).to_string(),
+                risk_level,
+                problem_count: group_info.record_count,
+                percentage: Self::percentage(group_info.record_count, result_data.total_records),
+            });
+        }
+
+        Ok(statistics)
+    }
+
+    /// 导出统计信息为CSV（UTF-8带BOM，便于Excel正确显示中文），列与报告中的统计表格相同
+    /// （表头随 config.language，报告编号按 config 生成），返回写入路径
+    ///
+    /// 与生成报告相同，先写入临时文件再重命名，不覆盖已有文件（重名时加 `_1` 等后缀）。
+    pub fn export_statistics_csv(
+        result_data: &ExcelProcessResult,
+        config: &ReportConfig,
+        output_path: &str,
+    ) -> Result<String> {
+        let statistics = Self::generate_statistics(result_data, config)?;
+        let labels = config.language.labels();
+
+        let header: Vec<String> = labels
+            .statistics_columns
+            .iter()
+            .map(|column| Self::csv_field(column))
+            .collect();
+        let mut content = format!("\u{FEFF}{}\r\n", header.join(","));
+        for stat in &statistics {
+            let fields = [
+                stat.seq_num.to_string(),
+                stat.report_number.clone(),
+                stat.problem_name.clone(),
+                stat.severity_level.clone(),
+                stat.problem_count.to_string(),
+                format!("{:.1}%", stat.percentage),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| Self::csv_field(f)).collect();
+            content.push_str(&row.join(","));
+            content.push_str("\r\n");
+        }
+
+        let path = Path::new(output_path);
+        let output_dir = path.parent().map(Path::to_string_lossy).unwrap_or_default();
+        let file_stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy())
+            .unwrap_or_default();
+        let extension = path
+            .extension()
+            .map_or("csv".into(), |e| e.to_string_lossy());
+        let output_file =
+            Self::write_output_file(&output_dir, &file_stem, &extension, content.as_bytes())?;
+
+        log::info!("统计CSV导出完成！文件: {}", output_file);
+        Ok(output_file)
+    }
+
+    /// CSV字段转义：包含逗号、引号或换行时加引号，内部引号加倍
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// 计算百分比并保留一位小数，总数为0时返回0
+    fn percentage(count: usize, total: usize) -> f32 {
+        if total == 0 {
+            return 0.0;
+        }
+        (count as f64 * 1000.0 / total as f64).round() as f32 / 10.0
+    }
+
+    /// 添加封面 - 报告标题及元信息，居中显示，之后分页；变化报告在标题下方注明并列出新增及已修复的问题数
+    fn add_cover_page(
+        mut doc: Docx,
+        config: &ReportConfig,
+        style: &DocumentStyle,
+        delta: Option<&ResultDiff>,
+    ) -> Result<Docx> {
+        let labels = config.language.labels();
+
+        // 标题前留白，使标题位于页面中上部
+        for _ in 0..8 {
+            doc = doc.add_paragraph(Paragraph::new());
+        }
+
+        doc = doc.add_paragraph(
+            Paragraph::new()
+                .add_run(
+                    Run::new()
+                        .add_text(config.title())
+                        .size(style.title_size * 3 / 2)
+                        .bold()
+                        .fonts(Self::run_fonts(style)),
+                )
+                .align(AlignmentType::Center),
+        );
+
+        if delta.is_some() {
+            doc = doc.add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(labels.delta_report)
+                            .size(style.heading_size)
+                            .bold()
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Center)
+                    .line_spacing(LineSpacing::new().before(240)),
+            );
+        }
+
+        for _ in 0..4 {
+            doc = doc.add_paragraph(Paragraph::new());
+        }
+
+        let generated_date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let mut metadata = vec![
+            (labels.identifier, config.identifier_tag.clone()),
+            (labels.software_version, config.code_version.clone()),
+            (labels.tester, config.ceshi_user.clone()),
+            (labels.test_time, config.ceshi_time.clone()),
+            (labels.generated_date, generated_date),
+        ];
+        if let Some(diff) = delta {
+            let increased = diff.count_changes.iter().filter(|c| c.delta() > 0).count();
+            metadata.push((labels.added_findings, diff.added_groups.len().to_string()));
+            metadata.push((labels.increased_findings, increased.to_string()));
+            metadata.push((
+                labels.resolved_findings,
+                diff.removed_groups.len().to_string(),
+            ));
+        }
+
+        for (label, value) in metadata {
+            doc = doc.add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(format!("{}{}{}", label, labels.colon, value))
+                            .size(style.heading_size)
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Center)
+                    .line_spacing(LineSpacing::new().before(120).after(120)),
+            );
+        }
+
+        // 封面之后分页
+        doc = doc.add_paragraph(Paragraph::new().add_run(Run::new().add_break(BreakType::Page)));
+
+        Ok(doc)
+    }
+
+    /// 添加已修复问题表格（变化报告中上次有、本次已消失的分组），没有已修复的分组时不添加
+    fn add_resolved_table(
+        doc: Docx,
+        removed_groups: &[(String, GroupInfo)],
+        config: &ReportConfig,
+    ) -> Docx {
+        let labels = config.language.labels();
+        let rows = removed_groups
+            .iter()
+            .map(|(_, info)| {
+                let counts = vec![info.record_count];
+                (info.b_column.as_str(), info.d_column.as_str(), counts)
+            })
+            .collect();
+        Self::add_delta_table(
+            doc,
+            labels.resolved_findings,
+            &labels.resolved_columns,
+            vec![800, 4200, 1900, 2300],
+            rows,
+            config,
+        )
+    }
+
+    /// 添加数量增加的问题表格（变化报告中两次都存在、本次记录数更多的分组），没有这样的分组时不添加
+    fn add_increased_table(
+        doc: Docx,
+        count_changes: &[GroupCountChange],
+        config: &ReportConfig,
+    ) -> Docx {
+        let labels = config.language.labels();
+        let rows = count_changes
+            .iter()
+            .filter(|change| change.delta() > 0)
+            .map(|change| {
+                let (name, severity) = change.name_and_severity();
+                (name, severity, vec![change.old_count, change.new_count])
+            })
+            .collect();
+        Self::add_delta_table(
+            doc,
+            labels.increased_findings,
+            &labels.increased_columns,
+            vec![800, 3700, 1500, 1600, 1600],
+            rows,
+            config,
+        )
+    }
+
+    /// 添加变化报告的汇总表格：标题及（问题名称、严重性级别、各问题数量）行，前面加序号列；没有行时不添加
+    fn add_delta_table(
+        mut doc: Docx,
+        title: &str,
+        columns: &[&str],
+        grid: Vec<usize>,
+        rows: Vec<(&str, &str, Vec<usize>)>,
+        config: &ReportConfig,
+    ) -> Docx {
+        if rows.is_empty() {
+            return doc;
+        }
+        let style = &config.document_style;
+
+        doc = doc.add_paragraph(
+            Paragraph::new()
+                .add_run(
+                    Run::new()
+                        .add_text(title)
+                        .size(style.title_size)
+                        .bold()
+                        .fonts(Self::run_fonts(style)),
+                )
+                .align(AlignmentType::Center),
+        );
+
+        let header_cells = columns
+            .iter()
+            .map(|header| Self::create_header_cell(header, style))
+            .collect();
+        let mut table = Table::new(vec![TableRow::new(header_cells)])
+            .set_grid(grid)
+            .set_borders(Self::table_borders(style))
+            .align(TableAlignmentType::Center);
+
+        for (seq, (name, severity, counts)) in (1..).zip(rows) {
+            let risk_level = RiskLevel::from_severity(severity);
+            let mut cells = vec![
+                Self::create_data_cell(&seq.to_string(), AlignmentType::Right, style),
+                Self::create_data_cell(name, AlignmentType::Center, style),
+                Self::create_data_cell(
+                    config.language.severity_label(&risk_level),
+                    AlignmentType::Center,
+                    style,
+                ),
+            ];
+            cells.extend(counts.iter().map(|count| {
+                Self::create_data_cell(&count.to_string(), AlignmentType::Right, style)
+            }));
+            table = table.add_row(TableRow::new(cells));
+        }
+
+        doc = doc.add_table(table);
+        doc.add_paragraph(Paragraph::new()) // 空行
+    }
+
+    /// 过滤后是否没有任何问题；no_findings_mode 为 Error 时没有问题返回 NoFindings 错误
+    pub(crate) fn no_findings(
+        config: &ReportConfig,
+        result_data: &ExcelProcessResult,
+    ) -> Result<bool> {
+        let no_findings = result_data.grouped_data.is_empty();
+        if no_findings && config.no_findings_mode == NoFindingsMode::Error {
+            return Err(ReportError::NoFindings.into());
+        }
+        if no_findings {
+            log::warn!("过滤和去重后没有任何问题，生成未发现问题的报告");
+        }
+        Ok(no_findings)
+    }
+
+    /// 添加“本次测试未发现问题”说明页
+    fn add_no_findings_page(doc: Docx, config: &ReportConfig) -> Docx {
+        let style = &config.document_style;
+        doc.add_paragraph(
+            Paragraph::new()
+                .add_run(
+                    Run::new()
+                        .add_text(config.language.labels().no_findings)
+                        .size(style.title_size)
+                        .bold()
+                        .fonts(Self::run_fonts(style)),
+                )
+                .align(AlignmentType::Center)
+                .line_spacing(LineSpacing::new().before(480).after(480)),
+        )
+    }
+
+    /// 读取并按行解析附录文件；未配置时返回 None，无法读取时记录警告并返回 None
+    pub(crate) fn appendix_lines(config: &ReportConfig) -> Option<Vec<AppendixLine>> {
+        let path = config.appendix_file.as_deref()?;
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                log::warn!("无法读取附录文件 {}，已跳过: {}", path, e);
+                return None;
+            }
+        };
+
+        let lines = normalize_newlines(&text)
+            .trim()
+            .lines()
+            .map(|line| {
+                let heading = line.trim_start();
+                let text = heading.trim_start_matches('#');
+                match heading.len() - text.len() {
+                    0 => AppendixLine::Text(line.to_string()),
+                    level => {
+                        AppendixLine::Heading(level.min(MAX_HEADING_LEVEL), text.trim().to_string())
+                    }
+                }
+            })
+            .collect();
+        Some(lines)
+    }
+
+    /// 添加附录：每行一个段落，空行保留为空段落；标题行使用对应级别的标题样式（HeadingN）
+    fn add_appendix(mut doc: Docx, lines: &[AppendixLine], style: &DocumentStyle) -> Docx {
+        for line in lines {
+            let paragraph = match line {
+                AppendixLine::Heading(level, text) => Paragraph::new()
+                    .add_run(Run::new().add_text(text).fonts(Self::run_fonts(style)))
+                    .style(&format!("Heading{}", level)),
+                AppendixLine::Text(text) => Paragraph::new().add_run(
+                    Run::new()
+                        .add_text(text)
+                        .size(style.body_size)
+                        .fonts(Self::run_fonts(style)),
+                ),
+            };
+            doc = doc.add_paragraph(paragraph);
+        }
+        doc
+    }
+
+    /// 声明 Heading1~Heading3 段落样式（附录标题、章节标题使用），设置大纲级别以便目录和导航窗格识别
+    fn add_heading_styles(mut doc: Docx, style: &DocumentStyle) -> Docx {
+        for level in 1..=MAX_HEADING_LEVEL {
+            let size = if level == 1 {
+                style.title_size
+            } else {
+                style.heading_size
+            };
+            doc = doc.add_style(
+                Style::new(format!("Heading{}", level), StyleType::Paragraph)
+                    .name(format!("heading {}", level))
+                    .size(size)
+                    .bold()
+                    .fonts(Self::run_fonts(style))
+                    .outline_lvl(level - 1),
+            );
+        }
+        doc
+    }
+
+    /// 统计表格列宽：使用配置的列宽，数量与列数不一致时使用默认列宽
+    fn statistics_column_widths(config: &ReportConfig) -> Vec<usize> {
+        match &config.statistics_column_widths {
+            Some(widths) if widths.len() == STATISTICS_COLUMNS.len() => widths.clone(),
+            Some(widths) => {
+                log::warn!(
+                    "统计表格列宽数量（{}）与列数（{}）不一致，使用默认列宽",
+                    widths.len(),
+                    STATISTICS_COLUMNS.len()
+                );
+                DEFAULT_STATISTICS_COLUMN_WIDTHS.to_vec()
+            }
+            None => DEFAULT_STATISTICS_COLUMN_WIDTHS.to_vec(),
+        }
+    }
+
+    /// 添加统计表格到文档，summary 为标题下方的汇总语句
+    fn add_statistics_table(
+        mut doc: Docx,
+        statistics: &[StatisticItem],
+        summary: Option<String>,
+        column_widths: Vec<usize>,
+        config: &ReportConfig,
+    ) -> Result<Docx> {
+        let style = &config.document_style;
+        let labels = config.language.labels();
+
+        // 添加标题
+        doc = doc.add_paragraph(
+            Paragraph::new()
+                .add_run(
+                    Run::new()
+                        .add_text(labels.statistics_title)
+                        .size(style.title_size)
+                        .bold()
+                        .fonts(Self::run_fonts(style)),
+                )
+                .align(AlignmentType::Center),
+        );
+
+        if let Some(summary) = summary {
+            doc = doc.add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(summary)
+                            .size(style.body_size)
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Center),
+            );
+        }
+
+        // 创建表头行 - 带样式
+        let header_cells = labels
+            .statistics_columns
+            .iter()
+            .map(|header| Self::create_header_cell(header, style))
+            .collect();
+
+        // 创建表格，设置边框
+        let mut table = Table::new(vec![TableRow::new(header_cells)])
+            .set_grid(column_widths)
+            .set_borders(Self::table_borders(style))
+            .align(TableAlignmentType::Center);
+
+        // 添加数据行
+        for stat in statistics {
+            let row = TableRow::new(vec![
+                Self::create_data_cell(&stat.seq_num.to_string(), AlignmentType::Right, style),
+                Self::create_link_cell(
+                    &stat.report_number,
+                    &Self::section_bookmark(&stat.report_number),
+                    style,
+                ),
+                Self::create_data_cell(&stat.problem_name, AlignmentType::Center, style),
+                Self::create_data_cell(&stat.severity_level, AlignmentType::Center, style),
+                Self::create_data_cell(
+                    &stat.problem_count.to_string(),
+                    AlignmentType::Right,
+                    style,
+                ),
+                Self::create_data_cell(
+                    &format!("{:.1}%", stat.percentage),
+                    AlignmentType::Right,
+                    style,
+                ),
+            ]);
+            table = table.add_row(row);
+        }
+
+        // 添加合计行
+        let summary = StatisticSummary::from_items(statistics);
+        table = table.add_row(TableRow::new(vec![
+            Self::create_summary_cell(labels.total, style).grid_span(3),
+            Self::create_summary_cell(&summary.severity_text(config.language), style),
+            Self::create_summary_cell(&summary.total_count.to_string(), style),
+            Self::create_summary_cell("", style),
+        ]));
+
+        doc = doc.add_table(table);
+        doc = doc.add_paragraph(Paragraph::new()); // 空行
+
+        Ok(doc)
+    }
+
+    /// 添加目录 - 引用各章节的 Heading3 标题（及附录中的标题）
+    ///
+    /// 目录以 `TOC \o "1-3"` 域写入，docx-rs 只能预先生成条目而无法计算页码，
+    /// 打开文档后需在目录上按 F9（更新域）刷新页码。
+    fn add_table_of_contents(mut doc: Docx, config: &ReportConfig) -> Result<Docx> {
+        let style = &config.document_style;
+        let labels = config.language.labels();
+
+        doc = doc.add_paragraph(
+            Paragraph::new()
+                .add_run(
+                    Run::new()
+                        .add_text(labels.table_of_contents)
+                        .size(style.title_size)
+                        .bold()
+                        .fonts(Self::run_fonts(style)),
+                )
+                .align(AlignmentType::Center),
+        );
+
+        doc = doc.add_table_of_contents(
+            TableOfContents::new()
+                .heading_styles_range(1, MAX_HEADING_LEVEL)
+                .alias(labels.table_of_contents)
+                .auto(),
+        );
+
+        // 目录之后分页
+        doc = doc.add_paragraph(Paragraph::new().add_run(Run::new().add_break(BreakType::Page)));
+
+        Ok(doc)
+    }
+
+    /// 创建表头单元格 - 加粗，居中，浅蓝背景
+    fn create_header_cell(text: &str, style: &DocumentStyle) -> TableCell {
+        TableCell::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(text)
+                            .size(style.header_size)
+                            .bold()
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Center),
+            )
+            .vertical_align(VAlignType::Center)
+            .shading(Shading::new().fill("D9E2F3")) // 浅蓝色背景
+    }
+
+    /// 创建数据单元格 - 普通文本，按 align 对齐（数字列右对齐，文本列居中）
+    fn create_data_cell(text: &str, align: AlignmentType, style: &DocumentStyle) -> TableCell {
+        TableCell::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(text)
+                            .size(style.body_size)
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(align),
+            )
+            .vertical_align(VAlignType::Center)
+    }
+
+    /// 创建内部链接单元格 - 居中，点击跳转到 anchor 书签
+    fn create_link_cell(text: &str, anchor: &str, style: &DocumentStyle) -> TableCell {
+        TableCell::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_hyperlink(
+                        Hyperlink::new(anchor, HyperlinkType::Anchor).add_run(
+                            Run::new()
+                                .add_text(text)
+                                .size(style.body_size)
+                                .color(LINK_COLOR)
+                                .underline("single")
+                                .fonts(Self::run_fonts(style)),
+                        ),
+                    )
+                    .align(AlignmentType::Center),
+            )
+            .vertical_align(VAlignType::Center)
+    }
+
+    /// 章节标题处的书签名称，由问题报告编号生成（统计表格中的编号链接到该书签）：
+    /// 字母、数字保持原样，其他字符转为 _十六进制码；超过书签名称长度限制时使用编号的哈希值
+    fn section_bookmark(report_number: &str) -> String {
+        let mut name = String::from("sec_");
+        for c in report_number.chars() {
+            if c.is_ascii_alphanumeric() {
+                name.push(c);
+            } else {
+                name.push_str(&format!("_{:X}", c as u32));
+            }
+        }
+        if name.len() > MAX_BOOKMARK_NAME_LEN {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            report_number.hash(&mut hasher);
+            name = format!("sec_{:016x}", hasher.finish());
+        }
+        name
+    }
+
+    /// 创建合计单元格 - 加粗，居中，浅灰背景
+    fn create_summary_cell(text: &str, style: &DocumentStyle) -> TableCell {
+        TableCell::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(text)
+                            .size(style.body_size)
+                            .bold()
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Center),
+            )
+            .vertical_align(VAlignType::Center)
+            .shading(Shading::new().fill("E7E6E6")) // 浅灰色背景
+    }
+
+    /// 添加报告章节 - 使用指定的表格格式
+    fn add_report_section(
+        mut doc: Docx,
+        config: &ReportConfig,
+        section: &ReportSection,
+        seq: usize,
+        images: &[SectionImage],
+    ) -> Result<Docx> {
+        let style = &config.document_style;
+        let labels = config.language.labels();
+
+        // 添加标题，标题处放置书签供统计表格链接
+        doc = doc.add_paragraph(
+            Paragraph::new()
+                .add_bookmark_start(seq, Self::section_bookmark(&section.report_number))
+                .add_run(
+                    Run::new()
+                        .add_text(&section.title)
+                        .size(style.heading_size)
+                        .bold()
+                        .fonts(Self::run_fonts(style)),
+                )
+                .add_bookmark_end(seq)
+                .style("Heading3"),
+        );
+
+        // 问题描述：缺陷描述、相关代码及截图
+        let phenomenon = format!(
+            "{}\n{}",
+            labels.line_label(labels.phenomenon),
+            section.phenomenon
+        );
+        let mut description_cell = Self::create_multiline_cell(&phenomenon, style, false)
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text(" ")));
+        description_cell =
+            Self::add_multiline_paragraphs(description_cell, &section.code, style, true);
+        for image in images {
+            description_cell = description_cell.add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_image(Self::create_pic(image)))
+                    .align(AlignmentType::Center),
+            );
+        }
+
+        // 相关文件路径：编号或项目符号使用Word列表
+        let (mut doc, path_cell) =
+            Self::create_path_cell(doc, section, config.path_list_style, style);
+
+        // 创建报告信息表格 (8行4列)
+        let mut table = Table::new(vec![
+            // 第1行：问题报告编号 | [编号] | 软件版本 | [版本]
+            Self::section_info_row([
+                Self::create_label_cell(labels.report_number, style),
+                Self::create_content_cell(&section.report_number, style),
+                Self::create_label_cell(labels.software_version, style),
+                Self::create_content_cell(&config.code_version, style),
+            ]),
+            // 第2行：测试人 | [测试人] | 测试时间 | [时间]
+            Self::section_info_row([
+                Self::create_label_cell(labels.tester, style),
+                Self::create_content_cell(&config.ceshi_user, style),
+                Self::create_label_cell(labels.test_time, style),
+                Self::create_content_cell(&config.ceshi_time, style),
+            ]),
+            // 第3行：问题描述 (跨3列)
+            Self::section_spanned_row(
+                Self::create_label_cell(labels.description, style),
+                description_cell,
+            ),
+            // 第4行：问题严重性级别 (跨3列)
+            Self::section_spanned_row(
+                Self::create_label_cell(labels.severity, style),
+                Self::create_risk_cell(
+                    &section.risk_level,
+                    section.raw_severity.as_deref(),
+                    config.language,
+                    style,
+                ),
+            ),
+            // 第5行：相关文件路径 (跨3列)
+            Self::section_spanned_row(Self::create_label_cell(labels.file_path, style), path_cell),
+            // 第6行：漏洞说明 (跨3列)
+            Self::section_spanned_row(
+                Self::create_label_cell(labels.vulnerability, style),
+                Self::create_multiline_cell(&section.vulnerability, style, false),
+            ),
+            // 第7行：整改建议 (跨3列)
+            Self::section_spanned_row(
+                Self::create_label_cell(labels.suggestion, style),
+                Self::create_multiline_cell(&section.suggestion, style, false),
+            ),
+        ]);
+
+        // 额外字段：每个字段一行 (跨3列)
+        for (label, value) in &section.extra_fields {
+            table = table.add_row(Self::section_spanned_row(
+                Self::create_label_cell(label, style),
+                Self::create_multiline_cell(value, style, false),
+            ));
+        }
+
+        // 设置表格样式和列宽：固定布局及总宽度，避免阅读器按内容重新计算列宽
+        table = table
+            .set_grid(SECTION_TABLE_GRID.to_vec())
+            .width(SECTION_TABLE_GRID.iter().sum(), WidthType::Dxa)
+            .layout(TableLayoutType::Fixed)
+            .set_borders(Self::table_borders(style))
+            .align(TableAlignmentType::Center);
+
+        doc = doc.add_table(table);
+
+        // 缺陷明细：每条记录一行
+        if !section.record_details.is_empty() {
+            doc = Self::add_record_details_table(doc, &section.record_details, labels, style);
+        }
+
+        // 添加空行作为分隔
+        doc = doc.add_paragraph(Paragraph::new());
+
+        Ok(doc)
+    }
+
+    /// 章节表格中四个单元格各占一列的行，每个单元格显式设置列宽
+    fn section_info_row(cells: [TableCell; 4]) -> TableRow {
+        TableRow::new(
+            cells
+                .into_iter()
+                .zip(SECTION_TABLE_GRID)
+                .map(|(cell, width)| cell.width(width, WidthType::Dxa))
+                .collect(),
+        )
+    }
+
+    /// 章节表格中标签 + 内容跨3列的行
+    ///
+    /// 合并单元格除 gridSpan 外还写入合并后的宽度（后三列之和）：只有 gridSpan、没有单元格宽度时，
+    /// 部分 LibreOffice 版本会按未合并的网格重新布局，在合并单元格内画出多余的竖线。
+    /// 章节表格没有跨行合并，不需要 vMerge。
+    fn section_spanned_row(label: TableCell, content: TableCell) -> TableRow {
+        TableRow::new(vec![
+            label.width(SECTION_TABLE_GRID[0], WidthType::Dxa),
+            content
+                .grid_span(SECTION_TABLE_GRID.len() - 1)
+                .width(SECTION_TABLE_GRID[1..].iter().sum(), WidthType::Dxa),
+        ])
+    }
+
+    /// 表格四周及内部的单实线边框（颜色、宽度取自文档样式），不依赖Word版本的默认表格样式
+    fn table_borders(style: &DocumentStyle) -> TableBorders {
+        [
+            TableBorderPosition::Top,
+            TableBorderPosition::Left,
+            TableBorderPosition::Bottom,
+            TableBorderPosition::Right,
+            TableBorderPosition::InsideH,
+            TableBorderPosition::InsideV,
+        ]
+        .into_iter()
+        .fold(TableBorders::with_empty(), |borders, position| {
+            borders.set(
+                TableBorder::new(position)
+                    .border_type(BorderType::Single)
+                    .size(style.border_size)
+                    .color(style.border_color.as_str()),
+            )
+        })
+    }
+
+    /// 添加缺陷明细表格（序号、文件路径、相关代码），前面加小标题以免与章节表格相连
+    fn add_record_details_table(
+        doc: Docx,
+        details: &[RecordDetail],
+        labels: &Labels,
+        style: &DocumentStyle,
+    ) -> Docx {
+        let doc = doc.add_paragraph(
+            Paragraph::new().add_run(
+                Run::new()
+                    .add_text(labels.record_details)
+                    .size(style.body_size)
+                    .bold()
+                    .fonts(Self::run_fonts(style)),
+            ),
+        );
+
+        let header_cells = labels
+            .record_detail_columns
+            .iter()
+            .map(|header| Self::create_header_cell(header, style))
+            .collect();
+        let mut table = Table::new(vec![TableRow::new(header_cells)])
+            .set_grid(vec![800, 3400, 5000])
+            .set_borders(Self::table_borders(style))
+            .align(TableAlignmentType::Center);
+
+        for (i, detail) in details.iter().enumerate() {
+            table = table.add_row(TableRow::new(vec![
+                Self::create_data_cell(&(i + 1).to_string(), AlignmentType::Center, style),
+                Self::create_data_cell(&detail.path, AlignmentType::Center, style),
+                Self::create_multiline_cell(&detail.code, style, true),
+            ]));
+        }
+
+        doc.add_table(table)
+    }
+
+    /// 创建标签单元格 - 浅灰背景，加粗，居中
+    fn create_label_cell(text: &str, style: &DocumentStyle) -> TableCell {
+        TableCell::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(text)
+                            .size(style.body_size)
+                            .bold()
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Center),
+            )
+            .vertical_align(VAlignType::Center)
+            .shading(Shading::new().fill("F2F2F2")) // 浅灰色背景
+    }
+
+    /// 创建内容单元格 - 普通文本，左对齐，垂直居中
+    fn create_content_cell(text: &str, style: &DocumentStyle) -> TableCell {
+        TableCell::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(text)
+                            .size(style.body_size)
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Left),
+            )
+            .vertical_align(VAlignType::Center)
+    }
+
+    /// 创建严重性级别单元格 - 复选框文本，按风险等级着色；raw 为原始严重性级别文本，附在复选框下方
+    fn create_risk_cell(
+        risk_level: &RiskLevel,
+        raw: Option<&str>,
+        language: Language,
+        style: &DocumentStyle,
+    ) -> TableCell {
+        let labels = language.labels();
+        let mut cell = Self::create_content_cell(&risk_level.text(language), style);
+        if let Some(raw) = raw {
+            cell = cell.add_paragraph(
+                Paragraph::new()
+                    .add_run(
+                        Run::new()
+                            .add_text(format!("{}{}{}", labels.raw_severity, labels.colon, raw))
+                            .size(style.body_size)
+                            .fonts(Self::run_fonts(style)),
+                    )
+                    .align(AlignmentType::Left),
+            );
+        }
+
+        match RISK_LEVEL_FILLS
+            .iter()
+            .find(|(level, _)| level == risk_level)
+        {
+            Some((_, fill)) => cell.shading(Shading::new().fill(*fill)),
+            None => cell,
+        }
+    }
+
+    /// 创建多行内容单元格 - 支持换行，左对齐，顶部对齐；代码使用等宽字体
+    fn create_multiline_cell(text: &str, style: &DocumentStyle, monospace: bool) -> TableCell {
+        Self::add_multiline_paragraphs(TableCell::new(), text, style, monospace)
+            .vertical_align(VAlignType::Top)
+    }
+
+    /// 创建相关文件路径单元格：编号、项目符号样式的路径使用Word列表（每个列表单独编号），
+    /// 标签及省略说明为普通段落；纯文本样式或没有路径时按行显示 code_path
+    fn create_path_cell(
+        mut doc: Docx,
+        section: &ReportSection,
+        list_style: PathListStyle,
+        style: &DocumentStyle,
+    ) -> (Docx, TableCell) {
+        let has_items = section
+            .path_lines
+            .iter()
+            .any(|line| matches!(line, PathLine::Item(..)));
+        let Some(abstract_id) = Self::list_abstract_id(list_style).filter(|_| has_items) else {
+            let cell = Self::create_multiline_cell(&section.code_path, style, true);
+            return (doc, cell);
+        };
+
+        let mut cell = TableCell::new();
+        let mut num_id = 0;
+        for (i, line) in section.path_lines.iter().enumerate() {
+            let mut para = match line {
+                PathLine::Text(text) => {
+                    Paragraph::new().add_run(Self::text_run(&Self::clean_text(text), style, true))
+                }
+                PathLine::Item(seq, path) => {
+                    // 每个列表的第一项新建编号实例，使编号从1开始
+                    if *seq == 1 {
+                        num_id = doc.numberings.numberings.len() + FIRST_LIST_NUMBERING_ID;
+                        doc = doc.add_numbering(
+                            Numbering::new(num_id, abstract_id)
+                                .add_override(LevelOverride::new(0).start(1)),
+                        );
+                    }
+                    Paragraph::new()
+                        .add_run(Self::text_run(&Self::clean_text(path), style, true))
+                        .numbering(NumberingId::new(num_id), IndentLevel::new(0))
+                }
+            }
+            .align(AlignmentType::Left);
+
+            if i > 0 {
+                para = para.line_spacing(LineSpacing::new().before(80).after(0));
+            }
+            cell = cell.add_paragraph(para);
+        }
+
+        (doc, cell.vertical_align(VAlignType::Top))
+    }
+
+    /// 路径列表样式对应的列表定义编号，纯文本样式不使用列表
+    fn list_abstract_id(list_style: PathListStyle) -> Option<usize> {
+        match list_style {
+            PathListStyle::Plain => None,
+            PathListStyle::Numbered => Some(NUMBERED_LIST_ABSTRACT_ID),
+            PathListStyle::Bulleted => Some(BULLETED_LIST_ABSTRACT_ID),
+        }
+    }
+
+    /// 声明路径列表使用的编号列表（1. 2. 3.）及项目符号列表（•）
+    fn add_list_numberings(doc: Docx) -> Docx {
+        let level = |format: &str, text: &str| {
+            Level::new(
+                0,
+                Start::new(1),
+                NumberFormat::new(format),
+                LevelText::new(text),
+                LevelJc::new("left"),
+            )
+            .indent(
+                Some(LIST_INDENT),
+                Some(SpecialIndentType::Hanging(LIST_INDENT)),
+                None,
+                None,
+            )
+        };
+        doc.add_abstract_numbering(
+            AbstractNumbering::new(NUMBERED_LIST_ABSTRACT_ID).add_level(level("decimal", "%1.")),
+        )
+        .add_abstract_numbering(
+            AbstractNumbering::new(BULLETED_LIST_ABSTRACT_ID).add_level(level("bullet", "•")),
+        )
+    }
+
+    /// 创建文本块，多行文本以换行符分隔；等宽模式下使用代码字体并添加浅灰背景
+    fn text_run(text: &str, style: &DocumentStyle, monospace: bool) -> Run {
+        let mut run = Run::new().size(style.body_size);
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                run = run.add_break(BreakType::TextWrapping);
+            }
+            run = run.add_text(line);
+        }
+        if monospace {
+            run.fonts(Self::code_fonts())
+                .shading(Shading::new().fill(CODE_FILL))
+        } else {
+            run.fonts(Self::run_fonts(style))
+        }
+    }
+
+    /// 按行向单元格追加段落，等宽模式下使用代码字体并添加浅灰背景
+    fn add_multiline_paragraphs(
+        mut cell: TableCell,
+        text: &str,
+        style: &DocumentStyle,
+        monospace: bool,
+    ) -> TableCell {
+        let cleaned_text = normalize_newlines(text);
+
+        // 按行分割文本
+        let lines: Vec<&str> = cleaned_text.split('\n').collect();
+
+        for (i, line) in lines.iter().enumerate() {
+            // 跳过空行，但保留一些间距
+            if line.trim().is_empty() && i > 0 {
+                cell = cell.add_paragraph(Paragraph::new().add_run(Run::new().add_text(" ")));
+                continue;
+            }
+
+            let run = Self::text_run(line, style, monospace);
+            let mut para = Paragraph::new().add_run(run).align(AlignmentType::Left);
+
+            // 为段落间添加适当间距
+            if i > 0 {
+                para = para.line_spacing(LineSpacing::new().before(80).after(0));
+            }
+
+            cell = cell.add_paragraph(para);
+        }
+
+        cell
+    }
+
+    /// 加载每条记录指定列中的截图，无法读取的路径跳过并记录警告
+    fn load_section_images(records: &[ExcelRecord], column: &str) -> Vec<SectionImage> {
+        let mut images = Vec::new();
+
+        for record in records {
+            let path = match record.data.get(column).and_then(|v| v.as_ref()) {
+                Some(path) => path,
+                None => continue,
+            };
+
+            let data = match std::fs::read(path) {
+                Ok(data) => data,
+                Err(e) => {
+                    log::warn!("无法读取截图 {}: {}", path, e);
+                    continue;
+                }
+            };
+
+            match Self::image_dimensions(&data) {
+                Some((width_px, height_px)) => images.push(SectionImage {
+                    data,
+                    width_px,
+                    height_px,
+                }),
+                None => log::warn!("截图不是有效的PNG/JPEG文件，已跳过: {}", path),
+            }
+        }
+
+        images
+    }
+
+    /// 每条记录指定列中截图文件的大小合计，无法读取的路径不计入
+    fn image_file_bytes(records: &[ExcelRecord], column: &str) -> u64 {
+        records
+            .iter()
+            .filter_map(|record| record.data.get(column).and_then(|v| v.as_ref()))
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// 创建图片，超过最大宽度时等比缩放
+    fn create_pic(image: &SectionImage) -> Pic {
+        let mut width_emu = image.width_px as u64 * EMU_PER_PIXEL as u64;
+        let mut height_emu = image.height_px as u64 * EMU_PER_PIXEL as u64;
+        if width_emu > MAX_IMAGE_WIDTH_EMU as u64 {
+            height_emu = height_emu * MAX_IMAGE_WIDTH_EMU as u64 / width_emu;
+            width_emu = MAX_IMAGE_WIDTH_EMU as u64;
+        }
+
+        Pic::new_with_dimensions(image.data.clone(), image.width_px, image.height_px)
+            .size(width_emu as u32, height_emu as u32)
+    }
+
+    /// 读取PNG/JPEG图片的像素尺寸，其他格式返回None
+    fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+        const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+        if data.starts_with(PNG_SIGNATURE) {
+            // IHDR 块：宽高位于偏移 16..24（大端）
+            let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+            let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+            return Some((width, height));
+        }
+
+        if data.starts_with(&[0xFF, 0xD8]) {
+            // 遍历JPEG标记，SOF段中记录了图片尺寸
+            let mut pos = 2;
+            while pos + 4 <= data.len() {
+                if data[pos] != 0xFF {
+                    return None;
+                }
+                let marker = data[pos + 1];
+                let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+                let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+                if is_sof {
+                    let segment = data.get(pos + 4..pos + 9)?;
+                    let height = u16::from_be_bytes([segment[1], segment[2]]) as u32;
+                    let width = u16::from_be_bytes([segment[3], segment[4]]) as u32;
+                    return Some((width, height));
+                }
+                pos += 2 + length;
+            }
+        }
+
+        None
+    }
+
+    /// 生成相关代码文本，单条记录的代码为空时替换为占位文本（配置了 empty_field_placeholder 时）
+    fn generate_code_text(
+        records: &[ExcelRecord],
+        column: &str,
+        max_entries: Option<usize>,
+        placeholder: Option<&str>,
+        labels: &Labels,
+    ) -> String {
+        if !Self::has_column(records, column) {
+            log::warn!("相关代码列 {} 不存在，相关代码留空", column);
+            return String::new();
+        }
+
+        let mut code_text = String::new();
+        let shown = Self::shown_entries(records.len(), max_entries);
+        for (i, record) in records.iter().take(shown).enumerate() {
+            let code = normalize_newlines(Self::cell_text(record, column));
+            code_text.push_str(&format!(
+                "{}\n{}\n",
+                labels.code_label(i + 1),
+                Self::or_placeholder(code, placeholder)
+            ));
+        }
+        code_text.push_str(&Self::omitted_note(labels, records.len(), shown));
+        code_text.trim().to_string()
+    }
+
+    /// 最多列出的条数：max_entries 为空时全部列出
+    fn shown_entries(total: usize, max_entries: Option<usize>) -> usize {
+        max_entries.map_or(total, |max| max.min(total))
+    }
+
+    /// 超出条数限制时附加的省略说明
+    fn omitted_note(labels: &Labels, total: usize, shown: usize) -> String {
+        match total - shown {
+            0 => String::new(),
+            omitted => format!("{}\n", labels.omitted_note(omitted)),
+        }
+    }
+
+    /// 生成文件路径文本（path_list_style 的编号或项目符号以文字表示）
+    #[cfg(test)]
+    fn generate_path_text(records: &[ExcelRecord], config: &ReportConfig) -> Result<String> {
+        let lines = Self::generate_path_lines(records, config)?;
+        Ok(Self::path_lines_text(&lines, config.path_list_style))
+    }
+
+    /// 生成文件路径各行：每条路径前加 path_label_template 标签（模板为空时不加），
+    /// 路径为空时替换为 empty_field_placeholder；路径作为同一列表的各项
+    fn generate_path_lines(
+        records: &[ExcelRecord],
+        config: &ReportConfig,
+    ) -> Result<Vec<PathLine>> {
+        let column = &config.column_mapping.path_column;
+        if !Self::has_column(records, column) {
+            log::warn!("文件路径列 {} 不存在，文件路径留空", column);
+            return Ok(Vec::new());
+        }
+
+        let strip_prefix = config.strip_path_prefix.as_deref();
+        let placeholder = config.empty_field_placeholder.as_deref();
+        let mut lines = Vec::new();
+        let shown = Self::shown_entries(records.len(), config.max_snippets_per_group);
+        for (i, record) in records.iter().take(shown).enumerate() {
+            let label = config.path_label(i + 1)?;
+            if !label.trim().is_empty() {
+                lines.push(PathLine::Text(label));
+            }
+            let path = Self::or_placeholder(
+                normalize_newlines(Self::record_path(record, column, strip_prefix)),
+                placeholder,
+            );
+            lines.push(PathLine::Item(i + 1, path));
+        }
+        let labels = config.language.labels();
+        lines.extend(Self::omitted_line(labels, records.len(), shown));
+        Ok(lines)
+    }
+
+    /// 超出条数限制时的省略说明行
+    fn omitted_line(labels: &Labels, total: usize, shown: usize) -> Option<PathLine> {
+        let note = Self::omitted_note(labels, total, shown);
+        (!note.is_empty()).then(|| PathLine::Text(note.trim_end().to_string()))
+    }
+
+    /// 将文件路径各行拼接为文本，列表项按 path_list_style 加编号或项目符号
+    fn path_lines_text(lines: &[PathLine], list_style: PathListStyle) -> String {
+        let lines: Vec<String> = lines
+            .iter()
+            .map(|line| match line {
+                PathLine::Text(text) => text.clone(),
+                PathLine::Item(seq, path) => list_style.item(*seq, path),
+            })
+            .collect();
+        lines.join("\n").trim().to_string()
+    }
+
+    /// 合并相同的相关代码：每段不同的代码只列出一次，文件路径按代码分组列出
+    /// （每段代码的路径单独成一个列表），为空的代码和路径替换为 empty_field_placeholder，
+    /// 返回（文件路径各行，相关代码文本）
+    fn generate_collapsed_code_texts(
+        records: &[ExcelRecord],
+        config: &ReportConfig,
+    ) -> Result<(Vec<PathLine>, String)> {
+        let code_column = &config.column_mapping.code_column;
+        let path_column = &config.column_mapping.path_column;
+        let strip_prefix = config.strip_path_prefix.as_deref();
+        let placeholder = config.empty_field_placeholder.as_deref();
+        if !Self::has_column(records, code_column) {
+            log::warn!("相关代码列 {} 不存在，相关代码留空", code_column);
+            let path_lines = Self::generate_path_lines(records, config)?;
+            return Ok((path_lines, String::new()));
+        }
+        let has_path = Self::has_column(records, path_column);
+        if !has_path {
+            log::warn!("文件路径列 {} 不存在，文件路径留空", path_column);
+        }
+
+        // 代码 -> 使用该代码的文件路径（保持首次出现顺序）
+        let mut snippets: IndexMap<String, Vec<String>> = IndexMap::new();
+        for record in records {
+            let code = normalize_newlines(Self::cell_text(record, code_column));
+            let path = Self::or_placeholder(
+                normalize_newlines(Self::record_path(record, path_column, strip_prefix)),
+                placeholder,
+            );
+            snippets.entry(code).or_default().push(path);
+        }
+
+        let labels = config.language.labels();
+        let mut path_lines = Vec::new();
+        let mut code_text = String::new();
+        let shown = Self::shown_entries(snippets.len(), config.max_snippets_per_group);
+        for (i, (code, paths)) in snippets.iter().take(shown).enumerate() {
+            code_text.push_str(&format!(
+                "{}\n{}\n",
+                labels.collapsed_code_label(i + 1, paths.len()),
+                Self::or_placeholder(code.clone(), placeholder)
+            ));
+            path_lines.push(PathLine::Text(labels.collapsed_path_label(i + 1)));
+            path_lines.extend(
+                paths
+                    .iter()
+                    .zip(1..)
+                    .map(|(path, seq)| PathLine::Item(seq, path.clone())),
+            );
+        }
+        code_text.push_str(&Self::omitted_note(labels, snippets.len(), shown));
+        path_lines.extend(Self::omitted_line(labels, snippets.len(), shown));
+
+        if !has_path {
+            path_lines.clear();
+        }
+        Ok((path_lines, code_text.trim().to_string()))
+    }
+
+    /// 记录中指定列的文本，列不存在或为空时返回空字符串
+    fn cell_text<'a>(record: &'a ExcelRecord, column: &str) -> &'a str {
+        record
+            .data
+            .get(column)
+            .and_then(|v| v.as_ref())
+            .map(|s| s.as_str())
+            .unwrap_or("")
+    }
+
+    /// 记录的文件路径，按配置去除前导目录
+    fn record_path<'a>(
+        record: &'a ExcelRecord,
+        column: &str,
+        strip_prefix: Option<&str>,
+    ) -> &'a str {
+        let path = Self::cell_text(record, column);
+        match strip_prefix {
+            Some(prefix) => Self::strip_path_prefix(path, prefix),
+            None => path,
+        }
+    }
+
+    /// 去除完整的前导路径组件（如 `root/`、`/root/`），保留其后的分隔符；
+    /// 仅部分匹配时（如 `rootkit/`）保持原样
+    fn strip_path_prefix<'a>(path: &'a str, prefix: &str) -> &'a str {
+        let is_separator = |c: char| c == '/' || c == '\\';
+        let prefix = prefix.trim_matches(is_separator);
+        if prefix.is_empty() {
+            return path;
+        }
+
+        let relative = path.strip_prefix(is_separator).unwrap_or(path);
+        match relative.strip_prefix(prefix) {
+            Some(rest) if rest.starts_with(is_separator) => rest,
+            _ => path,
+        }
+    }
+
+    /// 记录中是否包含指定列（以第一条记录为准）
+    fn has_column(records: &[ExcelRecord], column: &str) -> bool {
+        records
+            .first()
+            .is_some_and(|record| record.data.contains_key(column))
+    }
+
+    /// 创建页脚 - 居中显示页码，模板中的 {page}/{total} 替换为 PAGE/NUMPAGES 域
+    fn create_footer(template: &str, style: &DocumentStyle) -> Footer {
+        let mut para = Paragraph::new().align(AlignmentType::Center);
+
+        for part in Self::parse_footer_template(template) {
+            let run = Run::new()
+                .size(style.body_size)
+                .fonts(Self::run_fonts(style));
+            let run = match part {
+                FooterPart::Text(text) => run.add_text(text),
+                FooterPart::Page => Self::add_field(run, InstrText::PAGE(InstrPAGE::new())),
+                FooterPart::TotalPages => {
+                    Self::add_field(run, InstrText::NUMPAGES(InstrNUMPAGES::new()))
+                }
+            };
+            para = para.add_run(run);
+        }
+
+        Footer::new().add_paragraph(para)
+    }
+
+    /// 创建水印页眉 - 只含一个空段落，水印形状在打包后由 rewrite_package 插入该段落
+    fn create_watermark_header(watermark: Option<&str>) -> Option<Header> {
+        watermark.map(str::trim).filter(|text| !text.is_empty())?;
+        Some(Header::new().add_paragraph(Paragraph::new()))
+    }
+
+    /// 水印形状（VML艺术字，与Word“自定义水印”相同）：在页面中央斜置，衬于文字下方，
+    /// 宽度按文字长度估算（中文字符按1个字宽，其他按半个字宽），超出正文宽度时等比缩小
+    fn watermark_shape(text: &str, style: &DocumentStyle) -> String {
+        let ems: f32 = text
+            .chars()
+            .map(|c| if c.is_ascii() { 0.55 } else { 1.0 })
+            .sum();
+        let scale = (WATERMARK_MAX_WIDTH_PT / (ems * WATERMARK_HEIGHT_PT)).min(1.0);
+        let height = WATERMARK_HEIGHT_PT * scale;
+        let width = ems * height;
+
+        format!(
+            concat!(
+                r#"<w:r><w:pict>"#,
+                r#"<v:shapetype id="_x0000_t136" coordsize="21600,21600" o:spt="136" adj="10800" path="m@7,l@8,m@5,21600l@6,21600e">"#,
+                r#"<v:formulas><v:f eqn="sum #0 0 10800"/><v:f eqn="prod #0 2 1"/><v:f eqn="sum 21600 0 @1"/><v:f eqn="sum 0 0 @2"/><v:f eqn="sum 21600 0 @3"/><v:f eqn="if @0 @3 0"/><v:f eqn="if @0 21600 @1"/><v:f eqn="if @0 0 @2"/><v:f eqn="if @0 @4 21600"/><v:f eqn="mid @5 @6"/><v:f eqn="mid @8 @5"/><v:f eqn="mid @7 @8"/><v:f eqn="mid @6 @7"/><v:f eqn="sum @6 0 @5"/></v:formulas>"#,
+                r#"<v:path textpathok="t" o:connecttype="custom" o:connectlocs="@9,0;@10,10800;@11,21600;@12,10800" o:connectangles="270,180,90,0"/>"#,
+                r#"<v:textpath on="t" fitshape="t"/><o:lock v:ext="edit" text="t" shapetype="t"/></v:shapetype>"#,
+                r##"<v:shape id="ReportForgeWatermark" o:spid="_x0000_s1025" type="#_x0000_t136" o:allowincell="f" fillcolor="#{color}" stroked="f" "##,
+                r#"style="position:absolute;margin-left:0;margin-top:0;width:{width:.1}pt;height:{height:.1}pt;rotation:{rotation};z-index:-251657216;"#,
+                r#"mso-position-horizontal:center;mso-position-horizontal-relative:margin;mso-position-vertical:center;mso-position-vertical-relative:margin">"#,
+                r#"<v:fill opacity=".5"/><v:textpath style="font-family:&quot;{font}&quot;;font-size:1pt" string="{text}"/>"#,
+                r#"<w10:wrap anchorx="margin" anchory="margin"/></v:shape>"#,
+                r#"</w:pict></w:r>"#
+            ),
+            color = WATERMARK_COLOR,
+            width = width,
+            height = height,
+            rotation = WATERMARK_ROTATION,
+            font = Self::xml_escape(&style.east_asia_font),
+            text = Self::xml_escape(text),
+        )
+    }
+
+    /// 在页眉的第一个段落末尾插入水印形状
+    fn insert_watermark(header_xml: &str, shape: &str) -> String {
+        match header_xml.find("</w:p>") {
+            Some(pos) => format!("{}{}{}", &header_xml[..pos], shape, &header_xml[pos..]),
+            None => header_xml.to_string(),
+        }
+    }
+
+    /// 在文本段中添加域代码，Word打开时自动计算
+    fn add_field(run: Run, instr: InstrText) -> Run {
+        run.add_field_char(FieldCharType::Begin, true)
+            .add_instr_text(instr)
+            .add_field_char(FieldCharType::Separate, false)
+            .add_text("1")
+            .add_field_char(FieldCharType::End, false)
+    }
+
+    /// 解析页脚模板
+    fn parse_footer_template(template: &str) -> Vec<FooterPart> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+
+        while !rest.is_empty() {
+            let page_pos = rest.find("{page}");
+            let total_pos = rest.find("{total}");
+            let next = match (page_pos, total_pos) {
+                (Some(p), Some(t)) if t < p => Some((t, "{total}", FooterPart::TotalPages)),
+                (Some(p), _) => Some((p, "{page}", FooterPart::Page)),
+                (None, Some(t)) => Some((t, "{total}", FooterPart::TotalPages)),
+                (None, None) => None,
+            };
+
+            match next {
+                Some((pos, placeholder, part)) => {
+                    if pos > 0 {
+                        parts.push(FooterPart::Text(rest[..pos].to_string()));
+                    }
+                    parts.push(part);
+                    rest = &rest[pos + placeholder.len()..];
+                }
+                None => {
+                    parts.push(FooterPart::Text(rest.to_string()));
+                    break;
+                }
+            }
         }
-        path_text.trim().to_string()
+
+        parts
+    }
+
+    /// 根据文档样式创建字体
+    fn run_fonts(style: &DocumentStyle) -> RunFonts {
+        RunFonts::new()
+            .east_asia(&style.east_asia_font)
+            .ascii(&style.ascii_font)
     }
 
-    /// 清理文本
+    /// 代码块等宽字体
+    fn code_fonts() -> RunFonts {
+        RunFonts::new()
+            .east_asia(CODE_EAST_ASIA_FONT)
+            .ascii(CODE_ASCII_FONT)
+            .hi_ansi(CODE_ASCII_FONT)
+    }
+
+    /// 清理文本（统一换行符）
     fn clean_text(text: &str) -> String {
-        text.replace("_x000D_", "")
+        normalize_newlines(text)
             .replace("      ", "    ")
             .trim()
             .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProcessOptions;
+    use crate::processors::test_support::{document_xml, record_of};
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    fn group(name: &str, severity: &str, count: usize) -> (String, GroupInfo) {
+        (
+            format!("{}|{}", name, severity),
+            GroupInfo {
+                b_column: name.to_string(),
+                d_column: severity.to_string(),
+                record_count: count,
+                records: Vec::new(),
+                original_severity: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_statistics_summary_totals() {
+        let grouped_data = vec![
+            group("SQL注入", "高危", 5),
+            group("XSS", "中危", 3),
+            group("信息泄露", "低危", 2),
+            group("其他", "提示", 1),
+        ];
+        let result = ExcelProcessResult::from_groups(grouped_data);
+
+        let statistics =
+            WordGenerator::generate_statistics(&result, &ReportConfig::default()).unwrap();
+        let summary = StatisticSummary::from_items(&statistics);
+
+        let expected: usize = statistics.iter().map(|s| s.problem_count).sum();
+        assert_eq!(summary.total_count, expected);
+        assert_eq!(summary.high_count, 5);
+        assert_eq!(summary.medium_count, 3);
+        assert_eq!(summary.low_count, 2);
+        assert_eq!(summary.unknown_count, 1);
+        assert_eq!(
+            summary.severity_text(Language::Zh),
+            "高 5 / 中 3 / 低 2 / 未知 1"
+        );
+        assert_eq!(statistics[0].percentage, 45.5);
+        assert_eq!(statistics[3].percentage, 9.1);
+    }
+
+    #[test]
+    fn test_statistics_severity_in_english() {
+        let grouped_data = vec![
+            group("SQL注入", "高危", 2),
+            group("XSS", "中危", 1),
+            group("其他", "提示", 1),
+        ];
+        let result = ExcelProcessResult::from_groups(grouped_data);
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            language: Language::En,
+            ..ReportConfig::default()
+        };
+
+        let statistics = WordGenerator::generate_statistics(&result, &config).unwrap();
+        let levels: Vec<&str> = statistics
+            .iter()
+            .map(|stat| stat.severity_level.as_str())
+            .collect();
+        assert_eq!(levels, vec!["High", "Medium", "Unknown"]);
+
+        // 小计按风险等级汇总，不受显示语言影响
+        let summary = StatisticSummary::from_items(&statistics);
+        assert_eq!(
+            summary.severity_text(Language::En),
+            "High 2 / Medium 1 / Low 0 / Unknown 1"
+        );
+
+        let sections = WordGenerator::preview_sections(&config, &result).unwrap();
+        assert_eq!(
+            sections[0].risk_text,
+            "☑ High Risk  ☐ Medium Risk  ☐ Low Risk"
+        );
+    }
+
+    #[test]
+    fn test_english_report_has_no_chinese_text() {
+        let (key, mut info) = group("SQL Injection", "High", 3);
+        info.records = (1..=3)
+            .map(|i| {
+                let path = format!("src/{}.c", i);
+                record_of(&[("B", "SQL Injection"), ("I", &path), ("J", "query(input);")])
+            })
+            .collect();
+        // 问题名称为空的分组归入未分类
+        let (unnamed_key, mut unnamed) = group("", "Low", 1);
+        unnamed.records = vec![record_of(&[("I", "src/4.c"), ("J", "log(input);")])];
+        let result = ExcelProcessResult::from_groups(vec![(key, info), (unnamed_key, unnamed)]);
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            language: Language::En,
+            max_snippets_per_group: Some(2),
+            generation_stamp: true,
+            ..ReportConfig::default()
+        };
+        // 文档各部分（正文、页脚）中的文字
+        let texts = |config: &ReportConfig| {
+            let doc = WordGenerator::build_report(config, &result, |_, _| {}).unwrap();
+            let packed = WordGenerator::pack_to_bytes(doc, config).unwrap();
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(packed)).unwrap();
+            let text_run = regex::Regex::new(r"<w:t[^>]*>([^<]*)</w:t>").unwrap();
+            let mut texts = Vec::new();
+            for index in 0..archive.len() {
+                let mut xml = String::new();
+                let mut entry = archive.by_index(index).unwrap();
+                if entry.name().starts_with("word/") && entry.name().ends_with(".xml") {
+                    entry.read_to_string(&mut xml).unwrap();
+                }
+                texts.extend(text_run.captures_iter(&xml).map(|c| c[1].to_string()));
+            }
+            texts.join("\n")
+        };
+        let is_cjk = |c: char| ('\u{4E00}'..='\u{9FFF}').contains(&c) || "：、，（）".contains(c);
+
+        let text = texts(&config);
+        assert!(text.contains("Security Test Report"));
+        assert!(text.contains("1. SQL Injection"));
+        assert!(text.contains("Finding 2 code:"));
+        assert!(text.contains("Finding 1 file path:"));
+        assert!(text.contains("… (1 more omitted)"));
+        assert!(text.contains("2. Uncategorized"));
+        assert!(text.contains("Generated by report-forge v"));
+        assert!(text.contains("Page "));
+        assert!(!text.chars().any(is_cjk), "{}", text);
+
+        config.collapse_identical_code = true;
+        let text = texts(&config);
+        assert!(text.contains("Code 1 (3 occurrences):"));
+        assert!(text.contains("Code 1 file paths:"));
+        assert!(!text.chars().any(is_cjk), "{}", text);
+    }
+
+    #[test]
+    fn test_export_statistics_csv() {
+        let grouped_data = vec![group("SQL注入, 盲注", "高危", 3), group("XSS", "中危", 1)];
+        let result = ExcelProcessResult::from_groups(grouped_data);
+        let dir = std::env::temp_dir().join("report_forge_csv_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("statistics.csv");
+        let config = ReportConfig::default();
+
+        let written =
+            WordGenerator::export_statistics_csv(&result, &config, &path.display().to_string())
+                .unwrap();
+
+        assert_eq!(written, path.display().to_string());
+        let content = std::fs::read_to_string(&written).unwrap();
+        assert!(content.starts_with('\u{FEFF}'));
+        let lines: Vec<&str> = content.trim_start_matches('\u{FEFF}').lines().collect();
+        assert_eq!(
+            lines[0],
+            "序号,问题报告编号,问题名称,严重性级别,问题个数,占比"
+        );
+        let numbers = config.report_numbers(&result.grouped_data).unwrap();
+        assert_eq!(
+            lines[1],
+            format!("1,{},\"SQL注入, 盲注\",高,3,75.0%", numbers[0])
+        );
+        assert_eq!(lines[2], format!("2,{},XSS,中,1,25.0%", numbers[1]));
+
+        // 已有文件不被覆盖；表头随报告语言
+        let config = ReportConfig {
+            language: Language::En,
+            ..ReportConfig::default()
+        };
+        let english =
+            WordGenerator::export_statistics_csv(&result, &config, &path.display().to_string())
+                .unwrap();
+        assert_ne!(english, written);
+        let content = std::fs::read_to_string(&english).unwrap();
+        assert!(content.contains("No.,Report No.,Issue,Severity,Count,Percentage\r\n"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_percentage_with_no_records() {
+        assert_eq!(WordGenerator::percentage(0, 0), 0.0);
+        assert_eq!(WordGenerator::percentage(1, 3), 33.3);
+    }
+
+    #[test]
+    fn test_statistics_report_numbers_match_sections() {
+        let grouped_data = vec![group("SQL注入", "高危", 5), group("XSS", "中危", 3)];
+        let result = ExcelProcessResult::from_groups(grouped_data);
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            wt_add: 10,
+            report_number_format: "{tag}-{seq:05}".to_string(),
+            ..ReportConfig::default()
+        };
+
+        let statistics = WordGenerator::generate_statistics(&result, &config).unwrap();
+
+        assert_eq!(statistics[0].report_number, "TAG-00011");
+        for stat in &statistics {
+            let section_number = config.report_number(stat.seq_num as i32).unwrap();
+            assert_eq!(stat.report_number, section_number);
+        }
+    }
+
+    #[test]
+    fn test_severity_tags_match_between_statistics_and_sections() {
+        let grouped_data = vec![
+            group("SQL注入", "高危", 2),
+            group("XSS", "中危", 1),
+            group("命令注入", "高危", 1),
+            group("日志泄露", "低危", 1),
+        ];
+        let result = ExcelProcessResult::from_groups(grouped_data);
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            tag_by_severity: HashMap::from([
+                (RiskLevel::High, "H-".to_string()),
+                (RiskLevel::Medium, "M-".to_string()),
+            ]),
+            ..ReportConfig::default()
+        };
+
+        let statistics = WordGenerator::generate_statistics(&result, &config).unwrap();
+        let sections = WordGenerator::preview_sections(&config, &result).unwrap();
+
+        let statistic_numbers: Vec<&str> = statistics
+            .iter()
+            .map(|stat| stat.report_number.as_str())
+            .collect();
+        assert_eq!(
+            statistic_numbers,
+            vec!["H-0001", "M-0001", "H-0002", "TAG0001"]
+        );
+        for (stat, section) in statistics.iter().zip(&sections) {
+            assert_eq!(stat.report_number, section.report_number);
+        }
+    }
+
+    #[test]
+    fn test_reports_generated_back_to_back_do_not_collide() {
+        let dir = std::env::temp_dir().join("report_forge_collision_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = ReportConfig {
+            output_dir: dir.display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            code_version: "V1.0".to_string(),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(Vec::new());
+
+        let first = WordGenerator::generate_report(&config, &result, |_, _| {}).unwrap();
+        let second = WordGenerator::generate_report(&config, &result, |_, _| {}).unwrap();
+
+        assert_ne!(first, second);
+        assert!(Path::new(&first).is_file());
+        assert!(Path::new(&second).is_file());
+    }
+
+    #[test]
+    fn test_generated_report_has_core_properties() {
+        let dir = std::env::temp_dir().join("report_forge_core_properties_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = ReportConfig {
+            output_dir: dir.display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            code_version: "V1.0".to_string(),
+            ceshi_user: "张'三\u{1}".to_string(),
+            report_title: "A&B 安全测试报告".to_string(),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(Vec::new());
+
+        let output_file = WordGenerator::generate_report(&config, &result, |_, _| {}).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(output_file).unwrap()).unwrap();
+        assert!(archive.by_name("word/document.xml").is_ok());
+        let mut core_xml = String::new();
+        archive
+            .by_name(CORE_PROPERTIES_PATH)
+            .unwrap()
+            .read_to_string(&mut core_xml)
+            .unwrap();
+        assert!(core_xml.contains("<dc:title>A&amp;B 安全测试报告</dc:title>"));
+        assert!(core_xml.contains("<dc:creator>张&apos;三</dc:creator>"));
+        assert!(core_xml.contains("<dc:subject>安全测试报告</dc:subject>"));
+        assert!(core_xml.contains("<cp:keywords>安全测试报告,TAG,V1.0</cp:keywords>"));
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            WordGenerator::xml_escape("<a href=\"x\">'&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;"
+        );
+        assert_eq!(
+            WordGenerator::xml_escape("行1\r\n\t行2\u{0}\u{8}\u{1B}\u{FFFF}"),
+            "行1\r\n\t行2"
+        );
+    }
+
+    #[test]
+    fn test_pack_to_bytes_is_valid_docx() {
+        let config = ReportConfig {
+            output_dir: "/不存在的目录/不应被创建".to_string(),
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(vec![group("SQL注入", "高危", 2)]);
+
+        let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+        let bytes = WordGenerator::pack_to_bytes(doc, &config).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes.clone())).unwrap();
+        assert!(archive.by_name(CORE_PROPERTIES_PATH).is_ok());
+        assert!(document_xml(bytes).contains("SQL注入"));
+        assert!(!Path::new(&config.output_dir).exists());
+    }
+
+    #[test]
+    fn test_table_borders_follow_document_style() {
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            record_details_table: true,
+            ..ReportConfig::default()
+        };
+        config.document_style.border_color = "808080".to_string();
+        config.document_style.border_size = 8;
+        let mut sql = group("SQL注入", "高危", 1);
+        sql.1.records = vec![record_of(&[("I", "root/src/db.rs"), ("J", "query(sql);")])];
+        let result = ExcelProcessResult::from_groups(vec![sql]);
+
+        let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+        let document = document_xml(WordGenerator::pack_to_bytes(doc, &config).unwrap());
+
+        // 统计表格、章节表格、缺陷明细表格都使用配置的边框
+        let tables = document.matches("<w:tbl>").count();
+        let border = r#"<w:insideV w:val="single" w:sz="8" w:space="0" w:color="808080" />"#;
+        assert_eq!(tables, 3);
+        assert_eq!(document.matches(border).count(), tables);
+    }
+
+    #[test]
+    fn test_section_table_spanned_cells_have_widths() {
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            extra_fields: vec![("CWE编号".to_string(), "P".to_string())],
+            ..ReportConfig::default()
+        };
+        let mut xss = group("XSS", "中危", 1);
+        xss.1.records = vec![record_of(&[("P", "CWE-79")])];
+        let result = ExcelProcessResult::from_groups(vec![xss]);
+        let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+        let bytes = WordGenerator::pack_to_bytes(doc, &config).unwrap();
+
+        // 重新读取生成的文档，找到章节表格
+        let reopened = docx_rs::read_docx(&bytes).unwrap();
+        let table = reopened
+            .document
+            .children
+            .iter()
+            .find_map(|child| match child {
+                DocumentChild::Table(table) if table.grid == SECTION_TABLE_GRID => Some(table),
+                _ => None,
+            })
+            .unwrap();
+        let rows: Vec<Vec<(serde_json::Value, serde_json::Value)>> = table
+            .rows
+            .iter()
+            .map(|TableChild::TableRow(row)| {
+                row.cells
+                    .iter()
+                    .map(|TableRowChild::TableCell(cell)| {
+                        let property = serde_json::to_value(&cell.property).unwrap();
+                        (
+                            property["gridSpan"].clone(),
+                            property["width"]["width"].clone(),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // 前两行四列各占一列，其余行（含额外字段）为标签 + 跨3列的内容
+        assert_eq!(rows.len(), 8);
+        for row in &rows[..2] {
+            let widths: Vec<&serde_json::Value> = row.iter().map(|(_, width)| width).collect();
+            assert_eq!(widths, [1800, 2800, 1800, 2800]);
+            assert!(row.iter().all(|(span, _)| span.is_null()));
+        }
+        for row in &rows[2..] {
+            assert_eq!(row.len(), 2);
+            assert_eq!(row[0], (serde_json::Value::Null, 1800.into()));
+            assert_eq!(row[1], (3.into(), 7400.into()));
+        }
+    }
+
+    #[test]
+    fn test_appendix_appended_after_findings() {
+        let dir = std::env::temp_dir().join("report_forge_appendix_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let appendix = dir.join("appendix.md");
+        std::fs::write(
+            &appendix,
+            "# 术语表\r\n\r\nXSS：跨站脚本\nSQL注入：拼接SQL语句\n",
+        )
+        .unwrap();
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            appendix_file: Some(appendix.display().to_string()),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(vec![group("XSS", "中危", 1)]);
+        let document = |config: &ReportConfig| {
+            let doc = WordGenerator::build_report(config, &result, |_, _| {}).unwrap();
+            document_xml(WordGenerator::pack_to_bytes(doc, config).unwrap())
+        };
+
+        let with_appendix = document(&config);
+        let glossary = with_appendix.find("术语表").unwrap();
+        assert!(glossary > with_appendix.rfind("1、XSS").unwrap());
+        assert!(!with_appendix.contains("# 术语表"));
+        // 标题行使用标题样式而不是加粗文字
+        let heading = with_appendix[..glossary].rfind("</w:p>").unwrap();
+        assert!(with_appendix[heading..glossary].contains("<w:pStyle w:val=\"Heading1\" />"));
+        assert!(with_appendix.contains("SQL注入：拼接SQL语句"));
+
+        // 附录文件不存在时跳过
+        config.appendix_file = Some("/不存在/附录.md".to_string());
+        assert!(!document(&config).contains("术语表"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_delta_report_lists_only_added_groups() {
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+        let diff = ResultDiff {
+            added_groups: vec![group("路径遍历", "高危", 2)],
+            removed_groups: vec![group("XSS", "中危", 3)],
+            count_changes: vec![
+                GroupCountChange {
+                    group_key: "SQL注入|高危".to_string(),
+                    old_count: 1,
+                    new_count: 4,
+                },
+                GroupCountChange {
+                    group_key: "CSRF|低危".to_string(),
+                    old_count: 5,
+                    new_count: 2,
+                },
+            ],
+        };
+        let document = |config: &ReportConfig| {
+            let doc = WordGenerator::build_delta_report(config, &diff, |_, _| {}).unwrap();
+            document_xml(WordGenerator::pack_to_bytes(doc, config).unwrap())
+        };
+
+        let delta = document(&config);
+        assert!(delta.contains("变化报告（仅列出相比上次测试新增的问题）"));
+        assert!(delta.contains("新增问题：1"));
+        assert!(delta.contains("已修复问题：1"));
+        assert!(delta.contains("1、路径遍历"));
+        assert!(!delta.contains("XSS"));
+
+        // 列出数量增加的问题（数量减少的不列出），位于章节之前
+        assert!(delta.contains("数量增加的问题：1"));
+        let increased = delta.find("本次问题数量").unwrap();
+        assert!(increased < delta.find("1、路径遍历").unwrap());
+        assert!(delta.contains("SQL注入"));
+        assert!(!delta.contains("CSRF"));
+
+        // 附带已修复问题表格，位于统计表格之后、章节之前
+        config.delta_include_resolved = true;
+        let delta = document(&config);
+        let resolved = delta.find("上次问题数量").unwrap();
+        assert!(resolved > delta.find("问题统计表格").unwrap());
+        assert!(resolved < delta.find("1、路径遍历").unwrap());
+        assert!(delta.contains("XSS"));
+    }
+
+    #[test]
+    fn test_statistics_rows_link_to_section_bookmarks() {
+        let dir = std::env::temp_dir().join("report_forge_section_links_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = ReportConfig {
+            output_dir: dir.display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+        let grouped_data = vec![group("SQL注入", "高危", 2), group("XSS", "中危", 1)];
+        let result = ExcelProcessResult::from_groups(grouped_data);
+
+        let output_file = WordGenerator::generate_report(&config, &result, |_, _| {}).unwrap();
+
+        // 重新打开文件，每个链接都指向存在的书签
+        let document = document_xml(std::fs::read(output_file).unwrap());
+        let captures = |pattern: &str| -> Vec<String> {
+            regex::Regex::new(pattern)
+                .unwrap()
+                .captures_iter(&document)
+                .map(|c| c[1].to_string())
+                .collect()
+        };
+        let anchors = captures(r#"<w:hyperlink[^>]*w:anchor="([^"]+)""#);
+        let bookmarks = captures(r#"<w:bookmarkStart[^>]*w:name="([^"]+)""#);
+
+        assert_eq!(anchors, ["sec_TAG0001", "sec_TAG0002"]);
+        assert_eq!(bookmarks, anchors);
+    }
+
+    #[test]
+    fn test_section_links_after_filtering_and_appending() {
+        let baseline = ExcelProcessResult::from_groups(vec![
+            group("日志泄露", "低危", 1),
+            group("SQL注入", "高危", 2),
+        ]);
+        let current = ExcelProcessResult::from_groups(vec![
+            group("SQL注入", "高危", 2),
+            group("XSS", "中危", 1),
+            group("日志泄露", "低危", 1),
+        ]);
+        let result = ExcelProcessor::append_new_groups(&baseline, &current).unwrap();
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            severity_filter: Some(vec![RiskLevel::High, RiskLevel::Medium]),
+            tag_by_severity: HashMap::from([(RiskLevel::Medium, "M-".to_string())]),
+            ..ReportConfig::default()
+        };
+
+        let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+        let document = document_xml(WordGenerator::pack_to_bytes(doc, &config).unwrap());
+        let captures = |pattern: &str| -> Vec<String> {
+            regex::Regex::new(pattern)
+                .unwrap()
+                .captures_iter(&document)
+                .map(|c| c[1].to_string())
+                .collect()
+        };
+        let anchors = captures(r#"<w:hyperlink[^>]*w:anchor="([^"]+)""#);
+        let bookmarks = captures(r#"<w:bookmarkStart[^>]*w:name="([^"]+)""#);
+
+        // 低危分组被过滤，链接与书签都按问题报告编号对应
+        assert_eq!(anchors, ["sec_TAG0001", "sec_M_2D0001"]);
+        assert_eq!(bookmarks, anchors);
+        assert!(document.contains("M-0001"));
+    }
+
+    #[test]
+    fn test_section_bookmark_names() {
+        assert_eq!(WordGenerator::section_bookmark("TAG0001"), "sec_TAG0001");
+        assert_eq!(WordGenerator::section_bookmark("H-01"), "sec_H_2D01");
+        let long = WordGenerator::section_bookmark(&"缺陷".repeat(10));
+        assert!(long.len() <= MAX_BOOKMARK_NAME_LEN);
+        assert_ne!(long, WordGenerator::section_bookmark(&"缺陷".repeat(11)));
+    }
+
+    #[test]
+    fn test_build_report_without_writing() {
+        let config = ReportConfig {
+            output_dir: "/不存在的目录/不应被创建".to_string(),
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(vec![
+            group("SQL注入", "高危", 2),
+            group("XSS", "中危", 1),
+        ]);
+
+        let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+
+        // 统计表格 + 每个章节一个表格
+        let tables = doc
+            .document
+            .children
+            .iter()
+            .filter(|child| matches!(child, DocumentChild::Table(_)))
+            .count();
+        assert_eq!(tables, 3);
+        assert!(!Path::new(&config.output_dir).exists());
+    }
+
+    #[test]
+    fn test_estimate_report() {
+        let record = |path: &str, code: &str| record_of(&[("I", path), ("J", code)]);
+        let mut sql = group("SQL注入", "高危", 2);
+        sql.1.records = vec![
+            record("/src/a.c", "query(a);"),
+            record("/src/b.c", "query(b);"),
+        ];
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(vec![sql, group("XSS", "中危", 1)]);
+
+        let estimate = WordGenerator::estimate_report(&config, &result).unwrap();
+        assert_eq!(estimate.section_count, 2);
+        assert_eq!(estimate.total_records, 3);
+        assert!(estimate.code_chars > "query(a);query(b);".len());
+        assert!(estimate.path_chars > "/src/a.c/src/b.c".len());
+        assert_eq!(estimate.image_bytes, 0);
+        assert!(estimate.estimated_bytes > ESTIMATE_BASE_BYTES + 2 * ESTIMATE_SECTION_BYTES);
+        assert!(estimate.warning.is_none());
+
+        let grouped_data: Vec<_> = (0..LARGE_REPORT_SECTIONS)
+            .map(|i| group(&format!("问题{}", i), "低危", 1))
+            .collect();
+        let large = ExcelProcessResult {
+            total_groups: grouped_data.len(),
+            total_records: grouped_data.len(),
+            grouped_data,
+            ..result
+        };
+        let estimate = WordGenerator::estimate_report(&config, &large).unwrap();
+        assert_eq!(estimate.section_count, LARGE_REPORT_SECTIONS);
+        assert!(estimate.warning.unwrap().contains("500 个章节"));
+    }
+
+    #[test]
+    fn test_record_details_table_is_optional() {
+        let record = |path: &str, code: &str| record_of(&[("I", path), ("J", code)]);
+        let (key, mut info) = group("SQL注入", "高危", 2);
+        info.records = vec![
+            record("root/src/db.c", "query(sql);"),
+            record("root/src/api.c", "exec(sql);"),
+        ];
+        let result = ExcelProcessResult::from_groups(vec![(key, info)]);
+        let table_count = |config: &ReportConfig| {
+            WordGenerator::build_report(config, &result, |_, _| {})
+                .unwrap()
+                .document
+                .children
+                .iter()
+                .filter(|child| matches!(child, DocumentChild::Table(_)))
+                .count()
+        };
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+
+        assert_eq!(table_count(&config), 2);
+
+        config.record_details_table = true;
+        assert_eq!(table_count(&config), 3);
+
+        let (key, info) = &result.grouped_data[0];
+        let section =
+            WordGenerator::build_section(&config, key, info, 1, "TAG0001".to_string()).unwrap();
+        let details: Vec<(&str, &str)> = section
+            .record_details
+            .iter()
+            .map(|detail| (detail.path.as_str(), detail.code.as_str()))
+            .collect();
+        assert_eq!(
+            details,
+            vec![("/src/db.c", "query(sql);"), ("/src/api.c", "exec(sql);")]
+        );
+    }
+
+    #[test]
+    fn test_statistics_column_widths_override() {
+        let mut config = ReportConfig::default();
+        assert_eq!(
+            WordGenerator::statistics_column_widths(&config),
+            DEFAULT_STATISTICS_COLUMN_WIDTHS.to_vec()
+        );
+
+        config.statistics_column_widths = Some(vec![600, 1800, 4000, 1200, 800, 800]);
+        assert_eq!(
+            WordGenerator::statistics_column_widths(&config),
+            vec![600, 1800, 4000, 1200, 800, 800]
+        );
+
+        // 数量不一致时使用默认列宽
+        config.statistics_column_widths = Some(vec![600, 1800]);
+        assert_eq!(
+            WordGenerator::statistics_column_widths(&config),
+            DEFAULT_STATISTICS_COLUMN_WIDTHS.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_no_findings_report_or_error() {
+        let result = ExcelProcessResult::from_groups(Vec::new());
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+
+        // 默认生成说明页，不包含统计表格
+        let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+        assert!(!doc
+            .document
+            .children
+            .iter()
+            .any(|child| matches!(child, DocumentChild::Table(_))));
+
+        config.no_findings_mode = NoFindingsMode::Error;
+        let error = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ReportError>(),
+            Some(ReportError::NoFindings)
+        ));
+    }
+
+    #[test]
+    fn test_generate_report_reports_section_progress() {
+        let dir = std::env::temp_dir().join("report_forge_progress_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = ReportConfig {
+            output_dir: dir.display().to_string(),
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(vec![
+            group("SQL注入", "高危", 2),
+            group("XSS", "中危", 1),
+        ]);
+
+        let mut progress = Vec::new();
+        WordGenerator::generate_report(&config, &result, |current, total| {
+            progress.push((current, total))
+        })
+        .unwrap();
+
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_create_output_file_bumps_counter() {
+        let dir = std::env::temp_dir().join("report_forge_output_file_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let output_dir = dir.display().to_string();
+
+        let (first, _) = WordGenerator::create_output_file(&output_dir, "report", "docx").unwrap();
+        let (second, _) = WordGenerator::create_output_file(&output_dir, "report", "docx").unwrap();
+
+        assert!(first.ends_with("report.docx"));
+        assert!(second.ends_with("report_1.docx"));
+    }
+
+    #[test]
+    fn test_write_output_file_leaves_no_temp_file() {
+        let dir = std::env::temp_dir().join("report_forge_write_output_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let output_dir = dir.display().to_string();
+
+        let first = WordGenerator::write_output_file(&output_dir, "report", "md", b"a").unwrap();
+        let second = WordGenerator::write_output_file(&output_dir, "report", "md", b"b").unwrap();
+
+        assert_eq!(std::fs::read(&first).unwrap(), b"a");
+        assert_eq!(std::fs::read(&second).unwrap(), b"b");
+        let mut names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["report.md", "report_1.md"]);
+    }
+
+    #[test]
+    fn test_preview_sections_extracts_section_fields() {
+        let (key, mut info) = group("SQL注入", "高危", 1);
+        info.records = vec![record_of(&[
+            ("B", "SQL注入"),
+            ("D", "高危"),
+            ("I", "/src/db.c"),
+            ("J", "query(sql);"),
+            ("K", "拼接SQL语句"),
+            ("N", "使用参数化查询"),
+        ])];
+        let result = ExcelProcessResult::from_groups(vec![(key, info)]);
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            ..ReportConfig::default()
+        };
+
+        let sections = WordGenerator::preview_sections(&config, &result).unwrap();
+
+        assert_eq!(sections.len(), 1);
+        let section = &sections[0];
+        assert_eq!(section.report_number, "TAG0001");
+        assert_eq!(section.title, "1、SQL注入");
+        assert_eq!(section.risk_text, RiskLevel::High.text(Language::Zh));
+        assert_eq!(section.phenomenon, "SQL注入");
+        assert_eq!(section.code_path, "缺陷1文件路径：\n/src/db.c");
+        assert_eq!(section.code, "缺陷1相关代码如下：\nquery(sql);");
+        assert_eq!(section.vulnerability, "拼接SQL语句");
+        assert_eq!(section.suggestion, "使用参数化查询");
+    }
+
+    #[test]
+    fn test_build_section_raw_severity() {
+        let (key, mut info) = group("SQL注入", "高危 - CVSS 8.1", 1);
+        let mut config = ReportConfig::default();
+
+        let section = WordGenerator::build_section(&config, &key, &info, 1, String::new()).unwrap();
+        assert_eq!(section.raw_severity, None);
+
+        config.show_raw_severity = true;
+        let section = WordGenerator::build_section(&config, &key, &info, 1, String::new()).unwrap();
+        assert_eq!(section.risk_level, RiskLevel::High);
+        assert_eq!(section.raw_severity.as_deref(), Some("高危 - CVSS 8.1"));
+
+        // 风险升级后显示升级前的原文
+        info.d_column = "高危".to_string();
+        info.original_severity = Some("中危".to_string());
+        let section = WordGenerator::build_section(&config, &key, &info, 1, String::new()).unwrap();
+        assert_eq!(section.raw_severity.as_deref(), Some("中危"));
+
+        // 按CVSS评分识别的分组列出记录中的原始评分
+        info.records = ["9.8", "7.5", "9.8"]
+            .iter()
+            .map(|score| record_of(&[("D", score)]))
+            .collect();
+        let section = WordGenerator::build_section(&config, &key, &info, 1, String::new()).unwrap();
+        assert_eq!(section.raw_severity.as_deref(), Some("9.8 / 7.5"));
+    }
+
+    #[test]
+    fn test_build_section_extra_fields() {
+        let record = |cwe: &str| record_of(&[("B", "SQL注入"), ("P", cwe), ("Q", "")]);
+        let (key, mut info) = group("SQL注入", "高危", 2);
+        info.records = vec![record("CWE-89"), record("CWE-564")];
+        let mut config = ReportConfig {
+            extra_fields: vec![
+                ("CWE编号".to_string(), "P".to_string()),
+                ("首次发现时间".to_string(), "Z".to_string()),
+                ("备注".to_string(), "Q".to_string()),
+            ],
+            ..ReportConfig::default()
+        };
+
+        // 来源列不存在的字段不添加，列存在但为空时保留空行
+        let section = WordGenerator::build_section(&config, &key, &info, 1, String::new()).unwrap();
+        assert_eq!(
+            section.extra_fields,
+            vec![
+                ("CWE编号".to_string(), "CWE-89".to_string()),
+                ("备注".to_string(), String::new()),
+            ]
+        );
+
+        config.merge_record_fields = true;
+        let section = WordGenerator::build_section(&config, &key, &info, 1, String::new()).unwrap();
+        assert_eq!(section.extra_fields[0].1, "CWE-89\nCWE-564");
+    }
+
+    #[test]
+    fn test_build_section_with_missing_fields() {
+        let (key, info) = group("XSS", "中危", 0);
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            wt_add: 4,
+            ..ReportConfig::default()
+        };
+
+        let report_number = config.report_number(3).unwrap();
+        let section = WordGenerator::build_section(&config, &key, &info, 3, report_number).unwrap();
+
+        assert_eq!(section.report_number, "TAG0007");
+        assert_eq!(section.title, "3、XSS");
+        assert_eq!(section.risk_level, RiskLevel::Medium);
+        assert!(section.phenomenon.is_empty());
+        assert!(section.code.is_empty());
+        assert!(section.code_path.is_empty());
+        assert!(section.vulnerability.is_empty());
+        assert!(section.suggestion.is_empty());
+    }
+
+    #[test]
+    fn test_path_and_code_columns_are_configurable() {
+        let records = vec![record_of(&[("P", "root/src/a.c"), ("Q", "gets(buf);")])];
+
+        let mut config = ReportConfig {
+            strip_path_prefix: None,
+            ..ReportConfig::default()
+        };
+        config.column_mapping.path_column = "P".to_string();
+        assert_eq!(
+            WordGenerator::generate_path_text(&records, &config).unwrap(),
+            "缺陷1文件路径：\nroot/src/a.c"
+        );
+        assert_eq!(
+            WordGenerator::generate_code_text(&records, "Q", None, None, &Labels::ZH),
+            "缺陷1相关代码如下：\ngets(buf);"
+        );
+        config.column_mapping.path_column = "I".to_string();
+        assert_eq!(
+            WordGenerator::generate_path_text(&records, &config).unwrap(),
+            ""
+        );
+        assert_eq!(
+            WordGenerator::generate_code_text(&records, "J", None, None, &Labels::ZH),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_max_snippets_per_group_truncates_display_only() {
+        let records: Vec<_> = (1..=5)
+            .map(|i| {
+                let (path, code) = (format!("src/{}.c", i), format!("call{}();", i));
+                record_of(&[("I", &path), ("J", &code)])
+            })
+            .collect();
+        let (key, mut info) = group("XSS", "中危", records.len());
+        info.records = records;
+        let result = ExcelProcessResult::from_groups(vec![(key.clone(), info.clone())]);
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            strip_path_prefix: None,
+            max_snippets_per_group: Some(2),
+            ..ReportConfig::default()
+        };
+
+        let section =
+            WordGenerator::build_section(&config, &key, &info, 1, "TAG0001".to_string()).unwrap();
+
+        assert_eq!(
+            section.code,
+            "缺陷1相关代码如下：\ncall1();\n缺陷2相关代码如下：\ncall2();\n…（其余 3 处省略）"
+        );
+        assert_eq!(
+            section.code_path,
+            "缺陷1文件路径：\nsrc/1.c\n缺陷2文件路径：\nsrc/2.c\n…（其余 3 处省略）"
+        );
+        // 统计表格仍为实际总数
+        let statistics = WordGenerator::generate_statistics(&result, &config).unwrap();
+        assert_eq!(statistics[0].problem_count, 5);
+    }
+
+    #[test]
+    fn test_path_label_template_and_list_style() {
+        let records: Vec<ExcelRecord> = ["/src/a.c", "/src/b.c"]
+            .iter()
+            .map(|path| record_of(&[("I", path)]))
+            .collect();
+        let mut config = ReportConfig {
+            path_label_template: String::new(),
+            path_list_style: PathListStyle::Bulleted,
+            ..ReportConfig::default()
+        };
+        assert_eq!(
+            WordGenerator::generate_path_text(&records, &config).unwrap(),
+            "• /src/a.c\n• /src/b.c"
+        );
+
+        config.path_list_style = PathListStyle::Numbered;
+        assert_eq!(
+            WordGenerator::generate_path_text(&records, &config).unwrap(),
+            "1. /src/a.c\n2. /src/b.c"
+        );
+
+        config.path_label_template = "Finding {seq}:".to_string();
+        config.path_list_style = PathListStyle::Plain;
+        assert_eq!(
+            WordGenerator::generate_path_text(&records, &config).unwrap(),
+            "Finding 1:\n/src/a.c\nFinding 2:\n/src/b.c"
+        );
+
+        config.path_label_template = "{path}".to_string();
+        assert!(WordGenerator::generate_path_text(&records, &config).is_err());
+    }
+
+    #[test]
+    fn test_word_path_list_uses_numbering() {
+        let records: Vec<_> = [
+            ("src/a.c", "gets(buf);"),
+            ("src/b.c", "gets(buf);"),
+            ("src/c.c", "strcpy(d, s);"),
+        ]
+        .into_iter()
+        .map(|(path, code)| record_of(&[("I", path), ("J", code)]))
+        .collect();
+        let (key, mut info) = group("缓冲区溢出", "高危", records.len());
+        info.records = records;
+        let result = ExcelProcessResult::from_groups(vec![(key, info)]);
+        let mut config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            strip_path_prefix: None,
+            collapse_identical_code: true,
+            path_list_style: PathListStyle::Numbered,
+            ..ReportConfig::default()
+        };
+        let document = |config: &ReportConfig| {
+            let doc = WordGenerator::build_report(config, &result, |_, _| {}).unwrap();
+            document_xml(WordGenerator::pack_to_bytes(doc, config).unwrap())
+        };
+
+        // 每段代码的路径单独编号，编号不以文字写入
+        let numbered = document(&config);
+        assert!(numbered.contains(">src/a.c<"));
+        assert!(!numbered.contains("1. src/a.c"));
+        assert_eq!(numbered.matches("<w:numId w:val=\"2\" />").count(), 2);
+        assert_eq!(numbered.matches("<w:numId w:val=\"3\" />").count(), 1);
+
+        config.path_list_style = PathListStyle::Plain;
+        assert!(!document(&config).contains("<w:numPr>"));
+    }
+
+    #[test]
+    fn test_collapse_identical_code_groups_paths() {
+        let records: Vec<_> = [
+            ("src/a.c", "gets(buf);"),
+            ("src/b.c", "gets(buf);"),
+            ("src/c.c", "gets(buf);"),
+        ]
+        .into_iter()
+        .map(|(path, code)| record_of(&[("I", path), ("J", code)]))
+        .collect();
+        let (key, mut info) = group("缓冲区溢出", "高危", records.len());
+        info.records = records;
+        let mut config = ReportConfig {
+            strip_path_prefix: None,
+            ..ReportConfig::default()
+        };
+
+        // 默认逐条记录列出
+        let section =
+            WordGenerator::build_section(&config, &key, &info, 1, "TAG0001".to_string()).unwrap();
+        assert_eq!(section.code.matches("gets(buf);").count(), 3);
+
+        config.collapse_identical_code = true;
+        let section =
+            WordGenerator::build_section(&config, &key, &info, 1, "TAG0001".to_string()).unwrap();
+        assert_eq!(section.code, "缺陷代码1（共 3 处）如下：\ngets(buf);");
+        assert_eq!(
+            section.code_path,
+            "缺陷代码1文件路径：\nsrc/a.c\nsrc/b.c\nsrc/c.c"
+        );
+    }
+
+    #[test]
+    fn test_empty_field_placeholder() {
+        let (key, mut info) = group("空指针", "中危", 1);
+        info.records = vec![record_of(&[("B", "空指针"), ("I", "src/a.c"), ("K", "  ")])];
+        let mut config = ReportConfig {
+            strip_path_prefix: None,
+            ..ReportConfig::default()
+        };
+
+        // 默认保持空白
+        let section =
+            WordGenerator::build_section(&config, &key, &info, 1, "TAG0001".to_string()).unwrap();
+        assert_eq!(section.suggestion, "");
+
+        config.empty_field_placeholder = Some("N/A".to_string());
+        let section =
+            WordGenerator::build_section(&config, &key, &info, 1, "TAG0001".to_string()).unwrap();
+        assert_eq!(section.phenomenon, "空指针");
+        assert_eq!(section.code_path, "缺陷1文件路径：\nsrc/a.c");
+        assert_eq!(section.code, "N/A"); // 相关代码列不存在
+        assert_eq!(section.vulnerability, "N/A"); // 仅含空白
+        assert_eq!(section.suggestion, "N/A"); // 列不存在
+    }
+
+    #[test]
+    fn test_empty_field_placeholder_for_blank_code_and_path() {
+        let (key, mut info) = group("空指针", "中危", 2);
+        info.records = vec![
+            record_of(&[("B", "空指针"), ("I", "src/a.c"), ("J", " ")]),
+            record_of(&[("B", "空指针"), ("I", ""), ("J", "free(p);")]),
+        ];
+        let mut config = ReportConfig {
+            strip_path_prefix: None,
+            empty_field_placeholder: Some("无".to_string()),
+            ..ReportConfig::default()
+        };
+
+        // 列存在但单条记录为空时，逐条替换为占位文本
+        let section =
+            WordGenerator::build_section(&config, &key, &info, 1, "TAG0001".to_string()).unwrap();
+        assert_eq!(
+            section.code,
+            "缺陷1相关代码如下：\n无\n缺陷2相关代码如下：\nfree(p);"
+        );
+        assert_eq!(
+            section.code_path,
+            "缺陷1文件路径：\nsrc/a.c\n缺陷2文件路径：\n无"
+        );
+
+        config.collapse_identical_code = true;
+        let section =
+            WordGenerator::build_section(&config, &key, &info, 1, "TAG0001".to_string()).unwrap();
+        assert_eq!(
+            section.code,
+            "缺陷代码1（共 1 处）如下：\n无\n缺陷代码2（共 1 处）如下：\nfree(p);"
+        );
+        assert_eq!(
+            section.code_path,
+            "缺陷代码1文件路径：\nsrc/a.c\n缺陷代码2文件路径：\n无"
+        );
+    }
+
+    #[test]
+    fn test_strip_path_prefix_is_anchored() {
+        assert_eq!(
+            WordGenerator::strip_path_prefix("/root/src/a.c", "root"),
+            "/src/a.c"
+        );
+        assert_eq!(
+            WordGenerator::strip_path_prefix("root/src/a.c", "root"),
+            "/src/a.c"
+        );
+        assert_eq!(
+            WordGenerator::strip_path_prefix("rootkit/scan.c", "root"),
+            "rootkit/scan.c"
+        );
+        assert_eq!(
+            WordGenerator::strip_path_prefix("root_config/app.c", "root"),
+            "root_config/app.c"
+        );
+        assert_eq!(
+            WordGenerator::strip_path_prefix("root\\src\\a.c", "root/"),
+            "\\src\\a.c"
+        );
+    }
+
+    #[test]
+    fn test_record_field_text_merges_distinct_values() {
+        let records: Vec<ExcelRecord> = ["使用参数化查询", "过滤输入", "使用参数化查询"]
+            .iter()
+            .map(|suggestion| record_of(&[("N", suggestion)]))
+            .collect();
+
+        assert_eq!(
+            WordGenerator::record_field_text(&records, "N", false),
+            "使用参数化查询"
+        );
+        assert_eq!(
+            WordGenerator::record_field_text(&records, "N", true),
+            "使用参数化查询\n过滤输入"
+        );
+    }
+
+    #[test]
+    fn test_representative_selector_changes_suggestion() {
+        let records: Vec<ExcelRecord> = [
+            ("高危", "N/A", "说明", "升级组件"),
+            ("中危", "10", "短说明", "过滤输入"),
+            ("9.1", "9.1", "较长的漏洞说明", "使用参数化查询"),
+            ("9.8", "9", "另一条较长说明", "最小权限"),
+        ]
+        .iter()
+        .map(|(severity, score, vulnerability, suggestion)| {
+            record_of(&[
+                ("D", severity),
+                ("E", score),
+                ("K", vulnerability),
+                ("N", suggestion),
+            ])
+        })
+        .collect();
+        let (key, mut info) = group("SQL注入", "高危", records.len());
+        info.records = records;
+        let config = |selector: RepresentativeSelector| ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            representative_selector: selector,
+            process: ProcessOptions {
+                severity_from_cvss: true,
+                ..ProcessOptions::default()
+            },
+            ..ReportConfig::default()
+        };
+        let suggestion = |info: &GroupInfo, selector: RepresentativeSelector| {
+            WordGenerator::build_section(&config(selector), &key, info, 1, "TAG0001".to_string())
+                .unwrap()
+                .suggestion
+        };
+
+        assert_eq!(
+            suggestion(&info, RepresentativeSelector::FirstSeen),
+            "升级组件"
+        );
+        // 长度相同时取先出现的记录
+        assert_eq!(
+            suggestion(&info, RepresentativeSelector::LongestDescription),
+            "使用参数化查询"
+        );
+        // “高危”、9.1、9.8 同为高危：数字高于文本，数字之间比较评分，与记录顺序无关
+        assert_eq!(
+            suggestion(&info, RepresentativeSelector::HighestSeverity),
+            "最小权限"
+        );
+        let mut reversed = info.clone();
+        reversed.records.reverse();
+        assert_eq!(
+            suggestion(&reversed, RepresentativeSelector::HighestSeverity),
+            "最小权限"
+        );
+        // 按数值比较（10 > 9.1 > 9），而不是按文本比较；文本 “N/A” 低于数字
+        assert_eq!(
+            suggestion(&info, RepresentativeSelector::MaxColumn("E".to_string())),
+            "过滤输入"
+        );
+    }
+
+    #[test]
+    fn test_value_order_is_total() {
+        let mut values = vec!["高危", "9.8", "N/A", "10", "9.1", "", "高危"];
+        values.sort_by(|a, b| WordGenerator::value_order(a, b));
+        assert_eq!(values, vec!["", "N/A", "高危", "高危", "9.1", "9.8", "10"]);
+    }
+
+    #[test]
+    fn test_image_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&800u32.to_be_bytes());
+        png.extend_from_slice(&600u32.to_be_bytes());
+        assert_eq!(WordGenerator::image_dimensions(&png), Some((800, 600)));
+
+        let jpeg = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, // APP0
+            0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80, // SOF0: 480x640
+        ];
+        assert_eq!(WordGenerator::image_dimensions(&jpeg), Some((640, 480)));
+
+        assert_eq!(WordGenerator::image_dimensions(b"GIF89a"), None);
+    }
+
+    #[test]
+    fn test_watermark_header_only_when_configured() {
+        assert!(WordGenerator::create_watermark_header(Some("机密")).is_some());
+        assert!(WordGenerator::create_watermark_header(Some("  ")).is_none());
+        assert!(WordGenerator::create_watermark_header(None).is_none());
+    }
+
+    #[test]
+    fn test_watermark_is_rotated_shape_behind_text() {
+        let config = ReportConfig {
+            identifier_tag: "TAG".to_string(),
+            watermark: Some("机密<内部>".to_string()),
+            ..ReportConfig::default()
+        };
+        let result = ExcelProcessResult::from_groups(vec![group("XSS", "中危", 1)]);
+        let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+        let packed = WordGenerator::pack_to_bytes(doc, &config).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(packed.clone())).unwrap();
+        let mut header = String::new();
+        archive
+            .by_name("word/header1.xml")
+            .unwrap()
+            .read_to_string(&mut header)
+            .unwrap();
+        let shape = header.find("<v:shape id=").unwrap();
+        assert!(shape < header.find("</w:p>").unwrap());
+        assert!(header.contains("rotation:315;z-index:-"));
+        assert!(header.contains(r#"string="机密&lt;内部&gt;""#));
+        assert!(header.contains(r#"<v:textpath style="font-family:&quot;宋体&quot;"#));
+        // 水印不出现在正文中
+        assert!(!document_xml(packed).contains("机密"));
+    }
+
+    #[test]
+    fn test_parse_footer_template() {
+        assert_eq!(
+            WordGenerator::parse_footer_template("第 {page} 页 / 共 {total} 页"),
+            vec![
+                FooterPart::Text("第 ".to_string()),
+                FooterPart::Page,
+                FooterPart::Text(" 页 / 共 ".to_string()),
+                FooterPart::TotalPages,
+                FooterPart::Text(" 页".to_string()),
+            ]
+        );
+        assert_eq!(
+            WordGenerator::parse_footer_template("Page {page} of {total}"),
+            vec![
+                FooterPart::Text("Page ".to_string()),
+                FooterPart::Page,
+                FooterPart::Text(" of ".to_string()),
+                FooterPart::TotalPages,
+            ]
+        );
+    }
+}
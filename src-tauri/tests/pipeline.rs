@@ -0,0 +1,221 @@
+//! Excel → Word 全流程测试（读取、去重、分组、生成文档）
+
+use report_forge_lib::models::{FileErrorPolicy, ProcessOptions, ReportConfig};
+use report_forge_lib::processors::{ExcelProcessor, WordGenerator};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 测试用问题列表：6条不同的记录（3个分组）及1条与第1行完全相同的重复记录
+fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.xlsx")
+}
+
+/// 与 findings.xlsx 内容相同的CSV文件（UTF-8，含双引号转义的字段）
+fn csv_fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.csv")
+}
+
+/// 与 findings.xlsx 内容相同的OpenDocument表格
+fn ods_fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/findings.ods")
+}
+
+/// 相关代码列为富文本（多个格式片段）的问题列表：共享字符串与内联字符串各一条
+fn rich_text_fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rich_text.xlsx")
+}
+
+/// 读取docx中第一个表格（统计表格）的行数
+fn first_table_row_count(docx: &Path) -> usize {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(docx).unwrap()).unwrap();
+    let mut document = String::new();
+    archive
+        .by_name("word/document.xml")
+        .unwrap()
+        .read_to_string(&mut document)
+        .unwrap();
+
+    let start = document.find("<w:tbl>").expect("文档中没有表格");
+    let end = start + document[start..].find("</w:tbl>").unwrap();
+    let table = &document[start..end];
+    table.matches("<w:tr>").count() + table.matches("<w:tr ").count()
+}
+
+#[test]
+fn test_excel_to_docx_pipeline() {
+    let result =
+        ExcelProcessor::process_excel_to_json(fixture(), &ProcessOptions::default()).unwrap();
+
+    assert_eq!(result.total_records, 6);
+    assert_eq!(result.total_groups, 3);
+    assert_eq!(result.removed_duplicates.len(), 1);
+    let groups: Vec<(&str, &str, usize)> = result
+        .grouped_data
+        .iter()
+        .map(|(_, info)| {
+            (
+                info.b_column.as_str(),
+                info.d_column.as_str(),
+                info.record_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        vec![
+            ("SQL注入", "高危", 3),
+            ("跨站脚本", "中危", 2),
+            ("日志泄露", "低危", 1),
+        ]
+    );
+    // 数字单元格按整数读取
+    let first_record = &result.grouped_data[0].1.records[0];
+    assert_eq!(first_record.data["A"].as_deref(), Some("1"));
+
+    let output_dir = std::env::temp_dir().join("report_forge_pipeline_test");
+    let _ = std::fs::remove_dir_all(&output_dir);
+    let config = ReportConfig {
+        output_dir: output_dir.display().to_string(),
+        identifier_tag: "TAG".to_string(),
+        code_version: "V1.0".to_string(),
+        ceshi_user: "张三".to_string(),
+        ..ReportConfig::default()
+    };
+
+    let doc = WordGenerator::build_report(&config, &result, |_, _| {}).unwrap();
+    let output_file = WordGenerator::write_report(doc, &config).unwrap();
+
+    // 表头 + 3个分组 + 合计
+    assert_eq!(first_table_row_count(Path::new(&output_file)), 5);
+}
+
+#[test]
+fn test_merge_deduplicates_across_files() {
+    let merged =
+        ExcelProcessor::merge_excel_files(&[fixture(), fixture()], &ProcessOptions::default())
+            .unwrap();
+    assert_eq!(merged.rows.len(), 14);
+
+    let result = ExcelProcessor::process_raw_data(merged, &ProcessOptions::default()).unwrap();
+
+    assert_eq!(result.total_records, 6);
+    assert_eq!(result.total_groups, 3);
+    assert_eq!(result.removed_duplicates.len(), 8);
+}
+
+#[test]
+fn test_rich_text_cells_keep_all_runs() {
+    let result =
+        ExcelProcessor::process_excel_to_json(rich_text_fixture(), &ProcessOptions::default())
+            .unwrap();
+
+    let records = &result.grouped_data[0].1.records;
+    assert_eq!(records.len(), 2);
+    // 所有格式片段按顺序拼接，保留空白和换行，不包含注音（rPh）
+    assert_eq!(
+        records[0].data["J"].as_deref(),
+        Some("query(\"SELECT * FROM users WHERE id=\" + id);\n  exec(sql);")
+    );
+    assert_eq!(records[1].data["J"].as_deref(), Some("strcpy(buf, input);"));
+
+    // 流式读取与一次性读取结果一致
+    let streamed = ExcelProcessor::process_excel_files_streaming(
+        &[rich_text_fixture()],
+        &ProcessOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        streamed.grouped_data[0].1.records[0].data["J"],
+        records[0].data["J"]
+    );
+}
+
+#[test]
+fn test_csv_input_matches_xlsx() {
+    let from_xlsx =
+        ExcelProcessor::process_excel_to_json(fixture(), &ProcessOptions::default()).unwrap();
+    let from_csv =
+        ExcelProcessor::process_excel_to_json(csv_fixture(), &ProcessOptions::default()).unwrap();
+
+    assert_eq!(from_csv.total_records, 6);
+    assert_eq!(from_csv.total_groups, 3);
+    assert_eq!(from_csv.removed_duplicates.len(), 1);
+    for ((csv_key, csv_info), (xlsx_key, xlsx_info)) in
+        from_csv.grouped_data.iter().zip(&from_xlsx.grouped_data)
+    {
+        assert_eq!(csv_key, xlsx_key);
+        let csv_records: Vec<_> = csv_info.records.iter().map(|r| &r.data).collect();
+        let xlsx_records: Vec<_> = xlsx_info.records.iter().map(|r| &r.data).collect();
+        assert_eq!(csv_records, xlsx_records);
+    }
+
+    // CSV与xlsx文件可以一起合并
+    let merged =
+        ExcelProcessor::merge_excel_files(&[fixture(), csv_fixture()], &ProcessOptions::default())
+            .unwrap();
+    assert_eq!(merged.rows.len(), 14);
+}
+
+#[test]
+fn test_inspect_csv_workbook() {
+    let xlsx = ExcelProcessor::inspect_workbook(fixture()).unwrap();
+    let csv = ExcelProcessor::inspect_workbook(csv_fixture()).unwrap();
+
+    // CSV文件为一个以文件名命名的工作表
+    assert_eq!(csv.sheets.len(), 1);
+    assert_eq!(csv.sheets[0].name, "findings");
+    assert_eq!(csv.sheets[0].headers, xlsx.sheets[0].headers);
+    assert_eq!(csv.sheets[0].row_count, xlsx.sheets[0].row_count);
+}
+
+#[test]
+fn test_ods_input_matches_xlsx() {
+    let xlsx = ExcelProcessor::read_excel_raw(fixture()).unwrap();
+    let ods = ExcelProcessor::read_excel_raw(ods_fixture()).unwrap();
+
+    assert_eq!(ods.headers, xlsx.headers);
+    assert_eq!(ods.rows, xlsx.rows);
+
+    let result =
+        ExcelProcessor::process_excel_to_json(ods_fixture(), &ProcessOptions::default()).unwrap();
+    assert_eq!(result.total_records, 6);
+    assert_eq!(result.total_groups, 3);
+}
+
+#[test]
+fn test_inspect_ods_workbook() {
+    let xlsx = ExcelProcessor::inspect_workbook(fixture()).unwrap();
+    let ods = ExcelProcessor::inspect_workbook(ods_fixture()).unwrap();
+
+    assert_eq!(ods.sheets.len(), 1);
+    assert_eq!(ods.sheets[0].headers, xlsx.sheets[0].headers);
+    assert_eq!(ods.sheets[0].row_count, xlsx.sheets[0].row_count);
+}
+
+#[test]
+fn test_skip_unreadable_files() {
+    let missing = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/missing.xlsx");
+    let files = [missing.clone(), fixture()];
+
+    // 默认中止
+    assert!(ExcelProcessor::merge_excel_files(&files, &ProcessOptions::default()).is_err());
+
+    let skip = ProcessOptions {
+        on_file_error: FileErrorPolicy::Skip,
+        ..ProcessOptions::default()
+    };
+    // merge_excel_files 总是中止
+    assert!(ExcelProcessor::merge_excel_files(&files, &skip).is_err());
+    let (merged, skipped) = ExcelProcessor::merge_excel_files_with_policy(&files, &skip).unwrap();
+    assert_eq!(merged.rows.len(), 7);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].file, missing.display().to_string());
+
+    let streamed = ExcelProcessor::process_excel_files_streaming(&files, &skip).unwrap();
+    assert_eq!(streamed.total_records, 6);
+    assert_eq!(streamed.skipped_files, skipped);
+
+    // 全部失败时返回错误
+    let error = ExcelProcessor::merge_excel_files_with_policy(&[missing], &skip).unwrap_err();
+    assert!(error.to_string().contains("所有文件均读取失败"));
+}